@@ -0,0 +1,14 @@
+/// Square root by Newton's method, numbers below zero have none and give zero
+pub fun sqrt(x: f32): f32 {
+	if x <= 0.0 {
+		return 0.0
+	}
+
+	let mut root = x
+	let mut steps = 0
+	while steps < 20 {
+		root = (root + x / root) / 2.0
+		steps += 1
+	}
+	root
+}
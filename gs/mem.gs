@@ -0,0 +1,4 @@
+impl<T> ref[T] {
+	/// The `length` values starting at the referenced one, eg. the arguments of a program
+	pub fun to_array(self, length: usize): [T] => builtin::array(self, length)
+}
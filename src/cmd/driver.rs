@@ -0,0 +1,121 @@
+use std::ffi::OsStr;
+use std::path::{Path, PathBuf};
+use crate::cmd::error::{DriverError, DriverResult};
+use crate::cmd::InputArgs;
+use crate::file::source_file::absolute_path;
+use crate::file::source_map::{FileId, SourceMap};
+use crate::parser::ast::top_level::UnvalidatedTopLevel;
+use crate::parser::expansion::expand_macros;
+use crate::parser::parser::Parser;
+use crate::validation::module::Module;
+use crate::validation::monomorphize::{Instances, Monomorphizer};
+
+/// File extensions accepted as gosling source files
+pub const SOURCE_EXTENSIONS: &[&str] = &["gosling", "gs"];
+
+pub type ParsedModule = (FileId, Vec<UnvalidatedTopLevel>);
+
+/// Directory the module paths of the input files are relative to, the `--root` given on the
+/// command line or else the innermost directory containing every file
+pub fn module_root(input: &InputArgs) -> PathBuf {
+    if let Some(root) = &input.root {
+        return root.clone();
+    }
+
+    let mut directories = input.files
+        .iter()
+        .filter_map(|path| absolute_path(path)?.parent().map(Path::to_path_buf));
+
+    let Some(mut root) = directories.next() else {
+        return PathBuf::from(".");
+    };
+    for directory in directories {
+        while !directory.starts_with(&root) {
+            root.pop();
+        }
+    }
+    root
+}
+
+/// Reads a single source file into the source map, checking that it is a gosling source file
+pub fn load_file(sources: &mut SourceMap, path: &Path, root: &Path) -> Result<FileId, DriverError> {
+    let is_source = path
+        .extension()
+        .and_then(OsStr::to_str)
+        .is_some_and(|ext| SOURCE_EXTENSIONS.contains(&ext));
+
+    if !is_source {
+        return Err(DriverError::UnsupportedExtension(path.to_path_buf()));
+    }
+
+    sources
+        .load(&path.to_string_lossy(), root)
+        .map_err(|err| DriverError::Io(path.to_path_buf(), err))
+}
+
+/// Reads, tokenizes and parses a single source file, then expands the macros it calls
+pub fn parse_file(sources: &mut SourceMap, path: &Path, root: &Path) -> DriverResult<ParsedModule> {
+    let file = load_file(sources, path, root).map_err(|err| vec![err])?;
+
+    // keep parsing after lexer errors, the skipped characters rarely affect the rest of the file
+    let (tokens, lex_errors) = crate::lexer::tokenize(&sources[file]);
+    let (mut statements, mut parse_errors) = Parser::new(file, tokens).parse();
+    parse_errors.extend(expand_macros(&mut statements));
+
+    let errors: Vec<DriverError> = lex_errors
+        .into_iter()
+        .map(DriverError::from)
+        .chain(parse_errors.into_iter().map(DriverError::from))
+        .collect();
+
+    if errors.is_empty() {
+        Ok((file, statements))
+    } else {
+        Err(errors)
+    }
+}
+
+/// Parses every file, reporting the errors of all files instead of stopping at the first
+pub fn parse_files(sources: &mut SourceMap, input: &InputArgs) -> DriverResult<Vec<ParsedModule>> {
+    let root = module_root(input);
+    let mut modules = vec![];
+    let mut errors = vec![];
+
+    for path in &input.files {
+        match parse_file(sources, path, &root) {
+            Ok(module) => modules.push(module),
+            Err(err) => errors.extend(err),
+        }
+    }
+
+    if errors.is_empty() {
+        Ok(modules)
+    } else {
+        Err(errors)
+    }
+}
+
+/// Runs every file through the lexer, parser and validation
+pub fn check(sources: &mut SourceMap, input: &InputArgs) -> DriverResult<Module> {
+    let modules = parse_files(sources, input)?;
+
+    let mut module = Module::new();
+    module
+        .process_modules(sources, &modules)
+        .map_err(|errors| errors.into_iter().map(DriverError::from).collect::<Vec<_>>())?;
+
+    Ok(module)
+}
+
+/// Compiles the given files, there is no code generation yet so this stops once every generic
+/// function and type used by the program is instantiated
+pub fn build(sources: &mut SourceMap, input: &InputArgs) -> DriverResult<Instances> {
+    let module = check(sources, input)?;
+
+    Monomorphizer::run(&module).map_err(|err| vec![DriverError::from(err)])
+}
+
+pub fn run(sources: &mut SourceMap, input: &InputArgs) -> DriverResult<()> {
+    build(sources, input)?;
+    Err(vec![DriverError::MissingBackend])
+}
@@ -0,0 +1,125 @@
+use std::fmt::Debug;
+use std::io::{self, ErrorKind, Write};
+use clap::{Args, ValueEnum};
+use serde::Serialize;
+use serde_json::json;
+use crate::cmd::driver;
+use crate::cmd::error::{DriverError, DriverResult};
+use crate::cmd::InputArgs;
+use crate::file::source_map::SourceMap;
+
+#[derive(Args, Debug)]
+pub struct DumpArgs {
+    /// Compiler stage whose output is printed
+    #[arg(long, value_enum)]
+    pub stage: Stage,
+
+    #[arg(long, value_enum, default_value_t = DumpFormat::Json)]
+    pub format: DumpFormat,
+
+    #[command(flatten)]
+    pub input: InputArgs,
+}
+
+#[derive(ValueEnum, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Stage {
+    /// Output of the lexer
+    Tokens,
+
+    /// Syntax tree produced by the parser
+    Ast,
+
+    /// Module registry produced by validation
+    Validated,
+
+    /// Concrete functions and types produced by monomorphization
+    Instances,
+}
+
+#[derive(ValueEnum, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DumpFormat {
+    Json,
+    Pretty,
+}
+
+fn print<T: Serialize + Debug>(out: &mut impl Write, value: &T, format: DumpFormat) -> io::Result<()> {
+    match format {
+        DumpFormat::Json => writeln!(
+            out,
+            "{}",
+            serde_json::to_string_pretty(value).expect("Compiler stages are always serializable")
+        ),
+        DumpFormat::Pretty => writeln!(out, "{value:#?}"),
+    }
+}
+
+fn output_error(err: io::Error) -> Vec<DriverError> {
+    vec![DriverError::Output(err)]
+}
+
+pub fn dump(sources: &mut SourceMap, args: &DumpArgs) -> DriverResult<()> {
+    let mut out = io::stdout().lock();
+
+    match write_stage(&mut out, sources, args).and_then(|()| out.flush().map_err(output_error)) {
+        // the reader went away, eg. when piped into `head`, which is not a failure of the dump
+        Err(errors) if matches!(&errors[..], [DriverError::Output(err)] if err.kind() == ErrorKind::BrokenPipe) => Ok(()),
+        result => result,
+    }
+}
+
+fn write_stage(out: &mut impl Write, sources: &mut SourceMap, args: &DumpArgs) -> DriverResult<()> {
+    match args.stage {
+        Stage::Tokens => {
+            let root = driver::module_root(&args.input);
+            let mut errors = vec![];
+
+            for path in &args.input.files {
+                let file = match driver::load_file(sources, path, &root) {
+                    Ok(file) => &sources[file],
+                    Err(err) => {
+                        errors.push(err);
+                        continue;
+                    }
+                };
+                let (tokens, lex_errors) = crate::lexer::tokenize(file);
+                errors.extend(lex_errors.into_iter().map(DriverError::from));
+
+                match args.format {
+                    DumpFormat::Json => {
+                        let value = json!({ "file": file.path, "id": file.id, "tokens": &*tokens });
+                        print(out, &value, args.format).map_err(output_error)?;
+                    }
+                    DumpFormat::Pretty => {
+                        writeln!(out, "{}:", file.path).map_err(output_error)?;
+                        for token in &tokens {
+                            let location = file.location(token.trace().lo as usize);
+                            writeln!(out, "  {}:{}\t{}", location.line, location.column, token.token())
+                                .map_err(output_error)?;
+                        }
+                    }
+                }
+            }
+
+            if errors.is_empty() { Ok(()) } else { Err(errors) }
+        }
+        Stage::Ast => {
+            for (file, statements) in driver::parse_files(sources, &args.input)? {
+                let file = &sources[file];
+
+                match args.format {
+                    DumpFormat::Json => {
+                        let value = json!({ "file": file.path, "id": file.id, "ast": statements });
+                        print(out, &value, args.format).map_err(output_error)?;
+                    }
+                    DumpFormat::Pretty => {
+                        writeln!(out, "{}:", file.path).map_err(output_error)?;
+                        print(out, &statements, args.format).map_err(output_error)?;
+                    }
+                }
+            }
+            Ok(())
+        }
+        Stage::Validated => print(out, &driver::check(sources, &args.input)?, args.format).map_err(output_error),
+        Stage::Instances => print(out, &driver::build(sources, &args.input)?, args.format).map_err(output_error),
+    }
+}
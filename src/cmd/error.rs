@@ -0,0 +1,84 @@
+use std::fmt::{Display, Formatter};
+use std::path::PathBuf;
+use crate::diagnostic::Diagnostic;
+use crate::lexer::error::LexerError;
+use crate::parser::error::ParserError;
+use crate::validation::error::CompilerError;
+
+pub type DriverResult<T> = Result<T, Vec<DriverError>>;
+
+#[derive(Debug)]
+pub enum DriverError {
+    Io(PathBuf, std::io::Error),
+    UnsupportedExtension(PathBuf),
+    Lexer(LexerError),
+    Parser(ParserError),
+    /// Boxed as compiler errors carry whole data types around
+    Compiler(Box<CompilerError>),
+    MissingBackend,
+
+    /// Failed to write the output of the compiler
+    Output(std::io::Error),
+}
+
+impl DriverError {
+    /// Stable code identifying the kind of error
+    pub fn code(&self) -> &'static str {
+        match self {
+            DriverError::Io(..) => "D0001",
+            DriverError::UnsupportedExtension(_) => "D0002",
+            DriverError::MissingBackend => "D0003",
+            DriverError::Output(_) => "D0004",
+            DriverError::Lexer(err) => err.code(),
+            DriverError::Parser(err) => err.code(),
+            DriverError::Compiler(err) => err.code(),
+        }
+    }
+
+    pub fn diagnostic(&self) -> Diagnostic {
+        match self {
+            DriverError::Lexer(err) => err.diagnostic(),
+            DriverError::Parser(err) => err.diagnostic(),
+            DriverError::Compiler(err) => err.diagnostic(),
+            _ => Diagnostic::error(self.code(), self.to_string()),
+        }
+    }
+}
+
+impl std::error::Error for DriverError {}
+
+impl Display for DriverError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            DriverError::Io(path, err) => write!(f, "could not read '{}': {err}", path.display()),
+            DriverError::UnsupportedExtension(path) => write!(
+                f,
+                "'{}' is not a gosling source file (expected a '.gosling' or '.gs' extension)",
+                path.display()
+            ),
+            DriverError::Lexer(err) => write!(f, "{err}"),
+            DriverError::Parser(err) => write!(f, "{err}"),
+            DriverError::Compiler(err) => write!(f, "{err}"),
+            DriverError::MissingBackend => f.write_str("no code generation backend is available to run the program"),
+            DriverError::Output(err) => write!(f, "could not write output: {err}"),
+        }
+    }
+}
+
+impl From<LexerError> for DriverError {
+    fn from(value: LexerError) -> Self {
+        DriverError::Lexer(value)
+    }
+}
+
+impl From<ParserError> for DriverError {
+    fn from(value: ParserError) -> Self {
+        DriverError::Parser(value)
+    }
+}
+
+impl From<CompilerError> for DriverError {
+    fn from(value: CompilerError) -> Self {
+        DriverError::Compiler(Box::new(value))
+    }
+}
@@ -0,0 +1,89 @@
+use std::path::PathBuf;
+use std::process::ExitCode;
+use clap::{Args, Parser, Subcommand, ValueEnum};
+use crate::diagnostic::render::render;
+use crate::file::source_map::SourceMap;
+
+pub mod driver;
+pub mod dump;
+pub mod error;
+
+#[derive(Parser, Debug)]
+#[command(name = "goosey", version, about = "Compiler for the gosling language")]
+pub struct Cli {
+    #[command(subcommand)]
+    pub command: Command,
+
+    /// How errors and warnings are printed
+    #[arg(long, value_enum, global = true, default_value_t = MessageFormat::Human)]
+    pub message_format: MessageFormat,
+}
+
+#[derive(ValueEnum, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MessageFormat {
+    /// Human readable messages on stderr
+    Human,
+
+    /// One JSON record per diagnostic on stdout
+    Json,
+}
+
+#[derive(Subcommand, Debug)]
+pub enum Command {
+    /// Compiles the given source files
+    Build(InputArgs),
+
+    /// Checks the given source files for errors without producing any output
+    Check(InputArgs),
+
+    /// Compiles and runs the given source files
+    Run(InputArgs),
+
+    /// Prints the output of a compiler stage, for debugging the compiler itself
+    Dump(dump::DumpArgs),
+}
+
+#[derive(Args, Debug)]
+pub struct InputArgs {
+    /// Source files ('.gosling' or '.gs') that make up the program
+    #[arg(required = true)]
+    pub files: Vec<PathBuf>,
+
+    /// Directory the module paths of the files start at, eg. `std/ops.gs` inside of it declares
+    /// the module `std::ops`. Defaults to the innermost directory containing every file
+    #[arg(long)]
+    pub root: Option<PathBuf>,
+}
+
+impl Cli {
+    pub fn execute(self) -> ExitCode {
+        let mut sources = SourceMap::new();
+
+        let result = match self.command {
+            Command::Build(args) => driver::build(&mut sources, &args).map(|_| ()),
+            Command::Check(args) => driver::check(&mut sources, &args).map(|_| ()),
+            Command::Run(args) => driver::run(&mut sources, &args),
+            Command::Dump(args) => dump::dump(&mut sources, &args),
+        };
+
+        match result {
+            Ok(()) => ExitCode::SUCCESS,
+            Err(errors) => {
+                match self.message_format {
+                    MessageFormat::Human => {
+                        for error in &errors {
+                            eprintln!("{}", render(&error.diagnostic(), &sources));
+                        }
+                        eprintln!("error: aborting due to {} previous error(s)", errors.len());
+                    }
+                    MessageFormat::Json => {
+                        for error in &errors {
+                            println!("{}", error.diagnostic().to_json(&sources));
+                        }
+                    }
+                }
+                ExitCode::FAILURE
+            }
+        }
+    }
+}
@@ -0,0 +1,63 @@
+use serde::{Deserialize, Serialize};
+use crate::diagnostic::{Diagnostic, Label, Severity};
+use crate::file::source_file::Location;
+use crate::file::source_map::SourceMap;
+
+/// Stable, machine readable form of a [`Diagnostic`], emitted with `--message-format=json`
+#[derive(Serialize, Deserialize, Debug, PartialEq)]
+pub struct JsonDiagnostic {
+    pub severity: Severity,
+    pub code: String,
+    pub message: String,
+    pub primary: Option<JsonSpan>,
+    pub secondary: Vec<JsonSpan>,
+    pub notes: Vec<String>,
+    pub help: Vec<String>,
+}
+
+#[derive(Serialize, Deserialize, Debug, PartialEq)]
+pub struct JsonSpan {
+    pub file: String,
+    pub byte_start: usize,
+    pub byte_end: usize,
+    pub start: Location,
+    pub end: Location,
+    pub label: Option<String>,
+}
+
+impl JsonSpan {
+    pub fn new(label: &Label, sources: &SourceMap) -> Self {
+        let file = &sources[label.trace.file];
+        let range = label.trace.range();
+
+        Self {
+            file: file.path.clone(),
+            byte_start: range.start,
+            byte_end: range.end,
+            start: file.location(range.start),
+            end: file.location(range.end),
+            label: label.message.clone(),
+        }
+    }
+}
+
+impl JsonDiagnostic {
+    pub fn new(diagnostic: &Diagnostic, sources: &SourceMap) -> Self {
+        Self {
+            severity: diagnostic.severity,
+            code: diagnostic.code.into(),
+            message: diagnostic.message.clone(),
+            primary: diagnostic.primary.as_ref().map(|label| JsonSpan::new(label, sources)),
+            secondary: diagnostic.secondary.iter().map(|label| JsonSpan::new(label, sources)).collect(),
+            notes: diagnostic.notes.clone(),
+            help: diagnostic.help.clone(),
+        }
+    }
+}
+
+impl Diagnostic {
+    /// Serializes the diagnostic as a single line JSON record
+    pub fn to_json(&self, sources: &SourceMap) -> String {
+        serde_json::to_string(&JsonDiagnostic::new(self, sources)).expect("Diagnostic is always serializable")
+    }
+}
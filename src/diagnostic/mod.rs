@@ -0,0 +1,86 @@
+use std::fmt::{Display, Formatter};
+use serde::{Deserialize, Serialize};
+use crate::file::trace::Trace;
+
+pub mod json;
+pub mod render;
+
+#[cfg(test)]
+mod test;
+
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq, Clone, Copy)]
+#[serde(rename_all = "lowercase")]
+pub enum Severity {
+    Error,
+    Warning,
+}
+
+/// A span in a source file with an optional message explaining it
+#[derive(Debug, Clone, PartialEq)]
+pub struct Label {
+    pub trace: Trace,
+    pub message: Option<String>,
+}
+
+/// A single problem reported by any stage of the compiler
+#[derive(Debug, Clone, PartialEq)]
+pub struct Diagnostic {
+    pub severity: Severity,
+
+    /// Stable identifier for the kind of problem, eg. 'P0001'
+    pub code: &'static str,
+    pub message: String,
+
+    /// Span most responsible for the problem, not every error can point to one
+    pub primary: Option<Label>,
+    pub secondary: Vec<Label>,
+
+    /// Extra context printed after the source snippet
+    pub notes: Vec<String>,
+
+    /// Suggestions on how to fix the problem
+    pub help: Vec<String>,
+}
+
+impl Diagnostic {
+    pub fn error(code: &'static str, message: impl Into<String>) -> Self {
+        Self {
+            severity: Severity::Error,
+            code,
+            message: message.into(),
+            primary: None,
+            secondary: vec![],
+            notes: vec![],
+            help: vec![],
+        }
+    }
+
+    pub fn with_primary(mut self, trace: Trace, message: Option<String>) -> Self {
+        self.primary = Some(Label { trace, message });
+        self
+    }
+
+    pub fn with_secondary(mut self, trace: Trace, message: Option<String>) -> Self {
+        self.secondary.push(Label { trace, message });
+        self
+    }
+
+    pub fn with_note(mut self, note: impl Into<String>) -> Self {
+        self.notes.push(note.into());
+        self
+    }
+
+    pub fn with_help(mut self, help: impl Into<String>) -> Self {
+        self.help.push(help.into());
+        self
+    }
+}
+
+impl Display for Severity {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            Severity::Error => "error",
+            Severity::Warning => "warning",
+        })
+    }
+}
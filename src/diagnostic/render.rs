@@ -0,0 +1,100 @@
+use std::fmt::Write;
+use crate::diagnostic::{Diagnostic, Label};
+use crate::file::source_map::{FileId, SourceMap};
+
+const TAB_WIDTH: usize = 4;
+
+/// Labels pointing into the same file, paired with whether they are the primary label
+type FileLabels<'a> = (FileId, Vec<(&'a Label, bool)>);
+
+/// Renders a diagnostic the same way rustc does, with the source lines each label points at
+/// and carets underneath the offending code
+pub fn render(diagnostic: &Diagnostic, sources: &SourceMap) -> String {
+    let mut out = String::new();
+    write_diagnostic(&mut out, diagnostic, sources).expect("Writing to a string can not fail");
+    out
+}
+
+fn write_diagnostic(out: &mut String, diagnostic: &Diagnostic, sources: &SourceMap) -> std::fmt::Result {
+    writeln!(out, "{}[{}]: {}", diagnostic.severity, diagnostic.code, diagnostic.message)?;
+
+    let labels: Vec<(&Label, bool)> = diagnostic.primary
+        .iter()
+        .map(|label| (label, true))
+        .chain(diagnostic.secondary.iter().map(|label| (label, false)))
+        .collect();
+
+    let gutter = labels
+        .iter()
+        .map(|(label, _)| sources.location(label.trace).line.to_string().len())
+        .max()
+        .unwrap_or(1);
+    let pad = " ".repeat(gutter);
+
+    // group labels by file, the file of the primary label always comes first
+    let mut files: Vec<FileLabels> = vec![];
+    for (label, primary) in labels {
+        match files.iter_mut().find(|(file, _)| *file == label.trace.file) {
+            Some((_, group)) => group.push((label, primary)),
+            None => files.push((label.trace.file, vec![(label, primary)])),
+        }
+    }
+
+    for (i, (file, mut group)) in files.into_iter().enumerate() {
+        let file = &sources[file];
+        let arrow = if i == 0 { "-->" } else { ":::" };
+
+        writeln!(out, "{pad}{arrow} {}", sources.describe(group[0].0.trace))?;
+        writeln!(out, "{pad} |")?;
+
+        group.sort_by_key(|(label, _)| label.trace.lo);
+
+        let mut previous_line = None;
+        for (label, primary) in group {
+            let range = label.trace.range();
+            let line = file.location(range.start).line;
+            let bounds = file.line_range(line);
+            let text = &file.source[bounds.clone()];
+
+            if previous_line != Some(line) {
+                if previous_line.is_some_and(|previous| line > previous + 1) {
+                    writeln!(out, "...")?;
+                }
+                writeln!(out, "{line:>gutter$} | {}", expand_tabs(text))?;
+            }
+            previous_line = Some(line);
+
+            // spans covering multiple lines are only underlined until the end of their first line
+            let start = text.floor_char_boundary(range.start.clamp(bounds.start, bounds.end) - bounds.start);
+            let end = text.floor_char_boundary(range.end.clamp(bounds.start, bounds.end) - bounds.start);
+
+            let offset = expand_tabs(&text[..start]).chars().count();
+            let width = expand_tabs(&text[start..end.max(start)]).chars().count().max(1);
+            let marker = if primary { "^" } else { "-" };
+
+            write!(out, "{pad} | {}{}", " ".repeat(offset), marker.repeat(width))?;
+            match &label.message {
+                Some(message) => writeln!(out, " {message}")?,
+                None => writeln!(out)?,
+            }
+        }
+    }
+
+    if !diagnostic.notes.is_empty() || !diagnostic.help.is_empty() {
+        writeln!(out, "{pad} |")?;
+    }
+
+    for note in &diagnostic.notes {
+        writeln!(out, "{pad} = note: {note}")?;
+    }
+
+    for help in &diagnostic.help {
+        writeln!(out, "{pad} = help: {help}")?;
+    }
+
+    Ok(())
+}
+
+fn expand_tabs(text: &str) -> String {
+    text.replace('\t', &" ".repeat(TAB_WIDTH))
+}
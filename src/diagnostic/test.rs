@@ -0,0 +1,90 @@
+use crate::diagnostic::json::JsonDiagnostic;
+use crate::diagnostic::render::render;
+use crate::diagnostic::{Diagnostic, Severity};
+use crate::file::source_file::{Location, SourceFile};
+use crate::file::source_map::SourceMap;
+use crate::parser::parser::Parser;
+
+fn first_error(source: &str) -> JsonDiagnostic {
+    let mut sources = SourceMap::new();
+    let file = sources.add(SourceFile::new(source));
+    let (tokens, _) = crate::lexer::tokenize(&sources[file]);
+
+    let (_, errors) = Parser::new(file, tokens).parse();
+    let error = errors.first().expect("Expected a parser error");
+
+    serde_json::from_str(&error.diagnostic().to_json(&sources)).expect("Invalid JSON")
+}
+
+#[test]
+fn location() {
+    let source = SourceFile::new("fun\n  main()\n");
+
+    assert_eq!(source.location(0), Location { line: 1, column: 1 });
+    assert_eq!(source.location(3), Location { line: 1, column: 4 });
+    assert_eq!(source.location(4), Location { line: 2, column: 1 });
+    assert_eq!(source.location(6), Location { line: 2, column: 3 });
+    assert_eq!(source.location(100), Location { line: 3, column: 1 });
+}
+
+#[test]
+fn parser_error_record() {
+    let diagnostic = first_error("fun main() {}\nfun 2() {}");
+
+    assert_eq!(diagnostic.severity, Severity::Error);
+    assert_eq!(diagnostic.code, "P0003");
+    assert_eq!(diagnostic.message, "expected identifier");
+    assert!(diagnostic.secondary.is_empty());
+
+    let primary = diagnostic.primary.expect("Expected a primary span");
+    assert_eq!(primary.file, "<anonymous>");
+    assert_eq!(primary.byte_start, 18);
+    assert_eq!(primary.start, Location { line: 2, column: 5 });
+}
+
+#[test]
+fn render_snippet() {
+    let mut sources = SourceMap::new();
+    let file = sources.add(SourceFile::new("fun main() {\n    let x = foo(1\n}\n"));
+    let source = &sources[file];
+
+    let diagnostic = Diagnostic::error("P0005", "expected `)`")
+        .with_primary(source.trace(31..32), Some("expected `)` here".into()))
+        .with_secondary(source.trace(28..29), Some("unclosed parenthesis".into()))
+        .with_note("arguments are separated by commas")
+        .with_help("add a `)` after the last argument");
+
+    assert_eq!(render(&diagnostic, &sources), [
+        "error[P0005]: expected `)`",
+        " --> <anonymous>:3:1",
+        "  |",
+        "2 |     let x = foo(1",
+        "  |                - unclosed parenthesis",
+        "3 | }",
+        "  | ^ expected `)` here",
+        "  |",
+        "  = note: arguments are separated by commas",
+        "  = help: add a `)` after the last argument",
+        "",
+    ].join("\n"));
+}
+
+#[test]
+fn render_empty_trace() {
+    let mut sources = SourceMap::new();
+    let file = sources.add(SourceFile::new("fun"));
+    let source = &sources[file];
+
+    let diagnostic = Diagnostic::error("P0010", "expected function body")
+        .with_primary(source.trace(3..3), None);
+
+    assert_eq!(render(&diagnostic, &sources), [
+        "error[P0010]: expected function body",
+        " --> <anonymous>:1:4",
+        "  |",
+        "1 | fun",
+        "  |    ^",
+        "",
+    ].join("\n"));
+    assert_eq!(sources.describe(source.trace(3..3)), "<anonymous>:1:4");
+}
@@ -3,18 +3,19 @@ use serde::{Deserialize, Serialize};
 use std::ops::Deref;
 
 
-#[derive(Debug, Eq, PartialEq, Serialize, Deserialize, Clone, Hash)]
+#[derive(Debug, Eq, PartialEq, Ord, PartialOrd, Serialize, Deserialize, Clone, Hash)]
 pub struct GlobalIdentifier(pub Namespace, pub Identifier);
 
-#[derive(Debug, Eq, PartialEq, Serialize, Deserialize, Hash, Clone)]
+#[derive(Debug, Eq, PartialEq, Ord, PartialOrd, Serialize, Deserialize, Hash, Clone)]
 pub struct Namespace {
     pub chain: Vec<Identifier>,
 }
 
-#[derive(Debug, Eq, PartialEq, Serialize, Deserialize, Hash, Clone)]
+#[derive(Debug, Eq, PartialEq, Ord, PartialOrd, Serialize, Deserialize, Hash, Clone)]
 pub struct Identifier(pub String);
 
 impl GlobalIdentifier {
+    #[allow(dead_code)]
     pub fn prefix(&self, namespace: Namespace) -> Self {
         Self(self.0.prefix(namespace), self.1.clone())
     }
@@ -31,7 +32,7 @@ impl Namespace {
         Self {
             chain: prefix.chain
                 .into_iter()
-                .chain(self.chain.clone().into_iter())
+                .chain(self.chain.clone())
                 .collect(),
         }
     }
@@ -46,13 +47,13 @@ impl Namespace {
 
 impl Display for GlobalIdentifier {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
-        f.write_fmt(format_args!("{}:{}", &self.0, &self.1))
+        f.write_fmt(format_args!("{}:{}", self.0, self.1))
     }
 }
 
 impl Display for Namespace {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
-        if self.chain.len() == 0 {
+        if self.chain.is_empty() {
             f.write_str("<global>")?
         } else {
             for ident in &self.chain[0..(self.chain.len() - 1)] {
@@ -3,5 +3,6 @@
 
 
 pub mod source_file;
+pub mod source_map;
 pub mod trace;
 pub mod identifier;
@@ -1,52 +1,142 @@
 use std::fs::File;
 use std::io::Read;
 use std::ops::Range;
-use std::path::Path;
-use std::rc::Rc;
+use std::path::{Component, Path, PathBuf};
 use serde::{Deserialize, Serialize};
-use crate::file::identifier::Identifier;
+use crate::file::identifier::{Identifier, Namespace};
+use crate::file::source_map::FileId;
 use crate::file::trace::Trace;
 
 #[derive(Serialize, Deserialize, Debug, PartialEq, Clone)]
 pub struct SourceFile {
+    /// Assigned once the file is added to a [`SourceMap`](crate::file::source_map::SourceMap)
+    pub id: FileId,
     pub module_name: Identifier,
+
+    /// Modules of the directories the file is in, outermost first
+    pub parent_modules: Vec<Identifier>,
+    pub path: String,
     pub source: String,
+
+    /// Byte offset of the start of every line
+    line_starts: Vec<usize>,
+}
+
+/// Line and column of a byte offset in a source file, both starting at 1
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq, Clone, Copy)]
+pub struct Location {
+    pub line: usize,
+    pub column: usize,
 }
 
 impl SourceFile {
+    #[cfg(test)]
     pub fn new(source: &str) -> Self {
+        Self::with_name("main".into(), "<anonymous>".into(), source.into())
+    }
+
+    pub fn with_name(module_name: Identifier, path: String, source: String) -> Self {
+        let line_starts = std::iter::once(0)
+            .chain(source.match_indices('\n').map(|(i, _)| i + 1))
+            .collect();
+
         Self {
-            module_name: "main".into(),
-            source: String::from(source),
+            id: FileId(0),
+            module_name,
+            parent_modules: vec![],
+            path,
+            source,
+            line_starts,
         }
     }
 
-    pub fn create_from_file(path: &str) -> Result<Self, std::io::Error> {
+    pub fn create_from_file(path: &str, root: &Path) -> Result<Self, std::io::Error> {
         let mut contents = String::new();
 
-        let path = Path::new(path);
-
         let mut file = File::open(path)?;
         file.read_to_string(&mut contents)?;
 
+        Ok(Self::from_path(Path::new(path), root, contents))
+    }
+
+    /// File read from `path`, the directories in between `root` and the file become the modules
+    /// its module is nested in, eg. `std/ops.gs` declares the module `std::ops`. Files outside
+    /// of `root` declare top level modules
+    pub fn from_path(path: &Path, root: &Path, source: String) -> Self {
         let module_name: String = path.file_name().unwrap().to_str().unwrap().into();
         //
         let module_name: String = module_name.split(".").next().unwrap().into();
 
-        Ok(Self {
-            module_name: Identifier(module_name),
-            source: contents,
-        })
+        let relative = absolute_path(path).and_then(|path| {
+            let root = absolute_path(root)?;
+            path.strip_prefix(root).ok().map(Path::to_path_buf)
+        });
+
+        let directories = relative.as_deref().and_then(Path::parent).map(Path::components);
+
+        let parent_modules = directories
+            .into_iter()
+            .flatten()
+            .map(|directory| Identifier(directory.as_os_str().to_string_lossy().into()))
+            .collect();
+
+        Self {
+            parent_modules,
+            ..Self::with_name(Identifier(module_name), path.to_string_lossy().into(), source)
+        }
+    }
+
+    /// Path of the module the file declares, relative to the global module
+    pub fn module(&self) -> Namespace {
+        Namespace {
+            chain: self.parent_modules.iter().chain([&self.module_name]).cloned().collect(),
+        }
+    }
+
+    pub fn trace(&self, range: Range<usize>) -> Trace {
+        Trace::new(self.id, range)
     }
 
-    pub fn trace(self: &Rc<Self>, range: Range<usize>) -> Trace {
-        Trace {
-            source: Rc::clone(self),
-            range,
+    /// Gets the line and column (counted in characters) for a byte offset into the source
+    pub fn location(&self, offset: usize) -> Location {
+        let offset = self.source.floor_char_boundary(offset.min(self.source.len()));
+
+        let line = self.line_starts.partition_point(|&start| start <= offset) - 1;
+        let line_start = self.line_starts[line];
+
+        Location {
+            line: line + 1,
+            column: self.source[line_start..offset].chars().count() + 1,
         }
     }
 
-    pub fn rc(self) -> Rc<Self> {
-        Rc::new(self)
+    /// Byte range of a line (starting at 1), excluding the line break
+    pub fn line_range(&self, line: usize) -> Range<usize> {
+        let start = self.line_starts[line - 1];
+        let end = self.line_starts
+            .get(line)
+            .map_or(self.source.len(), |next| next - 1);
+
+        // do not include the carriage return of windows line endings
+        let end = if self.source[start..end].ends_with('\r') { end - 1 } else { end };
+
+        start..end.max(start)
     }
-}
\ No newline at end of file
+}
+
+/// Absolute form of a path with `.` and `..` resolved, without looking at the file system. [None]
+/// if there is no current directory to resolve a relative path against
+pub fn absolute_path(path: &Path) -> Option<PathBuf> {
+    let mut absolute = PathBuf::new();
+
+    for component in std::path::absolute(path).ok()?.components() {
+        match component {
+            Component::CurDir => {}
+            Component::ParentDir => {
+                absolute.pop();
+            }
+            component => absolute.push(component),
+        }
+    }
+    Some(absolute)
+}
@@ -0,0 +1,51 @@
+use std::ops::Index;
+use std::path::Path;
+use serde::{Deserialize, Serialize};
+use crate::file::source_file::{Location, SourceFile};
+use crate::file::trace::Trace;
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct FileId(pub u32);
+
+/// Owns every source file loaded by the compiler, traces refer to them by [`FileId`]
+#[derive(Debug, Default)]
+pub struct SourceMap {
+    files: Vec<SourceFile>,
+}
+
+impl SourceMap {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn add(&mut self, mut file: SourceFile) -> FileId {
+        let id = FileId(self.files.len() as u32);
+        file.id = id;
+        self.files.push(file);
+        id
+    }
+
+    /// Reads a file whose module path is relative to `root`
+    pub fn load(&mut self, path: &str, root: &Path) -> Result<FileId, std::io::Error> {
+        Ok(self.add(SourceFile::create_from_file(path, root)?))
+    }
+
+    /// Line and column of the start of the trace
+    pub fn location(&self, trace: Trace) -> Location {
+        self[trace.file].location(trace.lo as usize)
+    }
+
+    /// Formats the start of the trace as 'file:line:column'
+    pub fn describe(&self, trace: Trace) -> String {
+        let location = self.location(trace);
+        format!("{}:{}:{}", self[trace.file].path, location.line, location.column)
+    }
+}
+
+impl Index<FileId> for SourceMap {
+    type Output = SourceFile;
+
+    fn index(&self, index: FileId) -> &Self::Output {
+        &self.files[index.0 as usize]
+    }
+}
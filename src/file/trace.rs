@@ -1,38 +1,52 @@
-use std::fmt::{Debug, Display, Formatter};
+use std::hash::{Hash, Hasher};
 use std::ops::Range;
-use std::rc::Rc;
+use serde::{Deserialize, Serialize};
 
-use crate::file::source_file::SourceFile;
+use crate::file::source_map::FileId;
 
-#[derive(Debug)]
+/// Span of bytes in one of the files of a [`SourceMap`](crate::file::source_map::SourceMap)
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
 pub struct Trace {
-    pub source: Rc<SourceFile>,
-    pub range: Range<usize>,
+    pub file: FileId,
+    pub lo: u32,
+    pub hi: u32,
 }
 
-impl Eq for Trace {}
+impl Trace {
+    pub fn new(file: FileId, range: Range<usize>) -> Self {
+        Self {
+            file,
+            lo: range.start as u32,
+            hi: range.end as u32,
+        }
+    }
 
-impl PartialEq for Trace {
-    fn eq(&self, _: &Self) -> bool {
-        true
+    #[inline]
+    pub fn range(&self) -> Range<usize> {
+        (self.lo as usize)..(self.hi as usize)
     }
-}
 
-impl Clone for Trace {
-    fn clone(&self) -> Self {
+    /// Smallest trace covering both traces, they must be in the same file
+    pub fn to(self, other: Trace) -> Trace {
+        debug_assert_eq!(self.file, other.file, "Joined traces from different files");
+
         Self {
-            source: Rc::clone(&self.source),
-            range: self.range.clone(),
+            file: self.file,
+            lo: self.lo.min(other.lo),
+            hi: self.hi.max(other.hi),
         }
     }
 }
 
-impl Display for Trace {
-    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
-        let src = self.source.source.as_str();
-        let start = self.range.start.max(0);
-        let end = (self.range.end - 1).min(self.source.source.len() - 1);
-        f.write_fmt(format_args!("Trace {{ '{}' }}", &src[start..end]))?;
-        f.write_fmt(format_args!("{:?}", self.range))
+impl Eq for Trace {}
+
+impl PartialEq for Trace {
+    fn eq(&self, _: &Self) -> bool {
+        true
     }
-}
\ No newline at end of file
+}
+
+/// Traces are all equal, so none of them adds to the hash
+impl Hash for Trace {
+    fn hash<H: Hasher>(&self, _: &mut H) {}
+}
@@ -1,4 +1,5 @@
 pub mod visibility;
 mod module;
 
+#[allow(dead_code)]
 pub struct Module {}
\ No newline at end of file
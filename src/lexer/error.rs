@@ -0,0 +1,181 @@
+use std::fmt::{Display, Formatter};
+use crate::diagnostic::Diagnostic;
+use crate::file::trace::Trace;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum LexerError {
+    UnknownCharacter(char, Trace),
+    UnterminatedString(Trace),
+    UnterminatedComment(Trace),
+    LiteralOutOfRange {
+        literal: String,
+        ty: &'static str,
+        trace: Trace,
+    },
+    InvalidSuffix {
+        suffix: String,
+        kind: &'static str,
+        trace: Trace,
+    },
+    InvalidDigit {
+        digit: char,
+        radix: u32,
+        trace: Trace,
+    },
+    MissingDigits(Trace),
+    UnterminatedChar(Trace),
+    EmptyChar(Trace),
+    OverlongChar(Trace),
+    InvalidEscape(char, Trace),
+    InvalidUnicodeEscape {
+        reason: &'static str,
+        trace: Trace,
+    },
+    UnterminatedInterpolation(Trace),
+    UnmatchedFormatBrace(Trace),
+}
+
+impl LexerError {
+    pub fn trace(&self) -> Trace {
+        match self {
+            LexerError::UnknownCharacter(_, trace) => *trace,
+            LexerError::UnterminatedString(trace) => *trace,
+            LexerError::UnterminatedComment(trace) => *trace,
+            LexerError::LiteralOutOfRange { trace, .. } => *trace,
+            LexerError::InvalidSuffix { trace, .. } => *trace,
+            LexerError::InvalidDigit { trace, .. } => *trace,
+            LexerError::MissingDigits(trace) => *trace,
+            LexerError::UnterminatedChar(trace) => *trace,
+            LexerError::EmptyChar(trace) => *trace,
+            LexerError::OverlongChar(trace) => *trace,
+            LexerError::InvalidEscape(_, trace) => *trace,
+            LexerError::InvalidUnicodeEscape { trace, .. } => *trace,
+            LexerError::UnterminatedInterpolation(trace) => *trace,
+            LexerError::UnmatchedFormatBrace(trace) => *trace,
+        }
+    }
+
+    /// Stable code identifying the kind of error
+    pub fn code(&self) -> &'static str {
+        match self {
+            LexerError::UnknownCharacter(..) => "L0001",
+            LexerError::UnterminatedString(_) => "L0002",
+            LexerError::UnterminatedComment(_) => "L0003",
+            LexerError::LiteralOutOfRange { .. } => "L0004",
+            LexerError::InvalidSuffix { .. } => "L0005",
+            LexerError::InvalidDigit { .. } => "L0006",
+            LexerError::MissingDigits(_) => "L0007",
+            LexerError::UnterminatedChar(_) => "L0008",
+            LexerError::EmptyChar(_) => "L0009",
+            LexerError::OverlongChar(_) => "L0010",
+            LexerError::InvalidEscape(..) => "L0011",
+            LexerError::InvalidUnicodeEscape { .. } => "L0012",
+            LexerError::UnterminatedInterpolation(_) => "L0013",
+            LexerError::UnmatchedFormatBrace(_) => "L0014",
+        }
+    }
+
+    pub fn message(&self) -> String {
+        match self {
+            LexerError::UnknownCharacter(c, _) => format!("unknown character {c:?}"),
+            LexerError::UnterminatedString(_) => "unterminated string literal".into(),
+            LexerError::UnterminatedComment(_) => "unterminated block comment".into(),
+            LexerError::LiteralOutOfRange { literal, ty, .. } => {
+                format!("literal `{literal}` does not fit into the type `{ty}`")
+            }
+            LexerError::InvalidSuffix { suffix, kind, .. } => {
+                format!("invalid suffix `{suffix}` for {kind} literal")
+            }
+            LexerError::InvalidDigit { digit, radix, .. } => {
+                format!("invalid digit {digit:?} in {} literal", radix_name(*radix))
+            }
+            LexerError::MissingDigits(_) => "no valid digits found for number".into(),
+            LexerError::UnterminatedChar(_) => "unterminated character literal".into(),
+            LexerError::EmptyChar(_) => "empty character literal".into(),
+            LexerError::OverlongChar(_) => "character literal may only contain one character".into(),
+            LexerError::InvalidEscape(c, _) => format!("unknown character escape `\\{c}`"),
+            LexerError::InvalidUnicodeEscape { reason, .. } => (*reason).into(),
+            LexerError::UnterminatedInterpolation(_) => "unterminated interpolation in string".into(),
+            LexerError::UnmatchedFormatBrace(_) => "unmatched `}` in string".into(),
+        }
+    }
+
+    pub fn diagnostic(&self) -> Diagnostic {
+        let diagnostic = Diagnostic::error(self.code(), self.message());
+
+        match self {
+            LexerError::UnknownCharacter(..) => diagnostic
+                .with_primary(self.trace(), Some("not valid in gosling source".into())),
+            LexerError::UnterminatedString(_) => diagnostic
+                .with_primary(self.trace(), Some("string starts here and is never closed".into()))
+                .with_help("add a closing `\"`"),
+            LexerError::UnterminatedComment(_) => diagnostic
+                .with_primary(self.trace(), Some("comment starts here and is never closed".into()))
+                .with_help("add a closing `*/`"),
+            LexerError::LiteralOutOfRange { ty, .. } => {
+                let diagnostic = diagnostic.with_primary(self.trace(), None);
+                match integer_range(ty) {
+                    Some((min, max)) => diagnostic.with_note(format!("`{ty}` ranges from {min} to {max}")),
+                    None => diagnostic,
+                }
+            }
+            LexerError::InvalidSuffix { kind, .. } => {
+                let valid = if *kind == "float" { "`f32` and `f64`" } else { "`u32`, `u64`, `usize`, `i32`, `i64`, `f32` and `f64`" };
+                diagnostic
+                    .with_primary(self.trace(), Some("invalid suffix".into()))
+                    .with_help(format!("the suffix must be one of {valid}"))
+            }
+            LexerError::InvalidDigit { .. } => diagnostic
+                .with_primary(self.trace(), None),
+            LexerError::MissingDigits(_) => diagnostic
+                .with_primary(self.trace(), None),
+            LexerError::UnterminatedChar(_) => diagnostic
+                .with_primary(self.trace(), Some("character literal starts here and is never closed".into()))
+                .with_help("add a closing `'`"),
+            LexerError::EmptyChar(_) => diagnostic
+                .with_primary(self.trace(), None),
+            LexerError::OverlongChar(_) => diagnostic
+                .with_primary(self.trace(), None)
+                .with_help("use a string literal `\"...\"` for more than one character"),
+            LexerError::InvalidEscape(..) => diagnostic
+                .with_primary(self.trace(), Some("unknown character escape".into()))
+                .with_help("valid escapes are `\\n`, `\\t`, `\\r`, `\\0`, `\\\\`, `\\'`, `\\\"` and `\\u{...}`"),
+            LexerError::InvalidUnicodeEscape { .. } => diagnostic
+                .with_primary(self.trace(), None),
+            LexerError::UnterminatedInterpolation(_) => diagnostic
+                .with_primary(self.trace(), Some("interpolation starts here and is never closed".into()))
+                .with_help("add a closing `}`, or write `{{` for a literal brace"),
+            LexerError::UnmatchedFormatBrace(_) => diagnostic
+                .with_primary(self.trace(), None)
+                .with_help("write `}}` for a literal brace"),
+        }
+    }
+}
+
+fn radix_name(radix: u32) -> &'static str {
+    match radix {
+        2 => "binary",
+        8 => "octal",
+        16 => "hexadecimal",
+        _ => "decimal",
+    }
+}
+
+/// Smallest and largest value of an integer type
+pub fn integer_range(ty: &str) -> Option<(i128, i128)> {
+    Some(match ty {
+        "u32" => (0, u32::MAX.into()),
+        "u64" | "usize" => (0, u64::MAX.into()),
+        "i32" => (i32::MIN.into(), i32::MAX.into()),
+        "i64" => (i64::MIN.into(), i64::MAX.into()),
+        _ => return None,
+    })
+}
+
+impl std::error::Error for LexerError {}
+
+impl Display for LexerError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.message())
+    }
+}
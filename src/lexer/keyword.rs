@@ -1,19 +1,23 @@
+use std::fmt::{Display, Formatter};
 use serde::{Deserialize, Serialize};
 
 #[derive(Serialize, Deserialize, Debug, PartialEq, Clone, Hash)]
 pub enum Keyword {
     Let,
+    Const,
     Mut,
     Fun,
     Return,
     Ref,
     Deref,
     For,
+    In,
     While,
     Unless,
     Else,
     Struct,
     Impl,
+    Trait,
     Sizeof,
     As,
     Public,
@@ -21,7 +25,10 @@ pub enum Keyword {
     If,
     Unit,
     Import,
-    Enum
+    Enum,
+    Match,
+    Make,
+    Alloc,
 }
 
 impl Keyword {
@@ -29,12 +36,14 @@ impl Keyword {
         use Keyword as E;
         Some(match txt {
             "let" => E::Let,
+            "const" => E::Const,
             "mut" => E::Mut,
             "fun" => E::Fun,
             "return" => E::Return,
             "ref" => E::Ref,
             "deref" => E::Deref,
             "for" => E::For,
+            "in" => E::In,
             "while" => E::While,
             "unless" => E::Unless,
             "until" => E::Until,
@@ -42,13 +51,55 @@ impl Keyword {
             "else" => E::Else,
             "struct" => E::Struct,
             "impl" => E::Impl,
+            "trait" => E::Trait,
             "sizeof" => E::Sizeof,
             "as" => E::As,
             "pub" => E::Public,
             "unit" => E::Unit,
             "import" => E::Import,
             "enum" => E::Enum,
+            "match" => E::Match,
+            "make" => E::Make,
+            "alloc" => E::Alloc,
             _ => return None
         })
     }
-}
\ No newline at end of file
+
+    pub fn as_str(&self) -> &'static str {
+        use Keyword as E;
+        match self {
+            E::Let => "let",
+            E::Const => "const",
+            E::Mut => "mut",
+            E::Fun => "fun",
+            E::Return => "return",
+            E::Ref => "ref",
+            E::Deref => "deref",
+            E::For => "for",
+            E::In => "in",
+            E::While => "while",
+            E::Unless => "unless",
+            E::Until => "until",
+            E::If => "if",
+            E::Else => "else",
+            E::Struct => "struct",
+            E::Impl => "impl",
+            E::Trait => "trait",
+            E::Sizeof => "sizeof",
+            E::As => "as",
+            E::Public => "pub",
+            E::Unit => "unit",
+            E::Import => "import",
+            E::Enum => "enum",
+            E::Match => "match",
+            E::Make => "make",
+            E::Alloc => "alloc",
+        }
+    }
+}
+
+impl Display for Keyword {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
@@ -1,26 +1,44 @@
-use std::rc::Rc;
+use std::str::FromStr;
 use crate::file::identifier::Identifier;
 use crate::file::source_file::SourceFile;
 use crate::file::trace::Trace;
+use crate::lexer::error::LexerError;
 use crate::lexer::keyword::Keyword;
-use crate::lexer::token::{Token, TokenData};
+use crate::lexer::token::{FormatSegment, Operator, Token, TokenData, TokenStream};
+
+/// Character used for `curr` once the whole file has been consumed
+const EOF_CHAR: char = '\0';
 
 #[derive(Debug)]
-pub struct Lexer {
-    file: Rc<SourceFile>,
+pub struct Lexer<'a> {
+    file: &'a SourceFile,
+
+    /// Byte offset of the current character, always on a char boundary
     index: usize,
+    errors: Vec<LexerError>,
 }
 
-impl Lexer {
-    pub fn new(source: Rc<SourceFile>) -> Self {
+impl<'a> Lexer<'a> {
+    pub fn new(source: &'a SourceFile) -> Self {
         Self {
             file: source,
             index: 0,
+            errors: vec![],
         }
     }
 
+    /// Source code that has not been consumed yet
+    pub fn rest(&self) -> &'a str {
+        &self.file.source[self.index..]
+    }
+
     pub fn curr(&self) -> char {
-        self.file.source.chars().nth(self.index).unwrap_or(' ')
+        self.rest().chars().next().unwrap_or(EOF_CHAR)
+    }
+
+    /// Character after the current one
+    pub fn peek(&self) -> char {
+        self.rest().chars().nth(1).unwrap_or(EOF_CHAR)
     }
 
     pub fn trace_from(&self, from: usize, offset: usize) -> Trace {
@@ -28,26 +46,31 @@ impl Lexer {
     }
 
     pub fn trace(&self, offset: usize) -> Trace {
-        // self.file.trace(self.index..(self.index + offset))
         self.trace_from(self.index, offset)
     }
 
+    /// Moves past the current character and returns the next one
     pub fn advance(&mut self) -> char {
-        self.advance_by(1)
+        self.index += self.curr().len_utf8().min(self.rest().len());
+        self.curr()
     }
 
+    /// Moves past the next `delta` characters
     pub fn advance_by(&mut self, delta: usize) -> char {
-        self.index += delta;
+        for _ in 0..delta {
+            self.advance();
+        }
         self.curr()
     }
 
-    pub fn slice(&mut self, offset: usize) -> &str {
+    /// The next `offset` bytes, or an empty slice if that would split a character
+    pub fn slice(&self, offset: usize) -> &'a str {
         let upper = (self.index + offset).min(self.file.source.len());
-        &self.file.source[self.index..upper]
+        self.file.source.get(self.index..upper).unwrap_or("")
     }
 
     #[inline]
-    pub fn not_eof(&mut self) -> bool {
+    pub fn not_eof(&self) -> bool {
         self.has_clearance(1)
     }
 
@@ -55,9 +78,30 @@ impl Lexer {
         self.index + i <= self.file.source.len()
     }
 
-    pub fn tokenize(mut self) -> Vec<Token> {
-        let passes = &[
+    pub fn tokenize(mut self) -> (TokenStream, Vec<LexerError>) {
+        let mut toks = TokenStream::default();
+        let mut end = self.index;
+
+        while let Some(tok) = self.next_token() {
+            // comments and whitespace skipped before the token, including any line break
+            let line_break = self.file.source[end..tok.trace().lo as usize].contains('\n');
+            end = self.index;
+            toks.push(tok, line_break);
+        }
+
+        toks.push(Token(
+            TokenData::EOF, self.trace(0),
+        ), false);
+
+        (toks, self.errors)
+    }
+
+    /// Lexes the next token, [None] once the end of the file is reached
+    pub fn next_token(&mut self) -> Option<Token> {
+        let passes = [
             Self::string,
+            Self::raw_string,
+            Self::char,
             Self::number,
             Self::identifier,
             Self::tri_operator,
@@ -65,30 +109,34 @@ impl Lexer {
             Self::operator
         ];
 
-        let mut toks = vec![];
-        while self.not_eof() {
-            if self.curr().is_whitespace() {
-                self.advance();
-                continue;
-            }
+        loop {
+            self.skip_trivia();
 
-            if self.comment() {
-                continue;
+            if !self.not_eof() {
+                return None;
             }
 
-            for pass in passes {
-                if let Some(tok) = pass(&mut self) {
-                    toks.push(tok);
-                    break;
+            match passes.iter().find_map(|pass| pass(self)) {
+                Some(tok) => return Some(tok),
+                None => {
+                    // skip the character so the lexer always makes progress
+                    let c = self.curr();
+                    self.errors.push(LexerError::UnknownCharacter(c, self.trace(c.len_utf8())));
+                    self.advance();
                 }
             }
         }
+    }
 
-        toks.push(Token(
-            TokenData::EOF, self.trace(1),
-        ));
-
-        toks
+    /// Skips whitespace and comments
+    pub fn skip_trivia(&mut self) {
+        while self.not_eof() {
+            if self.curr().is_whitespace() {
+                self.advance();
+            } else if !self.comment() {
+                break;
+            }
+        }
     }
 
     pub fn comment(&mut self) -> bool {
@@ -102,7 +150,18 @@ impl Lexer {
                 true
             }
             "/*" => {
-                while self.not_eof() && self.slice(2) != "*/" {}
+                let start = self.index;
+                self.advance_by(2);
+
+                while self.not_eof() && self.slice(2) != "*/" {
+                    self.advance();
+                }
+
+                if self.not_eof() {
+                    self.advance_by(2);
+                } else {
+                    self.errors.push(LexerError::UnterminatedComment(self.trace_from(start, 2)));
+                }
                 true
             }
             _ => false
@@ -110,13 +169,13 @@ impl Lexer {
     }
 
     pub fn identifier(&mut self) -> Option<Token> {
-        if !self.curr().is_ascii_alphabetic() && self.curr() != '_' {
+        if !unicode_ident::is_xid_start(self.curr()) && self.curr() != '_' {
             return None;
         }
 
         let start = self.index;
 
-        while self.advance().is_ascii_alphanumeric() || self.curr() == '_' {}
+        while unicode_ident::is_xid_continue(self.advance()) {}
 
         if self.curr() == '!' {
             self.advance();
@@ -154,97 +213,428 @@ impl Lexer {
         }
 
         let start = self.index;
+        self.advance();
 
-        let mut builder = string_builder::Builder::default();
-        while self.not_eof() && self.advance() != '"' {
-            if self.curr() == '\\' {
-                builder.append(self.advance());
-            } else {
-                builder.append(self.curr());
+        let mut segments = vec![];
+        let mut value = String::new();
+        loop {
+            if !self.not_eof() {
+                self.errors.push(LexerError::UnterminatedString(self.trace_from(start, 1)));
+                break;
+            }
+
+            match self.curr() {
+                '"' => {
+                    self.advance();
+                    break;
+                }
+                '\\' => value.extend(self.escape()),
+                '{' if self.peek() == '{' => {
+                    value.push('{');
+                    self.advance_by(2);
+                }
+                '}' if self.peek() == '}' => {
+                    value.push('}');
+                    self.advance_by(2);
+                }
+                '{' => {
+                    if !value.is_empty() {
+                        segments.push(FormatSegment::Literal(std::mem::take(&mut value)));
+                    }
+                    segments.push(self.interpolation());
+                }
+                '}' => {
+                    self.errors.push(LexerError::UnmatchedFormatBrace(self.trace(1)));
+                    value.push('}');
+                    self.advance();
+                }
+                c => {
+                    value.push(c);
+                    self.advance();
+                }
             }
         }
+
+        let tok = if segments.is_empty() {
+            TokenData::StringLiteral(value)
+        } else {
+            if !value.is_empty() {
+                segments.push(FormatSegment::Literal(value));
+            }
+            TokenData::FormatString(segments)
+        };
+
+        Some(Token(tok, self.file.trace(start..self.index)))
+    }
+
+    /// Expression embedded into a string `"{...}"`, the current character is the opening brace.
+    /// The expression is lexed into its own token stream ending with an EOF token at the closing
+    /// brace, empty braces are a placeholder for formatting macros
+    fn interpolation(&mut self) -> FormatSegment {
+        let start = self.index;
         self.advance();
 
-        let tok = TokenData::StringLiteral(builder.string().expect("UTF8 Error"));
+        let mut tokens = vec![];
+        let mut depth = 0usize;
+
+        loop {
+            self.skip_trivia();
+
+            // a quote outside of any brackets is far more likely to be the end of the string
+            if !self.not_eof() || (self.curr() == '"' && depth == 0) {
+                self.errors.push(LexerError::UnterminatedInterpolation(self.trace_from(start, 1)));
+                break;
+            }
+
+            let Some(tok) = self.next_token() else { continue };
+
+            match tok.token() {
+                TokenData::Operator(Operator::CurlyClose) if depth == 0 => break,
+                TokenData::Operator(Operator::CurlyOpen | Operator::ParenOpen | Operator::BracketOpen) => depth += 1,
+                TokenData::Operator(Operator::CurlyClose | Operator::ParenClose | Operator::BracketClose) => {
+                    depth = depth.saturating_sub(1);
+                }
+                _ => {}
+            }
+            tokens.push(tok);
+        }
+
+        if tokens.is_empty() {
+            return FormatSegment::Placeholder(self.file.trace(start..self.index));
+        }
+
+        tokens.push(Token(TokenData::EOF, self.file.trace(self.index.saturating_sub(1)..self.index)));
+        FormatSegment::Interpolation(tokens)
+    }
+
+    /// Raw string literal `r"..."`, any number of `#` can surround the quotes to allow `"` inside
+    pub fn raw_string(&mut self) -> Option<Token> {
+        if self.curr() != 'r' {
+            return None;
+        }
+
+        let hashes = self.rest()[1..].chars().take_while(|&c| c == '#').count();
+        if !self.rest()[1 + hashes..].starts_with('"') {
+            return None;
+        }
+
+        let start = self.index;
+        self.index += 2 + hashes;
+
+        let terminator = format!("\"{}", "#".repeat(hashes));
+        let value = match self.rest().find(&terminator) {
+            Some(len) => {
+                let value = &self.rest()[..len];
+                self.index += len + terminator.len();
+                value
+            }
+            None => {
+                let value = self.rest();
+                self.index = self.file.source.len();
+                self.errors.push(LexerError::UnterminatedString(self.trace_from(start, 2 + hashes)));
+                value
+            }
+        };
+
         Some(Token(
-            tok,
-            self.trace_from(start, self.index - 1),
+            TokenData::StringLiteral(value.to_string()),
+            self.file.trace(start..self.index),
         ))
     }
 
-    pub fn number(&mut self) -> Option<Token> {
-        if !self.curr().is_ascii_digit() && !(self.curr() == '.') {
+    pub fn char(&mut self) -> Option<Token> {
+        if self.curr() != '\'' {
             return None;
         }
 
         let start = self.index;
-        let mut has_dot = false;
+        self.advance();
 
-        loop {
-            if self.curr() == '.' && !has_dot {
-                has_dot = true;
-            } else if !self.curr().is_ascii_digit() {
-                break;
+        let value = match self.curr() {
+            '\'' => {
+                self.advance();
+                self.errors.push(LexerError::EmptyChar(self.file.trace(start..self.index)));
+                None
+            }
+            _ if !self.not_eof() || self.curr() == '\n' => {
+                self.errors.push(LexerError::UnterminatedChar(self.trace_from(start, 1)));
+                return Some(Token(TokenData::CharLiteral('\0'), self.trace_from(start, 1)));
             }
+            '\\' => self.escape(),
+            c => {
+                self.advance();
+                Some(c)
+            }
+        };
+
+        if self.curr() == '\'' {
             self.advance();
+        } else if value.is_some() {
+            let line = self.rest().split('\n').next().unwrap_or("");
+
+            match line.find('\'') {
+                Some(len) => {
+                    self.index += len + 1;
+                    self.errors.push(LexerError::OverlongChar(self.file.trace(start..self.index)));
+                }
+                None => self.errors.push(LexerError::UnterminatedChar(self.trace_from(start, 1))),
+            }
         }
 
-        // if the only character is a dot then skip
-        if has_dot && self.index - start == 1 {
-            self.index = start;
+        Some(Token(
+            TokenData::CharLiteral(value.unwrap_or('\0')),
+            self.file.trace(start..self.index),
+        ))
+    }
+
+    /// Decodes the escape sequence starting at the current `\`, invalid escapes are reported and
+    /// decode to [None]
+    fn escape(&mut self) -> Option<char> {
+        let start = self.index;
+        let c = self.advance();
+
+        // the unterminated literal is reported by the caller
+        if !self.not_eof() {
             return None;
         }
+        self.advance();
 
-        let num_sub = String::from(&self.file.source[start..self.index]);
+        Some(match c {
+            'n' => '\n',
+            't' => '\t',
+            'r' => '\r',
+            '0' => '\0',
+            '\\' => '\\',
+            '\'' => '\'',
+            '"' => '"',
+            'u' => return self.unicode_escape(start),
+            _ => {
+                self.errors.push(LexerError::InvalidEscape(c, self.file.trace(start..self.index)));
+                return None;
+            }
+        })
+    }
 
-        match self.curr() {
-            // Explicit 32 bit float
-            'f' => {
+    /// `\u{...}` escape, `start` points at the backslash and the `u` was already consumed
+    fn unicode_escape(&mut self, start: usize) -> Option<char> {
+        let reason = 'escape: {
+            if self.curr() != '{' {
+                break 'escape "expected `{` after `\\u`";
+            }
+            self.advance();
+
+            let digits_start = self.index;
+            while self.curr().is_ascii_hexdigit() {
                 self.advance();
+            }
+            let digits = &self.file.source[digits_start..self.index];
 
-                return Some(Token(
-                    TokenData::F32Literal(num_sub.parse().unwrap()),
-                    self.file.trace(start..self.index),
-                ));
+            if self.curr() != '}' {
+                break 'escape "unterminated unicode escape";
             }
+            self.advance();
 
-            // Explicit 64 bit double
-            'd' => {
-                self.advance();
-                return Some(
-                    Token(
-                        TokenData::F64Literal(num_sub.parse().unwrap()),
-                        self.file.trace(start..self.index),
-                    ));
+            if digits.is_empty() || digits.len() > 6 {
+                break 'escape "unicode escape must have between 1 and 6 hex digits";
             }
-            _ => {}
+
+            match u32::from_str_radix(digits, 16).ok().and_then(char::from_u32) {
+                Some(c) => return Some(c),
+                None => "invalid unicode character escape",
+            }
+        };
+
+        self.errors.push(LexerError::InvalidUnicodeEscape {
+            reason,
+            trace: self.file.trace(start..self.index),
+        });
+        None
+    }
+
+    pub fn number(&mut self) -> Option<Token> {
+        // a leading dot is allowed (`.5`) unless it is the end of a range like `0..5`
+        let follows_dot = self.file.source[..self.index].ends_with('.');
+        let starts_number = self.curr().is_ascii_digit()
+            || (self.curr() == '.' && self.peek().is_ascii_digit() && !follows_dot);
+
+        if !starts_number {
+            return None;
+        }
+
+        let start = self.index;
+
+        let radix = match self.slice(2) {
+            "0x" => 16,
+            "0o" => 8,
+            "0b" => 2,
+            _ => 10,
         };
 
+        if radix != 10 {
+            self.advance_by(2);
+            return Some(self.radix_number(start, radix));
+        }
 
-        // if no valid suffix but has a dot, assume is 32 bit float
-        if has_dot {
-            return Some(Token(
-                TokenData::F32Literal(num_sub.parse().unwrap()),
-                self.file.trace(start..self.index),
-            ));
+        let mut is_float = false;
+        while self.curr().is_ascii_digit() || self.curr() == '_' {
+            self.advance();
         }
 
-        match self.curr() {
-            // L suffix with no dot assumes 64 bit integer
-            'L' => {
+        // a second dot belongs to a range or property access, `1..2` and `2.0.` are not floats
+        if self.is_decimal_point() {
+            is_float = true;
+            self.advance();
+            while self.curr().is_ascii_digit() || self.curr() == '_' {
                 self.advance();
-                Some(Token(
-                    TokenData::I64Literal(num_sub.parse().unwrap()),
-                    self.file.trace(start..self.index),
-                ))
             }
+        }
 
-            // assume i32 literal if not specified
-            _ => Some(Token(
-                TokenData::I32Literal(num_sub.parse().unwrap()),
-                self.file.trace(start..self.index),
-            ))
+        if matches!(self.curr(), 'e' | 'E') {
+            let sign = usize::from(matches!(self.peek(), '+' | '-'));
+            if self.rest().chars().nth(1 + sign).is_some_and(|c| c.is_ascii_digit()) {
+                is_float = true;
+                self.advance_by(1 + sign);
+                while self.curr().is_ascii_digit() || self.curr() == '_' {
+                    self.advance();
+                }
+            }
         }
+
+        let digits = self.file.source[start..self.index].replace('_', "");
+        let suffix_start = self.index;
+        let suffix = self.suffix();
+
+        let data = match suffix {
+            "" if is_float => TokenData::F32Literal(self.parse_float(&digits, "f32", start)),
+            "" => TokenData::IntegerLiteral(self.parse_integer(&digits, 10, "u64", start)),
+
+            // single letter suffixes from before the full suffix set existed
+            "f" | "f32" => TokenData::F32Literal(self.parse_float(&digits, "f32", start)),
+            "d" | "f64" => TokenData::F64Literal(self.parse_float(&digits, "f64", start)),
+            "L" if !is_float => TokenData::I64Literal(self.parse_integer(&digits, 10, "i64", start)),
+
+            _ if !is_float => match self.integer_literal(suffix, &digits, 10, start) {
+                Some(data) => data,
+                None => self.invalid_suffix(suffix, suffix_start, "integer"),
+            },
+            _ => self.invalid_suffix(suffix, suffix_start, "float"),
+        };
+
+        Some(Token(data, self.file.trace(start..self.index)))
+    }
+
+    /// Integer literal with a `0x`, `0o` or `0b` prefix, `start` points at the prefix
+    fn radix_number(&mut self, start: usize, radix: u32) -> Token {
+        let digits_start = self.index;
+
+        while self.curr().is_ascii_hexdigit() || self.curr() == '_' {
+            if !self.curr().is_digit(radix) && self.curr() != '_' {
+                let c = self.curr();
+                self.errors.push(LexerError::InvalidDigit {
+                    digit: c,
+                    radix,
+                    trace: self.trace(1),
+                });
+            }
+            self.advance();
+        }
+
+        let digits = self.file.source[digits_start..self.index].replace('_', "");
+        if digits.is_empty() {
+            self.errors.push(LexerError::MissingDigits(self.file.trace(start..self.index)));
+        }
+
+        let suffix_start = self.index;
+        let suffix = self.suffix();
+
+        let data = match suffix {
+            "" => TokenData::IntegerLiteral(self.parse_integer(&digits, radix, "u64", start)),
+            "L" => TokenData::I64Literal(self.parse_integer(&digits, radix, "i64", start)),
+            _ => match self.integer_literal(suffix, &digits, radix, start) {
+                Some(data) => data,
+                None => self.invalid_suffix(suffix, suffix_start, "integer"),
+            },
+        };
+
+        Token(data, self.file.trace(start..self.index))
+    }
+
+    /// Whether the current `.` is the decimal point of the number before it, which it is when
+    /// digits or a float suffix follow like in `2.5` or `2.f`. Otherwise it starts a range or a
+    /// method call like `1..2` or `1.max(2)`
+    fn is_decimal_point(&self) -> bool {
+        let Some(after) = self.rest().strip_prefix('.') else {
+            return false;
+        };
+
+        if after.starts_with(|c: char| c.is_ascii_digit()) {
+            return true;
+        }
+
+        let word = after.find(|c: char| !unicode_ident::is_xid_continue(c)).unwrap_or(after.len());
+        matches!(&after[..word], "f" | "d" | "f32" | "f64")
+    }
+
+    /// Consumes the type suffix directly following a literal
+    fn suffix(&mut self) -> &'a str {
+        let start = self.index;
+
+        if unicode_ident::is_xid_start(self.curr()) {
+            while unicode_ident::is_xid_continue(self.advance()) {}
+        }
+
+        &self.file.source[start..self.index]
+    }
+
+    /// Integer literal for the given integer suffix, [None] if the suffix is not an integer type
+    fn integer_literal(&mut self, suffix: &str, digits: &str, radix: u32, start: usize) -> Option<TokenData> {
+        Some(match suffix {
+            "u32" => TokenData::U32Literal(self.parse_integer(digits, radix, "u32", start)),
+            "u64" => TokenData::U64Literal(self.parse_integer(digits, radix, "u64", start)),
+            "usize" => TokenData::UsizeLiteral(self.parse_integer(digits, radix, "usize", start)),
+            "i32" => TokenData::I32Literal(self.parse_integer(digits, radix, "i32", start)),
+            "i64" => TokenData::I64Literal(self.parse_integer(digits, radix, "i64", start)),
+            _ => return None,
+        })
+    }
+
+    fn invalid_suffix(&mut self, suffix: &str, suffix_start: usize, kind: &'static str) -> TokenData {
+        self.errors.push(LexerError::InvalidSuffix {
+            suffix: suffix.to_string(),
+            kind,
+            trace: self.file.trace(suffix_start..self.index),
+        });
+        TokenData::IntegerLiteral(0)
+    }
+
+    /// Parses the digits of an integer literal, reporting literals that do not fit into their type
+    fn parse_integer<T: TryFrom<u128> + Default>(&mut self, digits: &str, radix: u32, ty: &'static str, start: usize) -> T {
+        let value = u128::from_str_radix(digits, radix).ok().and_then(|value| T::try_from(value).ok());
+
+        // literals with invalid or missing digits were already reported
+        if value.is_none() && !digits.is_empty() && digits.chars().all(|c| c.is_digit(radix)) {
+            self.out_of_range(ty, start);
+        }
+
+        value.unwrap_or_default()
+    }
+
+    fn parse_float<T: FromStr + Default + Copy + Into<f64>>(&mut self, digits: &str, ty: &'static str, start: usize) -> T {
+        match digits.parse::<T>() {
+            Ok(value) if value.into().is_finite() => value,
+            _ => {
+                self.out_of_range(ty, start);
+                T::default()
+            }
+        }
+    }
+
+    fn out_of_range(&mut self, ty: &'static str, start: usize) {
+        self.errors.push(LexerError::LiteralOutOfRange {
+            literal: self.file.source[start..self.index].to_string(),
+            ty,
+            trace: self.file.trace(start..self.index),
+        });
     }
 
     pub fn operator(&mut self) -> Option<Token> {
@@ -272,11 +662,13 @@ impl Lexer {
             '~' => E::BitNot,
             '.' => E::Dot,
             ',' => E::Comma,
+            ';' => E::Semicolon,
             ':' => E::Colon,
             _ => return None
         };
+        let trace = self.trace(1);
         self.advance();
-        Some(Token(TokenData::Operator(op), self.trace(1)))
+        Some(Token(TokenData::Operator(op), trace))
     }
 
     pub fn dual_operator(&mut self) -> Option<Token> {
@@ -304,6 +696,7 @@ impl Lexer {
             "::" => E::DoubleColon,
             "=>" => E::ThickRightArrow,
             "->" => E::ThinRightArrow,
+            ".." => E::DotDot,
 
             _ => return None
         };
@@ -323,6 +716,7 @@ impl Lexer {
             ">>=" => E::BitShiftRightAssign,
             "&&=" => E::AndAssign,
             "||=" => E::OrAssign,
+            "..=" => E::DotDotEquals,
             _ => return None
         };
         let trace = self.trace(3);
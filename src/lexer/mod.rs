@@ -1,16 +1,19 @@
-use std::rc::Rc;
 use crate::file::source_file::SourceFile;
 
+use crate::lexer::error::LexerError;
 use crate::lexer::lexer::Lexer;
-use crate::lexer::token::{Token};
+use crate::lexer::token::TokenStream;
 
+#[allow(clippy::module_inception)]
 mod lexer;
 pub mod token;
 pub mod keyword;
+pub mod error;
 
 #[cfg(test)]
 mod test;
 
-pub fn tokenize(file: Rc<SourceFile>) -> Vec<Token> {
+/// Splits the file into tokens, characters that could not be lexed are reported and skipped
+pub fn tokenize(file: &SourceFile) -> (TokenStream, Vec<LexerError>) {
     Lexer::new(file).tokenize()
-}
\ No newline at end of file
+}
@@ -1,15 +1,16 @@
 use crate::file::identifier::Identifier;
 use crate::file::source_file::SourceFile;
 
-use crate::lexer::token::{Operator, Token, TokenData};
+use crate::lexer::error::LexerError;
+use crate::lexer::token::{FormatSegment, Operator, Token, TokenData};
 
 #[test]
 pub fn identifier() {
     let file = SourceFile::new(r#"
         me _wh_en__ the
-    "#).rc();
+    "#);
 
-    let tokens: Vec<TokenData> = crate::lexer::tokenize(file).into_iter().map(|Token(f, _)| f).collect();
+    let tokens: Vec<TokenData> = crate::lexer::tokenize(&file).0.into_iter().map(|Token(f, _)| f).collect();
 
     assert_eq!(tokens, vec![
         TokenData::Identifier(Identifier::from("me")),
@@ -23,9 +24,9 @@ pub fn identifier() {
 pub fn bool() {
     let file = SourceFile::new(r#"
         true false false true true false true
-    "#).rc();
+    "#);
 
-    let tokens: Vec<TokenData> = crate::lexer::tokenize(file).into_iter().map(|Token(f, _)| f).collect();
+    let tokens: Vec<TokenData> = crate::lexer::tokenize(&file).0.into_iter().map(|Token(f, _)| f).collect();
 
     assert_eq!(tokens, vec![
         TokenData::BoolLiteral(true),
@@ -48,9 +49,9 @@ pub fn test_operator() {
     : && || << >> :: %= ^= &= *=
     -= += /= <<= >>= &&= ||= <= >= != == . ,
     => ->
-    "#).rc();
+    "#);
 
-    let tokens: Vec<TokenData> = crate::lexer::tokenize(file).into_iter().map(|Token(f, _)| f).collect();
+    let tokens: Vec<TokenData> = crate::lexer::tokenize(&file).0.into_iter().map(|Token(f, _)| f).collect();
     let mut expect = [
         E::CurlyOpen,
         E::CurlyClose,
@@ -96,7 +97,7 @@ pub fn test_operator() {
         E::Comma,
         E::ThickRightArrow,
         E::ThinRightArrow,
-    ].map(|o| TokenData::Operator(o)).into_iter().collect::<Vec<TokenData>>();
+    ].map(TokenData::Operator).into_iter().collect::<Vec<TokenData>>();
     expect.push(TokenData::EOF);
     assert_eq!(tokens, expect)
 }
@@ -105,8 +106,8 @@ pub fn test_operator() {
 fn string() {
     let file = SourceFile::new(r#"
         "me when the \" ""yuh"
-    "#).rc();
-    let tokens: Vec<TokenData> = crate::lexer::tokenize(file).into_iter().map(|Token(f, _)| f).collect();
+    "#);
+    let tokens: Vec<TokenData> = crate::lexer::tokenize(&file).0.into_iter().map(|Token(f, _)| f).collect();
 
     assert_eq!(tokens, vec![
         TokenData::StringLiteral("me when the \" ".into()),
@@ -119,12 +120,12 @@ fn string() {
 fn number() {
     let file = SourceFile::new(r#"
         1 4 2.0. 9L 2.f 10.0d 0.1f
-    "#).rc();
-    let tokens: Vec<TokenData> = crate::lexer::tokenize(file).into_iter().map(|Token(f, _)| f).collect();
+    "#);
+    let tokens: Vec<TokenData> = crate::lexer::tokenize(&file).0.into_iter().map(|Token(f, _)| f).collect();
 
     assert_eq!(tokens, vec![
-        TokenData::I32Literal(1),
-        TokenData::I32Literal(4),
+        TokenData::IntegerLiteral(1),
+        TokenData::IntegerLiteral(4),
         TokenData::F32Literal(2.0),
         TokenData::Operator(Operator::Dot),
         TokenData::I64Literal(9),
@@ -139,8 +140,8 @@ fn number() {
 fn test_macro() {
     let file = SourceFile::new(r#"
         bruh moment.println!("huh")
-    "#).rc();
-    let tokens: Vec<TokenData> = crate::lexer::tokenize(file).into_iter().map(|Token(f, _)| f).collect();
+    "#);
+    let tokens: Vec<TokenData> = crate::lexer::tokenize(&file).0.into_iter().map(|Token(f, _)| f).collect();
 
     assert_eq!(tokens, vec![
         TokenData::Identifier("bruh".into()),
@@ -152,4 +153,323 @@ fn test_macro() {
         TokenData::Operator(Operator::ParenClose),
         TokenData::EOF,
     ]);
-}
\ No newline at end of file
+}
+
+#[test]
+fn unknown_character() {
+    let file = SourceFile::new("a @ b # c");
+    let (tokens, errors) = crate::lexer::tokenize(&file);
+    let tokens: Vec<TokenData> = tokens.into_iter().map(|Token(f, _)| f).collect();
+
+    assert_eq!(tokens, vec![
+        TokenData::Identifier("a".into()),
+        TokenData::Identifier("b".into()),
+        TokenData::Identifier("c".into()),
+        TokenData::EOF,
+    ]);
+
+    let codes: Vec<&str> = errors.iter().map(LexerError::code).collect();
+    assert_eq!(codes, vec!["L0001", "L0001"]);
+    assert_eq!(errors[0].trace().range(), 2..3);
+    assert_eq!(errors[1].trace().range(), 6..7);
+}
+
+#[test]
+fn block_comment() {
+    let file = SourceFile::new("a /* b * / */ c /* d");
+    let (tokens, errors) = crate::lexer::tokenize(&file);
+    let tokens: Vec<TokenData> = tokens.into_iter().map(|Token(f, _)| f).collect();
+
+    assert_eq!(tokens, vec![
+        TokenData::Identifier("a".into()),
+        TokenData::Identifier("c".into()),
+        TokenData::EOF,
+    ]);
+    assert_eq!(errors.len(), 1);
+    assert!(matches!(errors[0], LexerError::UnterminatedComment(_)));
+    assert_eq!(errors[0].trace().range(), 16..18);
+}
+
+#[test]
+fn unterminated_string() {
+    let file = SourceFile::new(r#"x "abc\"#);
+    let (tokens, errors) = crate::lexer::tokenize(&file);
+    let tokens: Vec<TokenData> = tokens.into_iter().map(|Token(f, _)| f).collect();
+
+    assert_eq!(tokens, vec![
+        TokenData::Identifier("x".into()),
+        TokenData::StringLiteral("abc".into()),
+        TokenData::EOF,
+    ]);
+    assert_eq!(errors.len(), 1);
+    assert!(matches!(errors[0], LexerError::UnterminatedString(_)));
+    assert_eq!(errors[0].trace().range(), 2..3);
+}
+
+#[test]
+fn literal_out_of_range() {
+    let file = SourceFile::new("2147483647 2147483648 2147483648i32 99999999999999999999L 18446744073709551616");
+    let (tokens, errors) = crate::lexer::tokenize(&file);
+    let tokens: Vec<TokenData> = tokens.into_iter().map(|Token(f, _)| f).collect();
+
+    // unsuffixed literals are checked against the type they get during validation
+    assert_eq!(tokens, vec![
+        TokenData::IntegerLiteral(i32::MAX as u64),
+        TokenData::IntegerLiteral(2147483648),
+        TokenData::I32Literal(0),
+        TokenData::I64Literal(0),
+        TokenData::IntegerLiteral(0),
+        TokenData::EOF,
+    ]);
+
+    let messages: Vec<String> = errors.iter().map(LexerError::message).collect();
+    assert_eq!(messages, vec![
+        "literal `2147483648i32` does not fit into the type `i32`",
+        "literal `99999999999999999999L` does not fit into the type `i64`",
+        "literal `18446744073709551616` does not fit into the type `u64`",
+    ]);
+    assert_eq!(errors[1].trace().range(), 36..57);
+}
+
+#[test]
+fn unicode_identifier() {
+    let file = SourceFile::new("größe ñ_1 日本 _é");
+    let (tokens, errors) = crate::lexer::tokenize(&file);
+
+    assert_eq!(errors, vec![]);
+    let tokens: Vec<(TokenData, std::ops::Range<usize>)> = tokens
+        .into_iter()
+        .map(|Token(f, trace)| (f, trace.range()))
+        .collect();
+
+    assert_eq!(tokens, vec![
+        (TokenData::Identifier("größe".into()), 0..7),
+        (TokenData::Identifier("ñ_1".into()), 8..12),
+        (TokenData::Identifier("日本".into()), 13..19),
+        (TokenData::Identifier("_é".into()), 20..23),
+        (TokenData::EOF, 23..23),
+    ]);
+}
+
+#[test]
+fn byte_offsets_after_multibyte() {
+    let file = SourceFile::new("\"héllo 🦆\" + 1 → x");
+    let (tokens, errors) = crate::lexer::tokenize(&file);
+    let tokens: Vec<(TokenData, std::ops::Range<usize>)> = tokens
+        .into_iter()
+        .map(|Token(f, trace)| (f, trace.range()))
+        .collect();
+
+    assert_eq!(tokens, vec![
+        (TokenData::StringLiteral("héllo 🦆".into()), 0..13),
+        (TokenData::Operator(Operator::Plus), 14..15),
+        (TokenData::IntegerLiteral(1), 16..17),
+        (TokenData::Identifier("x".into()), 22..23),
+        (TokenData::EOF, 23..23),
+    ]);
+
+    assert_eq!(errors.len(), 1);
+    assert!(matches!(errors[0], LexerError::UnknownCharacter('→', _)));
+    assert_eq!(errors[0].trace().range(), 18..21);
+}
+
+#[test]
+fn number_prefixes_and_suffixes() {
+    let file = SourceFile::new(r#"
+        0xff 0o17 0b1010_1010 1_000_000 42u64 3usize 7u32 9i64 -1i32
+        1.5f64 2.5f32 1e3 2.5E-2f64 1..2 1..=2 0xFFu64
+    "#);
+    let (tokens, errors) = crate::lexer::tokenize(&file);
+    let tokens: Vec<TokenData> = tokens.into_iter().map(|Token(f, _)| f).collect();
+
+    assert_eq!(errors, vec![]);
+    assert_eq!(tokens, vec![
+        TokenData::IntegerLiteral(255),
+        TokenData::IntegerLiteral(15),
+        TokenData::IntegerLiteral(170),
+        TokenData::IntegerLiteral(1_000_000),
+        TokenData::U64Literal(42),
+        TokenData::UsizeLiteral(3),
+        TokenData::U32Literal(7),
+        TokenData::I64Literal(9),
+        TokenData::Operator(Operator::Minus),
+        TokenData::I32Literal(1),
+        TokenData::F64Literal(1.5),
+        TokenData::F32Literal(2.5),
+        TokenData::F32Literal(1000.),
+        TokenData::F64Literal(0.025),
+        TokenData::IntegerLiteral(1),
+        TokenData::Operator(Operator::DotDot),
+        TokenData::IntegerLiteral(2),
+        TokenData::IntegerLiteral(1),
+        TokenData::Operator(Operator::DotDotEquals),
+        TokenData::IntegerLiteral(2),
+        TokenData::U64Literal(255),
+        TokenData::EOF,
+    ]);
+}
+
+#[test]
+fn method_call_on_number() {
+    let file = SourceFile::new("1.foo() 2.5.max(1) 3.f");
+    let (tokens, errors) = crate::lexer::tokenize(&file);
+    let tokens: Vec<TokenData> = tokens.into_iter().map(|Token(f, _)| f).collect();
+
+    assert_eq!(errors, vec![]);
+    assert_eq!(tokens, vec![
+        TokenData::IntegerLiteral(1),
+        TokenData::Operator(Operator::Dot),
+        TokenData::Identifier("foo".into()),
+        TokenData::Operator(Operator::ParenOpen),
+        TokenData::Operator(Operator::ParenClose),
+        TokenData::F32Literal(2.5),
+        TokenData::Operator(Operator::Dot),
+        TokenData::Identifier("max".into()),
+        TokenData::Operator(Operator::ParenOpen),
+        TokenData::IntegerLiteral(1),
+        TokenData::Operator(Operator::ParenClose),
+        TokenData::F32Literal(3.),
+        TokenData::EOF,
+    ]);
+}
+
+#[test]
+fn invalid_numbers() {
+    let file = SourceFile::new("12u8 1.5u32 0b102 0x 4294967296u32 1e999f64");
+    let (_, errors) = crate::lexer::tokenize(&file);
+
+    let messages: Vec<String> = errors.iter().map(LexerError::message).collect();
+    assert_eq!(messages, vec![
+        "invalid suffix `u8` for integer literal",
+        "invalid suffix `u32` for float literal",
+        "invalid digit '2' in binary literal",
+        "no valid digits found for number",
+        "literal `4294967296u32` does not fit into the type `u32`",
+        "literal `1e999f64` does not fit into the type `f64`",
+    ]);
+
+    let traces: Vec<_> = errors.iter().map(|err| err.trace().range()).collect();
+    assert_eq!(traces, vec![2..4, 8..11, 16..17, 18..20, 21..34, 35..43]);
+}
+
+#[test]
+fn escapes() {
+    let file = SourceFile::new(r##""a\n\t\r\0\\\'\"\u{1F986}b" '\n' 'x' '\u{e9}' '"' r"raw\n" r#"has "quotes""#"##);
+    let (tokens, errors) = crate::lexer::tokenize(&file);
+    let tokens: Vec<TokenData> = tokens.into_iter().map(|Token(f, _)| f).collect();
+
+    assert_eq!(errors, vec![]);
+    assert_eq!(tokens, vec![
+        TokenData::StringLiteral("a\n\t\r\0\\'\"🦆b".into()),
+        TokenData::CharLiteral('\n'),
+        TokenData::CharLiteral('x'),
+        TokenData::CharLiteral('é'),
+        TokenData::CharLiteral('"'),
+        TokenData::StringLiteral("raw\\n".into()),
+        TokenData::StringLiteral("has \"quotes\"".into()),
+        TokenData::EOF,
+    ]);
+}
+
+#[test]
+fn invalid_escapes() {
+    let file = SourceFile::new(r#""a\qb\u{110000}\u41" '' 'ab' r"x"#);
+    let (tokens, errors) = crate::lexer::tokenize(&file);
+    let tokens: Vec<TokenData> = tokens.into_iter().map(|Token(f, _)| f).collect();
+
+    assert_eq!(tokens, vec![
+        TokenData::StringLiteral("ab41".into()),
+        TokenData::CharLiteral('\0'),
+        TokenData::CharLiteral('a'),
+        TokenData::StringLiteral("x".into()),
+        TokenData::EOF,
+    ]);
+
+    let codes: Vec<&str> = errors.iter().map(LexerError::code).collect();
+    assert_eq!(codes, vec!["L0011", "L0012", "L0012", "L0009", "L0010", "L0002"]);
+
+    let traces: Vec<_> = errors.iter().map(|err| err.trace().range()).collect();
+    assert_eq!(traces, vec![2..4, 5..15, 15..17, 21..23, 24..28, 29..31]);
+}
+
+#[test]
+fn format_string() {
+    let file = SourceFile::new(r#""a {x + 1} b {f("}}")} {} {{c}}" "plain {{}}""#);
+    let (tokens, errors) = crate::lexer::tokenize(&file);
+    assert_eq!(errors, vec![]);
+
+    let strip = |tokens: &[Token]| -> Vec<TokenData> { tokens.iter().map(|Token(f, _)| f.clone()).collect() };
+
+    let TokenData::FormatString(segments) = &tokens[0].0 else {
+        panic!("Expected a format string, got {}", tokens[0].0);
+    };
+
+    match segments.as_slice() {
+        [
+            FormatSegment::Literal(a),
+            FormatSegment::Interpolation(sum),
+            FormatSegment::Literal(b),
+            FormatSegment::Interpolation(call),
+            FormatSegment::Literal(space),
+            FormatSegment::Placeholder(placeholder),
+            FormatSegment::Literal(c),
+        ] => {
+            assert_eq!((a.as_str(), b.as_str(), space.as_str(), c.as_str()), ("a ", " b ", " ", " {c}"));
+            assert_eq!(strip(sum), vec![
+                TokenData::Identifier("x".into()),
+                TokenData::Operator(Operator::Plus),
+                TokenData::IntegerLiteral(1),
+                TokenData::EOF,
+            ]);
+            assert_eq!(strip(call), vec![
+                TokenData::Identifier("f".into()),
+                TokenData::Operator(Operator::ParenOpen),
+                TokenData::StringLiteral("}".into()),
+                TokenData::Operator(Operator::ParenClose),
+                TokenData::EOF,
+            ]);
+            assert_eq!(sum[0].trace().range(), 4..5);
+            assert_eq!(placeholder.range(), 23..25);
+        }
+        other => panic!("Unexpected segments {other:?}"),
+    }
+
+    assert_eq!(tokens[1].0, TokenData::StringLiteral("plain {}".into()));
+}
+
+#[test]
+fn unterminated_interpolation() {
+    let file = SourceFile::new(r#""a {x" "b } c""#);
+    let (tokens, errors) = crate::lexer::tokenize(&file);
+
+    assert_eq!(tokens.len(), 3);
+    let codes: Vec<&str> = errors.iter().map(LexerError::code).collect();
+    assert_eq!(codes, vec!["L0013", "L0014"]);
+    assert_eq!(errors[0].trace().range(), 3..4);
+    assert_eq!(errors[1].trace().range(), 10..11);
+}
+
+#[test]
+fn line_breaks() {
+    let file = SourceFile::new("a; b\n-c // comment\n(d) /* two\nlines */ e");
+    let (tokens, errors) = crate::lexer::tokenize(&file);
+    assert_eq!(errors, vec![]);
+
+    let tokens: Vec<(TokenData, bool)> = tokens
+        .iter()
+        .enumerate()
+        .map(|(i, Token(f, _))| (f.clone(), tokens.starts_line(i)))
+        .collect();
+    assert_eq!(tokens, vec![
+        (TokenData::Identifier("a".into()), false),
+        (TokenData::Operator(Operator::Semicolon), false),
+        (TokenData::Identifier("b".into()), false),
+        (TokenData::Operator(Operator::Minus), true),
+        (TokenData::Identifier("c".into()), false),
+        (TokenData::Operator(Operator::ParenOpen), true),
+        (TokenData::Identifier("d".into()), false),
+        (TokenData::Operator(Operator::ParenClose), false),
+        (TokenData::Identifier("e".into()), true),
+        (TokenData::EOF, false),
+    ]);
+}
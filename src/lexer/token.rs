@@ -1,10 +1,11 @@
+use std::fmt::{Display, Formatter};
 use std::ops::{Deref, DerefMut};
 use serde::{Deserialize, Serialize};
 use crate::file::identifier::Identifier;
 use crate::file::trace::Trace;
 use crate::lexer::keyword::Keyword;
 
-#[derive(Debug, PartialEq, Clone)]
+#[derive(Debug, PartialEq, Clone, Serialize, Deserialize)]
 pub struct Token(pub TokenData, pub Trace);
 
 impl Token {
@@ -34,21 +35,112 @@ impl DerefMut for Token {
     }
 }
 
+/// Tokens of a file together with the line breaks between them, which the parser needs to find
+/// where statements end
+#[derive(Debug, PartialEq, Clone, Default)]
+pub struct TokenStream {
+    tokens: Vec<Token>,
+
+    /// Whether a line break separates each token from the one before it
+    line_breaks: Vec<bool>,
+}
+
+impl TokenStream {
+    pub fn push(&mut self, token: Token, line_break: bool) {
+        self.tokens.push(token);
+        self.line_breaks.push(line_break);
+    }
+
+    /// Inserts a token on the same line as the one before it
+    pub fn insert(&mut self, index: usize, token: Token) {
+        self.tokens.insert(index, token);
+        self.line_breaks.insert(index, false);
+    }
+
+    /// Whether a line break separates the token at the index from the one before it
+    pub fn starts_line(&self, index: usize) -> bool {
+        self.line_breaks.get(index).copied().unwrap_or(false)
+    }
+}
+
+/// Tokens that do not come from a file directly, like the arguments of a macro call, are all
+/// treated as being on one line
+impl From<Vec<Token>> for TokenStream {
+    fn from(tokens: Vec<Token>) -> Self {
+        let line_breaks = vec![false; tokens.len()];
+        Self { tokens, line_breaks }
+    }
+}
+
+impl Deref for TokenStream {
+    type Target = [Token];
+
+    fn deref(&self) -> &Self::Target {
+        &self.tokens
+    }
+}
+
+impl DerefMut for TokenStream {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.tokens
+    }
+}
+
+impl IntoIterator for TokenStream {
+    type Item = Token;
+    type IntoIter = std::vec::IntoIter<Token>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.tokens.into_iter()
+    }
+}
+
+impl<'a> IntoIterator for &'a TokenStream {
+    type Item = &'a Token;
+    type IntoIter = std::slice::Iter<'a, Token>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.tokens.iter()
+    }
+}
+
 #[derive(Serialize, Deserialize, Debug, PartialEq, Clone)]
+#[allow(clippy::upper_case_acronyms)]
 pub enum TokenData {
     MacroIdentifier(Identifier),
     Identifier(Identifier),
     Operator(Operator),
     Keyword(Keyword),
     StringLiteral(String),
+
+    /// String literal containing interpolated expressions, eg. `"x is {x}"`
+    FormatString(Vec<FormatSegment>),
+    CharLiteral(char),
     BoolLiteral(bool),
     F32Literal(f32),
     F64Literal(f64),
+
+    /// Integer literal without a suffix, it takes its type from where it is used
+    IntegerLiteral(u64),
     I32Literal(i32),
     I64Literal(i64),
+    U32Literal(u32),
+    U64Literal(u64),
+    UsizeLiteral(u64),
     EOF,
 }
 
+#[derive(Serialize, Deserialize, Debug, PartialEq, Clone)]
+pub enum FormatSegment {
+    Literal(String),
+
+    /// Empty braces `{}`, filled in by the arguments of a formatting macro
+    Placeholder(Trace),
+
+    /// Tokens of the expression between the braces, always ends with an EOF token
+    Interpolation(Vec<Token>),
+}
+
 #[derive(Serialize, Deserialize, Debug, PartialEq, Copy, Clone, Hash)]
 pub enum Operator {
     CurlyOpen,
@@ -100,5 +192,92 @@ pub enum Operator {
     Equals,
 
     Dot,
+    DotDot,
+    DotDotEquals,
     Comma,
-}
\ No newline at end of file
+    Semicolon,
+}
+impl Operator {
+    pub fn as_str(&self) -> &'static str {
+        use Operator as E;
+        match self {
+            E::CurlyOpen => "{",
+            E::CurlyClose => "}",
+            E::ParenOpen => "(",
+            E::ParenClose => ")",
+            E::BracketOpen => "[",
+            E::BracketClose => "]",
+            E::BitNot => "~",
+            E::Not => "!",
+            E::Mod => "%",
+            E::Xor => "^",
+            E::BitAnd => "&",
+            E::Multiply => "*",
+            E::Minus => "-",
+            E::Plus => "+",
+            E::Divide => "/",
+            E::BitOr => "|",
+            E::Assign => "=",
+            E::Less => "<",
+            E::Greater => ">",
+            E::Colon => ":",
+            E::And => "&&",
+            E::Or => "||",
+            E::BitShiftLeft => "<<",
+            E::BitShiftRight => ">>",
+            E::DoubleColon => "::",
+            E::ThickRightArrow => "=>",
+            E::ThinRightArrow => "->",
+            E::ModAssigns => "%=",
+            E::XorAssign => "^=",
+            E::BitAndAssign => "&=",
+            E::MultiplyAssign => "*=",
+            E::MinusAssign => "-=",
+            E::PlusAssign => "+=",
+            E::DivideAssign => "/=",
+            E::BitShiftLeftAssign => "<<=",
+            E::BitShiftRightAssign => ">>=",
+            E::AndAssign => "&&=",
+            E::OrAssign => "||=",
+            E::LessEquals => "<=",
+            E::GreaterEquals => ">=",
+            E::NotEquals => "!=",
+            E::Equals => "==",
+            E::Dot => ".",
+            E::DotDot => "..",
+            E::DotDotEquals => "..=",
+            E::Comma => ",",
+            E::Semicolon => ";",
+        }
+    }
+}
+
+impl Display for Operator {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl Display for TokenData {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            TokenData::MacroIdentifier(ident) => write!(f, "macro `{ident}`"),
+            TokenData::Identifier(ident) => write!(f, "identifier `{ident}`"),
+            TokenData::Operator(op) => write!(f, "`{op}`"),
+            TokenData::Keyword(keyword) => write!(f, "keyword `{keyword}`"),
+            TokenData::StringLiteral(s) => write!(f, "string literal {s:?}"),
+            TokenData::FormatString(_) => f.write_str("format string"),
+            TokenData::CharLiteral(c) => write!(f, "character literal {c:?}"),
+            TokenData::BoolLiteral(b) => write!(f, "`{b}`"),
+            TokenData::F32Literal(n) => write!(f, "float literal `{n}`"),
+            TokenData::F64Literal(n) => write!(f, "double literal `{n}`"),
+            TokenData::IntegerLiteral(n) => write!(f, "integer literal `{n}`"),
+            TokenData::I32Literal(n) => write!(f, "integer literal `{n}`"),
+            TokenData::I64Literal(n) => write!(f, "integer literal `{n}`"),
+            TokenData::U32Literal(n) => write!(f, "integer literal `{n}`"),
+            TokenData::U64Literal(n) => write!(f, "integer literal `{n}`"),
+            TokenData::UsizeLiteral(n) => write!(f, "integer literal `{n}`"),
+            TokenData::EOF => f.write_str("end of file"),
+        }
+    }
+}
@@ -1,10 +1,14 @@
+use std::process::ExitCode;
+use clap::Parser;
+
 mod lexer;
 mod file;
 mod parser;
 mod ir;
 mod cmd;
+mod diagnostic;
 mod validation;
 
-fn main() -> Result<(), Box<dyn std::error::Error>> {
-    Ok(())
+fn main() -> ExitCode {
+    cmd::Cli::parse().execute()
 }
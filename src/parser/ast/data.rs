@@ -1,19 +1,33 @@
 use serde::{Deserialize, Serialize};
 use crate::file::identifier::Identifier;
+use crate::file::trace::Trace;
 
 #[derive(Debug, PartialEq, Serialize, Deserialize, Hash)]
 pub enum UnvalidatedType {
-    Type(Identifier),
+    Type(Identifier, Trace),
     Reference(Box<UnvalidatedType>),
     Array(Box<UnvalidatedType>),
     Template {
         base: Identifier,
         template_arguments: Vec<UnvalidatedType>,
+        trace: Trace,
     },
     Implicit,
     Unit,
 }
 
+impl UnvalidatedType {
+    /// Trace of the named type, the one a reference or array type refers to. Implicit and unit
+    /// types are not written out and have none
+    pub fn trace(&self) -> Option<Trace> {
+        match self {
+            UnvalidatedType::Type(_, trace) | UnvalidatedType::Template { trace, .. } => Some(*trace),
+            UnvalidatedType::Reference(ty) | UnvalidatedType::Array(ty) => ty.trace(),
+            UnvalidatedType::Implicit | UnvalidatedType::Unit => None,
+        }
+    }
+}
+
 #[derive(Debug, PartialEq, Serialize, Deserialize, Hash)]
 pub struct UnvalidatedVariableInfo {
     pub ident: Identifier,
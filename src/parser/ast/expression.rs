@@ -1,11 +1,14 @@
-use crate::file::identifier::{Identifier, Namespace};
+use serde::Serialize;
+use crate::file::identifier::Identifier;
 use crate::file::trace::Trace;
+use crate::lexer::token::Token;
 use crate::parser::ast::data::UnvalidatedType;
 use crate::parser::ast::function::UnvalidatedFunctionExpression;
 use crate::parser::ast::operations::{BinaryOperation, UnaryOperator};
+use crate::parser::ast::pattern::UnvalidatedMatchArm;
 use crate::parser::ast::UnvalidatedSymbol;
 
-#[derive(Debug, PartialEq)]
+#[derive(Debug, PartialEq, Serialize)]
 pub enum UnvalidatedExpression {
     // if else chains can evaluated to an expression
     IfElse {
@@ -16,14 +19,40 @@ pub enum UnvalidatedExpression {
     },
     Scope(Vec<UnvalidatedFunctionExpression>, Trace),
 
+    /// `match value { pattern if guard => body }`, arms are tried from top to bottom
+    Match {
+        scrutinee: Box<UnvalidatedExpression>,
+        arms: Vec<UnvalidatedMatchArm>,
+        trace: Trace,
+    },
+
     BoolLiteral(bool, Trace),
     F32Literal(f32, Trace),
     F64Literal(f64, Trace),
+
+    /// Integer literal without a suffix, its range is checked once its type is known
+    IntegerLiteral(u64, Trace),
     I32Literal(i32, Trace),
     I64Literal(i64, Trace),
+    U32Literal(u32, Trace),
+    U64Literal(u64, Trace),
+    UsizeLiteral(u64, Trace),
     StringLiteral(String, Trace),
+    FormatString {
+        segments: Vec<UnvalidatedFormatSegment>,
+        trace: Trace,
+    },
+    CharLiteral(char, Trace),
 
     Parenthetical(Box<UnvalidatedExpression>, Trace),
+
+    /// `start..end` excludes the end, `start..=end` includes it
+    Range {
+        start: Box<UnvalidatedExpression>,
+        end: Box<UnvalidatedExpression>,
+        inclusive: bool,
+        trace: Trace,
+    },
     Binary {
         /// Left hand Side
         lhs: Box<UnvalidatedExpression>,
@@ -43,6 +72,15 @@ pub enum UnvalidatedExpression {
         ty: UnvalidatedType,
         trace: Trace,
     },
+    /// Invocation of a built-in macro like `println!("{}", x)`, replaced by the expansion phase
+    /// before validation
+    MacroCall {
+        name: Identifier,
+
+        /// Tokens between the delimiters of the call, they are balanced and end with an EOF token
+        arguments: Vec<Token>,
+        trace: Trace,
+    },
     FunctionCall {
         symbol: UnvalidatedSymbol,
         arguments: Vec<UnvalidatedExpression>,
@@ -52,22 +90,68 @@ pub enum UnvalidatedExpression {
         symbol: UnvalidatedSymbol,
         trace: Trace,
     },
+    /// `make T { field: value }` constructs a structure in place, `alloc T { .. }` constructs it
+    /// on the heap and evaluates to a reference to it. `make T::Variant { .. }` constructs a
+    /// struct-like enum variant
+    Construct {
+        ty: UnvalidatedType,
+        variant: Option<Identifier>,
+        fields: Vec<UnvalidatedFieldInit>,
+        heap: bool,
+        trace: Trace,
+    },
+    /// `object.field`
     ObjectProperty {
         object: Box<UnvalidatedExpression>,
         identifier: Identifier,
         trace: Trace,
     },
+
+    /// `object.method(arguments)`, the object is passed as the receiver of the method
+    MethodCall {
+        object: Box<UnvalidatedExpression>,
+        method: Identifier,
+        arguments: Vec<UnvalidatedExpression>,
+        trace: Trace,
+    },
+
+    /// `object[index]`
+    Index {
+        object: Box<UnvalidatedExpression>,
+        index: Box<UnvalidatedExpression>,
+        trace: Trace,
+    },
+
+    /// Placeholder for an expression that failed to parse
+    Error(Trace),
+}
+
+/// Field of a construction, shorthand fields `{ x }` take the value of the variable of the same name
+#[derive(Debug, PartialEq, Serialize)]
+pub struct UnvalidatedFieldInit {
+    pub name: Identifier,
+    pub value: UnvalidatedExpression,
+    pub trace: Trace,
+}
+
+#[derive(Debug, PartialEq, Serialize)]
+pub enum UnvalidatedFormatSegment {
+    Literal(String),
+
+    /// `{}` filled in by an argument of a formatting macro
+    Placeholder(Trace),
+    Expression(UnvalidatedExpression),
 }
 
-impl Into<UnvalidatedFunctionExpression> for UnvalidatedExpression {
-    fn into(self) -> UnvalidatedFunctionExpression {
-        UnvalidatedFunctionExpression::Expression(self)
+impl From<UnvalidatedExpression> for UnvalidatedFunctionExpression {
+    fn from(value: UnvalidatedExpression) -> Self {
+        UnvalidatedFunctionExpression::Expression(value)
     }
 }
 
 impl UnvalidatedExpression {
     pub fn trace(&self) -> Trace {
-        (match self {
+        *(match self {
             UnvalidatedExpression::IfElse {
                 condition: _,
                 then: _,
@@ -75,19 +159,32 @@ impl UnvalidatedExpression {
                 trace
             } => trace,
             UnvalidatedExpression::Scope(_, trace) => trace,
+            UnvalidatedExpression::Match { trace, .. } => trace,
             UnvalidatedExpression::BoolLiteral(_, trace) => trace,
+            UnvalidatedExpression::IntegerLiteral(_, trace) => trace,
             UnvalidatedExpression::I32Literal(_, trace) => trace,
             UnvalidatedExpression::I64Literal(_, trace) => trace,
+            UnvalidatedExpression::U32Literal(_, trace) => trace,
+            UnvalidatedExpression::U64Literal(_, trace) => trace,
+            UnvalidatedExpression::UsizeLiteral(_, trace) => trace,
             UnvalidatedExpression::StringLiteral(_, trace) => trace,
+            UnvalidatedExpression::FormatString { trace, .. } => trace,
+            UnvalidatedExpression::CharLiteral(_, trace) => trace,
             UnvalidatedExpression::Parenthetical(_, trace) => trace,
+            UnvalidatedExpression::Range { trace, .. } => trace,
             UnvalidatedExpression::F32Literal(_, trace) => trace,
             UnvalidatedExpression::F64Literal(_, trace) => trace,
             UnvalidatedExpression::Binary { trace, .. } => trace,
             UnvalidatedExpression::FunctionCall { trace, .. } => trace,
+            UnvalidatedExpression::MacroCall { trace, .. } => trace,
             UnvalidatedExpression::VariableReference { trace, .. } => trace,
-            UnvalidatedExpression::ObjectProperty { .. } => todo!(),
+            UnvalidatedExpression::ObjectProperty { trace, .. } => trace,
+            UnvalidatedExpression::MethodCall { trace, .. } => trace,
+            UnvalidatedExpression::Index { trace, .. } => trace,
             UnvalidatedExpression::Cast { trace, .. } => trace,
+            UnvalidatedExpression::Construct { trace, .. } => trace,
             UnvalidatedExpression::Unary { trace, .. } => trace,
-        }).clone()
+            UnvalidatedExpression::Error(trace) => trace,
+        })
     }
 }
\ No newline at end of file
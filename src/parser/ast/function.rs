@@ -5,16 +5,44 @@ use crate::parser::ast::data::{UnvalidatedType, UnvalidatedVariableInfo};
 use crate::parser::ast::expression::UnvalidatedExpression;
 use crate::ir::visibility::Visibility;
 
+/// Name of the receiver argument of methods
+pub const RECEIVER: &str = "self";
+
+/// Type of the receiver, refers to the type an impl block is for or to the type implementing a
+/// trait
+pub const SELF_TYPE: &str = "Self";
+
 #[derive(Debug, PartialEq, Serialize, Deserialize, Hash)]
 pub struct UnvalidatedFunctionPrototype {
     pub name: Identifier,
+
+    /// Names of the type parameters, eg. `T` of `fun id<T>(value: T): T`
+    pub generics: Vec<Identifier>,
     pub arguments: Vec<UnvalidatedVariableInfo>,
+
+    /// [UnvalidatedType::Implicit] for `=> expression` bodies without a written return type
     pub returns: UnvalidatedType,
     pub visibility: Visibility,
 }
 
+impl UnvalidatedFunctionPrototype {
+    /// Whether the first argument is a `self`, `ref[self]` or `[self]` receiver
+    #[cfg(test)]
+    pub fn is_method(&self) -> bool {
+        self.arguments.first().is_some_and(|argument| argument.ident.as_str() == RECEIVER)
+    }
+}
+
+/// Function declared inside of an impl block
+#[derive(Debug, PartialEq, Serialize)]
+pub struct UnvalidatedFunction {
+    pub proto: UnvalidatedFunctionPrototype,
+    pub body: UnvalidatedFunctionExpression,
+    pub trace: Trace,
+}
+
 /// Expressions that can not be evaluated to a value
-#[derive(Debug, PartialEq)]
+#[derive(Debug, PartialEq, Serialize)]
 pub enum UnvalidatedFunctionExpression {
     // an single if can not evaluated to an expression
     If {
@@ -27,6 +55,13 @@ pub enum UnvalidatedFunctionExpression {
         then: Box<UnvalidatedExpression>,
         trace: Trace,
     },
+    /// `for variable in iterable { .. }`
+    For {
+        variable: Identifier,
+        iterable: UnvalidatedExpression,
+        then: Box<UnvalidatedExpression>,
+        trace: Trace,
+    },
     Let {
         variable: UnvalidatedVariableInfo,
         initial: Option<UnvalidatedExpression>,
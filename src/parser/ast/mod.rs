@@ -7,9 +7,12 @@ pub mod data;
 pub mod expression;
 pub mod top_level;
 pub mod r#struct;
+pub mod r#trait;
 pub mod operations;
+pub mod pattern;
 
 
+#[allow(dead_code)]
 #[derive(Serialize, Deserialize, Hash, PartialEq, Debug)]
 pub struct ParserTrace {
     token_range: Range<usize>,
@@ -1,8 +1,7 @@
 use serde::{Deserialize, Serialize};
 use crate::lexer::token::Operator;
-use crate::parser::ast::operations::UnaryOperator::Negate;
 
-#[derive(Debug, PartialEq, Serialize, Deserialize, Hash)]
+#[derive(Debug, PartialEq, Serialize, Deserialize, Hash, Clone, Copy)]
 pub enum BinaryOperation {
     Mod,
     Xor,
@@ -34,9 +33,14 @@ pub enum BinaryOperation {
     LesserOrEquals,
     GreaterOrEquals,
     NotEqual,
+
+    And,
+    Or,
+    BitShiftLeft,
+    BitShiftRight,
 }
 
-#[derive(Debug, PartialEq, Serialize, Deserialize, Hash)]
+#[derive(Debug, PartialEq, Serialize, Deserialize, Hash, Clone, Copy)]
 pub enum UnaryOperator {
     Ref,
     Deref,
@@ -45,6 +49,77 @@ pub enum UnaryOperator {
     BitNot,
 }
 
+impl BinaryOperation {
+    pub fn as_str(&self) -> &'static str {
+        use BinaryOperation as B;
+        match self {
+            B::Mod => "%",
+            B::Xor => "^",
+            B::BitAnd => "&",
+            B::Multiply => "*",
+            B::Minus => "-",
+            B::Plus => "+",
+            B::Divide => "/",
+            B::BitOr => "|",
+            B::Assign => "=",
+            B::Less => "<",
+            B::Greater => ">",
+            B::Colon => ":",
+            B::ModAssigns => "%=",
+            B::XorAssign => "^=",
+            B::BitAndAssign => "&=",
+            B::MultiplyAssign => "*=",
+            B::MinusAssign => "-=",
+            B::PlusAssign => "+=",
+            B::DivideAssign => "/=",
+            B::BitShiftLeftAssign => "<<=",
+            B::BitShiftRightAssign => ">>=",
+            B::AndAssign => "&&=",
+            B::OrAssign => "||=",
+            B::Equals => "==",
+            B::LesserOrEquals => "<=",
+            B::GreaterOrEquals => ">=",
+            B::NotEqual => "!=",
+            B::And => "&&",
+            B::Or => "||",
+            B::BitShiftLeft => "<<",
+            B::BitShiftRight => ">>",
+        }
+    }
+
+    /// Operator applied before assigning for compound assignments like `+=`, [None] for other
+    /// operators
+    pub fn compound(&self) -> Option<BinaryOperation> {
+        use BinaryOperation as B;
+        Some(match self {
+            B::ModAssigns => B::Mod,
+            B::XorAssign => B::Xor,
+            B::BitAndAssign => B::BitAnd,
+            B::MultiplyAssign => B::Multiply,
+            B::MinusAssign => B::Minus,
+            B::PlusAssign => B::Plus,
+            B::DivideAssign => B::Divide,
+            B::BitShiftLeftAssign => B::BitShiftLeft,
+            B::BitShiftRightAssign => B::BitShiftRight,
+            B::AndAssign => B::And,
+            B::OrAssign => B::Or,
+            _ => return None,
+        })
+    }
+}
+
+impl UnaryOperator {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            UnaryOperator::Ref => "ref",
+            UnaryOperator::Deref => "deref",
+            UnaryOperator::Negate => "-",
+            UnaryOperator::Not => "!",
+            UnaryOperator::BitNot => "~",
+        }
+    }
+}
+
 impl TryFrom<Operator> for UnaryOperator {
     type Error = ();
 
@@ -95,6 +170,11 @@ impl TryFrom<Operator> for BinaryOperation {
             E::LessEquals => B::LesserOrEquals,
             E::GreaterEquals => B::GreaterOrEquals,
             E::NotEquals => B::NotEqual,
+
+            E::And => B::And,
+            E::Or => B::Or,
+            E::BitShiftLeft => B::BitShiftLeft,
+            E::BitShiftRight => B::BitShiftRight,
             _ => return Err(())
         })
     }
@@ -0,0 +1,81 @@
+use serde::Serialize;
+use crate::file::identifier::Identifier;
+use crate::file::trace::Trace;
+use crate::parser::ast::expression::UnvalidatedExpression;
+use crate::parser::ast::UnvalidatedSymbol;
+
+#[derive(Debug, PartialEq, Serialize)]
+pub enum UnvalidatedPattern {
+    /// `_`, matches anything without binding it
+    Wildcard(Trace),
+
+    /// Binds the matched value to a new variable, eg. `v` or `mut v`.
+    /// A lone identifier can also name a unit enum variant like `None`, which is only known once
+    /// the pattern is validated against the type being matched
+    Binding {
+        ident: Identifier,
+        mutable: bool,
+        trace: Trace,
+    },
+
+    /// Bool, char, string or numeric literal, numbers may be negated
+    Literal(UnvalidatedExpression),
+
+    /// `a..b`, `a..=b`, `a..` or `..=b`
+    Range {
+        start: Option<Box<UnvalidatedExpression>>,
+        end: Option<Box<UnvalidatedExpression>>,
+        inclusive: bool,
+        trace: Trace,
+    },
+
+    /// Enum variant with tuple fields, eg. `Some(v)`, or a unit variant named by its path, eg. `Option::None`
+    Variant {
+        symbol: UnvalidatedSymbol,
+        fields: Vec<UnvalidatedPattern>,
+        trace: Trace,
+    },
+
+    /// Variant or structure with named fields, eg. `Shape::Rect { width, height: 0, .. }`
+    Struct {
+        symbol: UnvalidatedSymbol,
+        fields: Vec<UnvalidatedFieldPattern>,
+
+        /// Whether the remaining fields are ignored with `..`
+        rest: bool,
+        trace: Trace,
+    },
+
+    /// Patterns separated by `|`, matches if any of them does
+    Alternatives(Vec<UnvalidatedPattern>, Trace),
+}
+
+#[derive(Debug, PartialEq, Serialize)]
+pub struct UnvalidatedFieldPattern {
+    pub field: Identifier,
+
+    /// Pattern for the field, shorthand fields like `{ x }` bind the field to a variable of the same name
+    pub pattern: UnvalidatedPattern,
+}
+
+#[derive(Debug, PartialEq, Serialize)]
+pub struct UnvalidatedMatchArm {
+    pub pattern: UnvalidatedPattern,
+    pub guard: Option<UnvalidatedExpression>,
+    pub body: UnvalidatedExpression,
+    pub trace: Trace,
+}
+
+impl UnvalidatedPattern {
+    pub fn trace(&self) -> Trace {
+        match self {
+            UnvalidatedPattern::Wildcard(trace) => *trace,
+            UnvalidatedPattern::Binding { trace, .. } => *trace,
+            UnvalidatedPattern::Literal(literal) => literal.trace(),
+            UnvalidatedPattern::Range { trace, .. } => *trace,
+            UnvalidatedPattern::Variant { trace, .. } => *trace,
+            UnvalidatedPattern::Struct { trace, .. } => *trace,
+            UnvalidatedPattern::Alternatives(_, trace) => *trace,
+        }
+    }
+}
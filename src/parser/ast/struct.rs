@@ -1,4 +1,3 @@
-use either::Either;
 use serde::{Deserialize, Serialize};
 use crate::file::identifier::Identifier;
 use crate::parser::ast::data::UnvalidatedType;
@@ -7,6 +6,9 @@ use crate::ir::visibility::Visibility;
 #[derive(Debug, PartialEq, Serialize, Deserialize, Hash)]
 pub struct UnvalidatedStructPrototype {
     pub identifier: Identifier,
+
+    /// Names of the type parameters, eg. `T` of `Vec<T>`
+    pub generics: Vec<Identifier>,
     pub properties: Vec<UnvalidatedStructProperty>,
     pub visibility: Visibility,
 }
@@ -23,6 +25,16 @@ pub struct UnvalidatedProperty {
     pub ty: UnvalidatedType,
 }
 
+#[derive(Debug, PartialEq, Serialize, Deserialize, Hash)]
+pub struct UnvalidatedEnumPrototype {
+    pub identifier: Identifier,
+
+    /// Names of the type parameters, eg. `T` and `E` of `Result<T, E>`
+    pub generics: Vec<Identifier>,
+    pub variants: Vec<UnvalidatedEnumVariant>,
+    pub visibility: Visibility,
+}
+
 #[derive(Debug, PartialEq, Serialize, Deserialize, Hash)]
 pub struct UnvalidatedEnumVariant {
     pub name: Identifier,
@@ -1,12 +1,15 @@
-use crate::file::identifier::{Namespace};
+use serde::Serialize;
+use crate::file::identifier::Identifier;
 use crate::file::trace::Trace;
-use crate::parser::ast::data::{UnvalidatedVariableInfo};
+use crate::ir::visibility::Visibility;
+use crate::parser::ast::data::{UnvalidatedType, UnvalidatedVariableInfo};
 use crate::parser::ast::expression::UnvalidatedExpression;
-use crate::parser::ast::function::{UnvalidatedFunctionPrototype, UnvalidatedFunctionExpression};
-use crate::parser::ast::r#struct::{UnvalidatedEnumVariant, UnvalidatedStructPrototype};
+use crate::parser::ast::function::{UnvalidatedFunction, UnvalidatedFunctionExpression, UnvalidatedFunctionPrototype};
+use crate::parser::ast::r#struct::{UnvalidatedEnumPrototype, UnvalidatedStructPrototype};
+use crate::parser::ast::r#trait::UnvalidatedTraitPrototype;
 use crate::parser::ast::UnvalidatedSymbol;
 
-#[derive(Debug, PartialEq)]
+#[derive(Debug, PartialEq, Serialize)]
 pub enum UnvalidatedTopLevel {
     FunctionDefinition {
         proto: UnvalidatedFunctionPrototype,
@@ -17,18 +20,53 @@ pub enum UnvalidatedTopLevel {
         proto: UnvalidatedStructPrototype,
         trace: Trace,
     },
+    /// `impl Type { .. }` or `impl Trait for Type { .. }`, the functions are associated to the type
+    ImplBlock {
+        /// Type parameters introduced by `impl<T>`, usable in the target and in every function
+        generics: Vec<Identifier>,
+        target: UnvalidatedType,
+        implements: Option<UnvalidatedType>,
+        functions: Vec<UnvalidatedFunction>,
+        trace: Trace,
+    },
     EnumDefinition {
-        variants: Vec<UnvalidatedEnumVariant>,
-        trace: Trace
+        proto: UnvalidatedEnumPrototype,
+        trace: Trace,
+    },
+    TraitDefinition {
+        proto: UnvalidatedTraitPrototype,
+        trace: Trace,
     },
+    /// `let [mut] name = initial` at module scope, or `const NAME = initial` whose initial value
+    /// has to be known at compile time
     GlobalVariable {
         variable: UnvalidatedVariableInfo,
         initial: UnvalidatedExpression,
+        constant: bool,
+        visibility: Visibility,
         trace: Trace,
     },
+    /// `import a::b`, `import a::b as c`, `import a::*` or `import a::{b, c::*}` which imports
+    /// every path inside of the braces
     Import {
-        namespace: UnvalidatedSymbol,
-        star: bool,
+        imports: Vec<UnvalidatedImport>,
         trace: Trace,
     },
-}
\ No newline at end of file
+
+    /// Placeholder for an item that failed to parse
+    Error(Trace),
+}
+
+/// Single path brought into scope by an import
+#[derive(Debug, PartialEq, Serialize)]
+pub struct UnvalidatedImport {
+    /// Imported item, or the module whose items are all imported for glob imports
+    pub path: UnvalidatedSymbol,
+
+    /// `import a::*` imports every item of `a`
+    pub star: bool,
+
+    /// Name the item is known by in the importing module, `import a::b as c`
+    pub alias: Option<Identifier>,
+    pub trace: Trace,
+}
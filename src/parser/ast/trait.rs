@@ -0,0 +1,25 @@
+use serde::Serialize;
+use crate::file::identifier::Identifier;
+use crate::file::trace::Trace;
+use crate::ir::visibility::Visibility;
+use crate::parser::ast::function::{UnvalidatedFunctionExpression, UnvalidatedFunctionPrototype};
+
+#[derive(Debug, PartialEq, Serialize)]
+pub struct UnvalidatedTraitPrototype {
+    pub identifier: Identifier,
+
+    /// Names of the type parameters, eg. `T` of `Index<T>`
+    pub generics: Vec<Identifier>,
+    pub methods: Vec<UnvalidatedTraitMethod>,
+    pub visibility: Visibility,
+}
+
+/// Method declared inside of a trait, `Self` refers to the type implementing the trait
+#[derive(Debug, PartialEq, Serialize)]
+pub struct UnvalidatedTraitMethod {
+    pub proto: UnvalidatedFunctionPrototype,
+
+    /// Body used by implementations that leave the method out, required methods have none
+    pub body: Option<UnvalidatedFunctionExpression>,
+    pub trace: Trace,
+}
@@ -1,4 +1,6 @@
 use std::fmt::{Display, Formatter};
+use crate::diagnostic::Diagnostic;
+use crate::file::identifier::Identifier;
 use crate::file::trace::Trace;
 use crate::lexer::keyword::Keyword;
 use crate::lexer::token::{Operator, Token, TokenData};
@@ -10,6 +12,7 @@ pub type ParserResult<T> = Result<T, ParserError>;
 pub enum ParserError {
     UnexpectedToken(Token),
 
+    #[allow(dead_code)]
     ExpectedToken {
         expected: TokenData,
         received: Token,
@@ -17,22 +20,46 @@ pub enum ParserError {
 
     ExpectedIdentifier(Trace),
 
+    #[allow(dead_code)]
     ExpectedAnyOperator(Trace),
     ExpectedOperator(Operator, Trace),
+    #[allow(dead_code)]
     ExpectedAnyKeyword(Trace),
     ExpectedKeyword(Keyword, Trace),
 
     OpenParenthetical(Trace),
 
+    #[allow(dead_code)]
     NonUnaryOperator(Operator, Trace),
 
     ExpectedFunctionBody(Trace),
+
+    ExpectedPattern(Token),
+
+    UnknownMacro(Identifier, Trace),
+
+    /// Macro called without an argument it requires, eg. `assert!()`
+    MissingMacroArgument(Identifier, Trace),
+
+    /// First argument of a formatting macro that is not a string literal
+    ExpectedFormatString(Trace),
+
+    /// Formatting macro whose `{}` placeholders do not match its number of arguments
+    FormatArgumentCount {
+        placeholders: usize,
+        arguments: usize,
+        trace: Trace,
+    },
+
+    /// Operator starting a line that could either continue the previous line or start a new
+    /// statement, eg. `-` or `(`
+    AmbiguousContinuation(Operator, Trace),
 }
 
 /// TODO implement more detailed error messages
 impl ParserError {
     /// Gets the trace / place in file for error
-    fn trace(&self) -> Vec<&Trace> {
+    pub fn trace(&self) -> Vec<&Trace> {
         match self {
             ParserError::UnexpectedToken(Token(_, trace)) => vec![trace],
 
@@ -49,6 +76,108 @@ impl ParserError {
             ParserError::ExpectedAnyKeyword(trace) => vec![trace],
             ParserError::ExpectedKeyword(_, trace) => vec![trace],
             ParserError::ExpectedFunctionBody(trace) => vec![trace],
+            ParserError::ExpectedPattern(Token(_, trace)) => vec![trace],
+            ParserError::UnknownMacro(_, trace) => vec![trace],
+            ParserError::MissingMacroArgument(_, trace) => vec![trace],
+            ParserError::ExpectedFormatString(trace) => vec![trace],
+            ParserError::FormatArgumentCount { trace, .. } => vec![trace],
+            ParserError::AmbiguousContinuation(_, trace) => vec![trace],
+        }
+    }
+
+    /// Stable code identifying the kind of error
+    pub fn code(&self) -> &'static str {
+        match self {
+            ParserError::UnexpectedToken(_) => "P0001",
+            ParserError::ExpectedToken { .. } => "P0002",
+            ParserError::ExpectedIdentifier(_) => "P0003",
+            ParserError::ExpectedAnyOperator(_) => "P0004",
+            ParserError::ExpectedOperator(..) => "P0005",
+            ParserError::ExpectedAnyKeyword(_) => "P0006",
+            ParserError::ExpectedKeyword(..) => "P0007",
+            ParserError::OpenParenthetical(_) => "P0008",
+            ParserError::NonUnaryOperator(..) => "P0009",
+            ParserError::ExpectedFunctionBody(_) => "P0010",
+            ParserError::ExpectedPattern(_) => "P0011",
+            ParserError::UnknownMacro(..) => "P0012",
+            ParserError::MissingMacroArgument(..) => "P0013",
+            ParserError::ExpectedFormatString(_) => "P0014",
+            ParserError::FormatArgumentCount { .. } => "P0015",
+            ParserError::AmbiguousContinuation(..) => "P0016",
+        }
+    }
+
+    pub fn message(&self) -> String {
+        match self {
+            ParserError::UnexpectedToken(tok) => format!("unexpected {}", tok.token()),
+            ParserError::ExpectedToken { expected, received } => {
+                format!("expected {expected}, found {}", received.token())
+            }
+            ParserError::ExpectedIdentifier(_) => "expected identifier".into(),
+            ParserError::ExpectedAnyOperator(_) => "expected operator".into(),
+            ParserError::ExpectedOperator(op, _) => format!("expected `{op}`"),
+            ParserError::ExpectedAnyKeyword(_) => "expected keyword".into(),
+            ParserError::ExpectedKeyword(keyword, _) => format!("expected keyword `{keyword}`"),
+            ParserError::OpenParenthetical(_) => "unclosed parenthesis".into(),
+            ParserError::NonUnaryOperator(op, _) => format!("`{op}` is not a unary operator"),
+            ParserError::ExpectedFunctionBody(_) => "expected function body".into(),
+            ParserError::ExpectedPattern(tok) => format!("expected pattern, found {}", tok.token()),
+            ParserError::UnknownMacro(name, _) => format!("cannot find macro `{name}`"),
+            ParserError::MissingMacroArgument(name, _) => format!("missing argument of `{name}`"),
+            ParserError::ExpectedFormatString(_) => "format argument must be a string literal".into(),
+            ParserError::FormatArgumentCount { placeholders, arguments, .. } => {
+                format!("{placeholders} placeholder(s) but {arguments} argument(s) were given")
+            }
+            ParserError::AmbiguousContinuation(op, _) => format!("ambiguous `{op}` at the start of a line"),
+        }
+    }
+
+    /// Short explanation shown underneath the primary trace
+    fn label(&self) -> Option<String> {
+        Some(match self {
+            ParserError::UnexpectedToken(_) => "unexpected token".into(),
+            ParserError::ExpectedToken { expected, .. } => format!("expected {expected}"),
+            ParserError::ExpectedIdentifier(_) => "expected an identifier here".into(),
+            ParserError::ExpectedOperator(op, _) => format!("expected `{op}` here"),
+            ParserError::ExpectedKeyword(keyword, _) => format!("expected `{keyword}` here"),
+            ParserError::OpenParenthetical(_) => "this parenthesis is never closed".into(),
+            ParserError::ExpectedFunctionBody(_) => "this function has no body".into(),
+            ParserError::ExpectedPattern(_) => "expected a pattern here".into(),
+            ParserError::UnknownMacro(..) => "not a built-in macro".into(),
+            ParserError::ExpectedFormatString(_) => "expected a string literal here".into(),
+            ParserError::FormatArgumentCount { placeholders, .. } => format!("this format string has {placeholders} placeholder(s)"),
+            ParserError::AmbiguousContinuation(..) => "this line is parsed as a new statement".into(),
+            _ => return None,
+        })
+    }
+
+    pub fn diagnostic(&self) -> Diagnostic {
+        let mut traces = self.trace().into_iter();
+
+        let mut diagnostic = Diagnostic::error(self.code(), self.message());
+
+        if let Some(primary) = traces.next() {
+            diagnostic = diagnostic.with_primary(*primary, self.label());
+        }
+
+        let diagnostic = traces.fold(diagnostic, |diagnostic, trace| {
+            diagnostic.with_secondary(*trace, None)
+        });
+
+        match self {
+            ParserError::ExpectedFunctionBody(_) => diagnostic
+                .with_help("function bodies are either a block `{ ... }` or a single expression after `=>`"),
+            ParserError::ExpectedPattern(_) => diagnostic
+                .with_note("patterns are `_`, bindings, literals, ranges, enum variants and structures"),
+            ParserError::UnknownMacro(..) => diagnostic
+                .with_note("the built-in macros are `print!`, `println!`, `format!`, `crash!` and `assert!`"),
+            ParserError::FormatArgumentCount { .. } => diagnostic
+                .with_note("every `{}` in the format string is filled in by one argument, in order"),
+            ParserError::NonUnaryOperator(..) => diagnostic
+                .with_note("the unary operators are `-`, `!` and `~`"),
+            ParserError::AmbiguousContinuation(op, _) => diagnostic
+                .with_help(format!("end the previous line with `;` to start a new statement, or move the `{op}` to the end of the previous line to continue it")),
+            _ => diagnostic,
         }
     }
 }
@@ -57,7 +186,6 @@ impl std::error::Error for ParserError {}
 
 impl Display for ParserError {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
-        // FIXME display trace from file
-        f.write_fmt(format_args!("{self:#?}"))
+        f.write_str(&self.message())
     }
 }
@@ -0,0 +1,289 @@
+use crate::file::identifier::{Identifier, Namespace};
+use crate::file::trace::Trace;
+use crate::lexer::token::{Operator, Token};
+use crate::parser::ast::expression::{UnvalidatedExpression, UnvalidatedFormatSegment};
+use crate::parser::ast::function::UnvalidatedFunctionExpression;
+use crate::parser::ast::operations::UnaryOperator;
+use crate::parser::ast::top_level::UnvalidatedTopLevel;
+use crate::parser::ast::UnvalidatedSymbol;
+use crate::parser::error::{ParserError, ParserResult};
+use crate::parser::modules::expression_parser::ExpressionParser;
+use crate::parser::parser::Parser;
+
+/// Module of the functions the compiler provides itself, the built-in macros expand into calls
+/// to them
+pub const BUILTIN_MODULE: &str = "builtin";
+
+/// `print(text: ref[char])` writes the text to the standard output
+pub const PRINT: &str = "print";
+
+/// `crash(message: ref[char])` ends the program with the message, it never returns
+pub const CRASH: &str = "crash";
+
+/// `array<T>(start: ref[T], length: usize): [T]` is the array of `length` values starting at the
+/// referenced one
+pub const ARRAY: &str = "array";
+
+/// Replaces every macro call of the parsed files by the code it expands to. Calls that fail to
+/// expand are replaced by error expressions so that validation can go on
+pub fn expand_macros(statements: &mut [UnvalidatedTopLevel]) -> Vec<ParserError> {
+    let mut expander = MacroExpander { errors: vec![] };
+
+    for statement in statements {
+        match statement {
+            UnvalidatedTopLevel::FunctionDefinition { body, .. } => expander.expand_statement(body),
+            UnvalidatedTopLevel::ImplBlock { functions, .. } => {
+                for function in functions {
+                    expander.expand_statement(&mut function.body);
+                }
+            }
+            UnvalidatedTopLevel::TraitDefinition { proto, .. } => {
+                for body in proto.methods.iter_mut().filter_map(|method| method.body.as_mut()) {
+                    expander.expand_statement(body);
+                }
+            }
+            UnvalidatedTopLevel::GlobalVariable { initial, .. } => expander.expand_expression(initial),
+            UnvalidatedTopLevel::StructDefinition { .. }
+            | UnvalidatedTopLevel::EnumDefinition { .. }
+            | UnvalidatedTopLevel::Import { .. }
+            | UnvalidatedTopLevel::Error(_) => {}
+        }
+    }
+
+    expander.errors
+}
+
+struct MacroExpander {
+    errors: Vec<ParserError>,
+}
+
+impl MacroExpander {
+    fn expand_statement(&mut self, statement: &mut UnvalidatedFunctionExpression) {
+        match statement {
+            UnvalidatedFunctionExpression::If { condition, then, .. }
+            | UnvalidatedFunctionExpression::While { condition, then, .. }
+            | UnvalidatedFunctionExpression::For { iterable: condition, then, .. } => {
+                self.expand_expression(condition);
+                self.expand_expression(then);
+            }
+            UnvalidatedFunctionExpression::Let { initial: Some(expr), .. }
+            | UnvalidatedFunctionExpression::Return(Some(expr))
+            | UnvalidatedFunctionExpression::Expression(expr) => self.expand_expression(expr),
+            UnvalidatedFunctionExpression::Let { initial: None, .. }
+            | UnvalidatedFunctionExpression::Return(None) => {}
+        }
+    }
+
+    fn expand_expression(&mut self, expr: &mut UnvalidatedExpression) {
+        use UnvalidatedExpression as E;
+
+        match expr {
+            E::MacroCall { name, arguments, trace } => {
+                let expanded = expand(name, std::mem::take(arguments), *trace).unwrap_or_else(|errors| {
+                    self.errors.extend(errors);
+                    E::Error(*trace)
+                });
+                *expr = expanded;
+
+                // the arguments can call macros as well
+                if !matches!(expr, E::Error(_)) {
+                    self.expand_expression(expr);
+                }
+            }
+            E::IfElse { condition, then, otherwise, .. } => {
+                self.expand_expression(condition);
+                self.expand_statement(then);
+                self.expand_statement(otherwise);
+            }
+            E::Scope(statements, _) => {
+                for statement in statements {
+                    self.expand_statement(statement);
+                }
+            }
+            E::Match { scrutinee, arms, .. } => {
+                self.expand_expression(scrutinee);
+                for arm in arms {
+                    if let Some(guard) = &mut arm.guard {
+                        self.expand_expression(guard);
+                    }
+                    self.expand_expression(&mut arm.body);
+                }
+            }
+            E::FormatString { segments, .. } => {
+                for segment in segments {
+                    if let UnvalidatedFormatSegment::Expression(expr) = segment {
+                        self.expand_expression(expr);
+                    }
+                }
+            }
+            E::Binary { lhs, rhs, .. }
+            | E::Range { start: lhs, end: rhs, .. }
+            | E::Index { object: lhs, index: rhs, .. } => {
+                self.expand_expression(lhs);
+                self.expand_expression(rhs);
+            }
+            E::Parenthetical(expr, _)
+            | E::Unary { expr, .. }
+            | E::Cast { expr, .. }
+            | E::ObjectProperty { object: expr, .. } => self.expand_expression(expr),
+            E::MethodCall { object, arguments, .. } => {
+                self.expand_expression(object);
+                for argument in arguments {
+                    self.expand_expression(argument);
+                }
+            }
+            E::FunctionCall { arguments, .. } => {
+                for argument in arguments {
+                    self.expand_expression(argument);
+                }
+            }
+            E::Construct { fields, .. } => {
+                for field in fields {
+                    self.expand_expression(&mut field.value);
+                }
+            }
+            E::BoolLiteral(..)
+            | E::F32Literal(..)
+            | E::F64Literal(..)
+            | E::IntegerLiteral(..)
+            | E::I32Literal(..)
+            | E::I64Literal(..)
+            | E::U32Literal(..)
+            | E::U64Literal(..)
+            | E::UsizeLiteral(..)
+            | E::StringLiteral(..)
+            | E::CharLiteral(..)
+            | E::VariableReference { .. }
+            | E::Error(_) => {}
+        }
+    }
+}
+
+/// Code a single macro call expands to
+fn expand(name: &Identifier, tokens: Vec<Token>, trace: Trace) -> Result<UnvalidatedExpression, Vec<ParserError>> {
+    let mut arguments = Parser::parse_detached(trace.file, tokens, parse_arguments)?.into_iter();
+    let name = name.as_str();
+
+    let expanded = match name {
+        "format!" => format(name, arguments, trace),
+        "print!" => format(name, arguments, trace).map(|text| builtin_call(PRINT, text, trace)),
+        "println!" => {
+            // `println!()` prints an empty line
+            let text = match arguments.len() {
+                0 => Ok(UnvalidatedExpression::StringLiteral(String::new(), trace)),
+                _ => format(name, arguments, trace),
+            };
+            text.map(|text| builtin_call(PRINT, with_newline(text), trace))
+        }
+        "crash!" => {
+            let message = match arguments.len() {
+                0 => Ok(UnvalidatedExpression::StringLiteral("explicit crash".into(), trace)),
+                _ => format(name, arguments, trace),
+            };
+            message.map(|message| builtin_call(CRASH, message, trace))
+        }
+        "assert!" => {
+            let condition = arguments.next().ok_or(ParserError::MissingMacroArgument(name.into(), trace));
+            let message = match arguments.len() {
+                0 => Ok(UnvalidatedExpression::StringLiteral("assertion failed".into(), trace)),
+                _ => format(name, arguments, trace),
+            };
+
+            condition.and_then(|condition| Ok(assertion(condition, message?, trace)))
+        }
+        _ => Err(ParserError::UnknownMacro(name.into(), trace)),
+    };
+
+    expanded.map_err(|err| vec![err])
+}
+
+/// Comma separated expressions, a trailing comma is allowed
+fn parse_arguments(p: &mut Parser) -> ParserResult<Vec<UnvalidatedExpression>> {
+    let mut arguments = vec![];
+
+    while !p.is_eof() {
+        arguments.push(ExpressionParser::consume_expression(p)?);
+
+        if !p.is_eof() {
+            p.expect_operator(Operator::Comma)?;
+        }
+    }
+    Ok(arguments)
+}
+
+/// Fills the `{}` placeholders of the format string, the first argument, with the remaining
+/// arguments in order
+fn format(
+    name: &str,
+    mut arguments: impl ExactSizeIterator<Item=UnvalidatedExpression>,
+    trace: Trace,
+) -> ParserResult<UnvalidatedExpression> {
+    let segments = match arguments.next() {
+        // strings without placeholders are plain string literals
+        Some(UnvalidatedExpression::StringLiteral(text, literal_trace)) => match arguments.len() {
+            0 => return Ok(UnvalidatedExpression::StringLiteral(text, literal_trace)),
+            _ => vec![UnvalidatedFormatSegment::Literal(text)],
+        },
+        Some(UnvalidatedExpression::FormatString { segments, .. }) => segments,
+        Some(other) => return Err(ParserError::ExpectedFormatString(other.trace())),
+        None => return Err(ParserError::MissingMacroArgument(name.into(), trace)),
+    };
+
+    let placeholders = segments
+        .iter()
+        .filter(|segment| matches!(segment, UnvalidatedFormatSegment::Placeholder(_)))
+        .count();
+
+    if placeholders != arguments.len() {
+        return Err(ParserError::FormatArgumentCount { placeholders, arguments: arguments.len(), trace });
+    }
+
+    let segments = segments
+        .into_iter()
+        .map(|segment| match segment {
+            UnvalidatedFormatSegment::Placeholder(_) => {
+                UnvalidatedFormatSegment::Expression(arguments.next().expect("Counted placeholders"))
+            }
+            segment => segment,
+        })
+        .collect();
+
+    Ok(UnvalidatedExpression::FormatString { segments, trace })
+}
+
+fn with_newline(text: UnvalidatedExpression) -> UnvalidatedExpression {
+    match text {
+        UnvalidatedExpression::StringLiteral(text, trace) => UnvalidatedExpression::StringLiteral(text + "\n", trace),
+        UnvalidatedExpression::FormatString { mut segments, trace } => {
+            segments.push(UnvalidatedFormatSegment::Literal("\n".into()));
+            UnvalidatedExpression::FormatString { segments, trace }
+        }
+        text => text,
+    }
+}
+
+fn builtin_call(function: &str, argument: UnvalidatedExpression, trace: Trace) -> UnvalidatedExpression {
+    UnvalidatedExpression::FunctionCall {
+        symbol: UnvalidatedSymbol {
+            explicit_namespace: Namespace { chain: vec![BUILTIN_MODULE.into()] },
+            identifier: function.into(),
+        },
+        arguments: vec![argument],
+        trace,
+    }
+}
+
+/// `unless condition { crash(message) }`
+fn assertion(condition: UnvalidatedExpression, message: UnvalidatedExpression, trace: Trace) -> UnvalidatedExpression {
+    let check = UnvalidatedFunctionExpression::If {
+        condition: UnvalidatedExpression::Unary {
+            trace: condition.trace(),
+            expr: Box::new(condition),
+            op: UnaryOperator::Not,
+        },
+        then: Box::new(UnvalidatedExpression::Scope(vec![builtin_call(CRASH, message, trace).into()], trace)),
+        trace,
+    };
+
+    UnvalidatedExpression::Scope(vec![check], trace)
+}
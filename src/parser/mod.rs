@@ -2,9 +2,11 @@
 
 
 pub mod ast;
+#[allow(clippy::module_inception)]
 pub mod parser;
 mod modules;
-mod error;
+pub mod error;
+pub mod expansion;
 
 #[cfg(test)]
 mod test;
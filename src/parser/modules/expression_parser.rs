@@ -1,12 +1,15 @@
 use crate::lexer::keyword::Keyword;
-use crate::lexer::token::{Operator, Token, TokenData};
-use crate::parser::ast::data::UnvalidatedType;
-use crate::parser::ast::expression::UnvalidatedExpression;
+use crate::lexer::token::{FormatSegment, Operator, Token, TokenData};
+use crate::file::identifier::{Identifier, Namespace};
+use crate::parser::ast::expression::{UnvalidatedExpression, UnvalidatedFieldInit, UnvalidatedFormatSegment};
 use crate::parser::ast::function::UnvalidatedFunctionExpression;
 use crate::parser::ast::operations::{BinaryOperation, UnaryOperator};
+use crate::parser::ast::UnvalidatedSymbol;
 use crate::parser::error::{ParserError, ParserResult};
+use crate::parser::modules::pattern_parser::PatternParser;
 use crate::parser::modules::statement_parser::StatementParser;
-use crate::parser::parser::Parser;
+use crate::parser::modules::top_level::TopLevelParser;
+use crate::parser::parser::{LineBreaks, Parser};
 
 pub struct ExpressionParser;
 
@@ -22,6 +25,7 @@ pub const ORDER_OF_OPERATIONS: &[&[Operator]] = &[
     &[Operator::BitOr],
     &[Operator::And],
     &[Operator::Or],
+    &[Operator::DotDot, Operator::DotDotEquals],
     &[
         Operator::ModAssigns,
         Operator::XorAssign,
@@ -47,29 +51,68 @@ impl ExpressionParser {
 
         p.advance();
 
-        let mut body = vec![];
         let start = p.position();
+        let body = p.with_line_breaks(LineBreaks::EndStatement, Self::consume_statements);
+        p.expect_operator(Operator::CurlyClose)?;
+
+        Ok(Some(UnvalidatedExpression::Scope(body, p.trace_from(start))))
+    }
+
+    /// Statements of a block up to its closing brace, empty statements `;` are skipped
+    fn consume_statements(p: &mut Parser) -> Vec<UnvalidatedFunctionExpression> {
+        let mut body = vec![];
 
         while !p.is_eof() && !p.has_operator(Operator::CurlyClose) {
-            let statement = StatementParser::consume_function_expression(p)?;
-            body.push(statement);
+            if p.has_operator(Operator::Semicolon) {
+                p.advance();
+                continue;
+            }
+
+            let statement_start = p.position();
+
+            match StatementParser::consume_function_expression(p) {
+                Ok(statement) => body.push(statement),
+                Err(err) => {
+                    p.report(err);
+                    p.synchronize(statement_start, StatementParser::is_statement_boundary);
+                    body.push(UnvalidatedExpression::Error(p.trace_from(statement_start)).into());
+
+                    // the block was never closed, leave it to the top level to recover
+                    if TopLevelParser::is_item_start(p.curr().token()) {
+                        break;
+                    }
+                }
+            }
         }
-        p.expect_operator(Operator::CurlyClose)?;
 
-        Ok(Some(UnvalidatedExpression::Scope(body, p.trace_from(start))))
+        body
     }
     pub fn consume_expression(p: &mut Parser) -> ParserResult<UnvalidatedExpression> {
         Self::consume_binary_expression(p, ORDER_OF_OPERATIONS.len() - 1)
     }
 
     fn consume_factor(p: &mut Parser) -> ParserResult<UnvalidatedExpression> {
-        let trace = p.curr().trace().clone();
+        let trace = *p.curr().trace();
 
         Ok(match p.curr().token().clone() {
             TokenData::StringLiteral(s) => {
                 p.advance();
                 UnvalidatedExpression::StringLiteral(s, trace)
             }
+            TokenData::FormatString(segments) => {
+                p.advance();
+
+                let segments = segments
+                    .into_iter()
+                    .map(|segment| Self::consume_format_segment(p, segment))
+                    .collect::<ParserResult<_>>()?;
+
+                UnvalidatedExpression::FormatString { segments, trace }
+            }
+            TokenData::CharLiteral(c) => {
+                p.advance();
+                UnvalidatedExpression::CharLiteral(c, trace)
+            }
             TokenData::BoolLiteral(l) => {
                 p.advance();
                 UnvalidatedExpression::BoolLiteral(l, trace)
@@ -82,6 +125,10 @@ impl ExpressionParser {
                 p.advance();
                 UnvalidatedExpression::F64Literal(l, trace)
             }
+            TokenData::IntegerLiteral(l) => {
+                p.advance();
+                UnvalidatedExpression::IntegerLiteral(l, trace)
+            }
             TokenData::I32Literal(l) => {
                 p.advance();
                 UnvalidatedExpression::I32Literal(l, trace)
@@ -90,33 +137,44 @@ impl ExpressionParser {
                 p.advance();
                 UnvalidatedExpression::I64Literal(l, trace)
             }
+            TokenData::U32Literal(l) => {
+                p.advance();
+                UnvalidatedExpression::U32Literal(l, trace)
+            }
+            TokenData::U64Literal(l) => {
+                p.advance();
+                UnvalidatedExpression::U64Literal(l, trace)
+            }
+            TokenData::UsizeLiteral(l) => {
+                p.advance();
+                UnvalidatedExpression::UsizeLiteral(l, trace)
+            }
+            TokenData::Keyword(Keyword::Match) => PatternParser::consume_match(p)?,
+            TokenData::MacroIdentifier(name) => Self::consume_macro_call(p, name)?,
+            TokenData::Keyword(Keyword::If | Keyword::Unless) => {
+                let start = p.position();
+
+                match StatementParser::parse_if(p)?.expect("Checked for if") {
+                    UnvalidatedFunctionExpression::Expression(expr) => expr,
+
+                    // an if without else evaluates to unit
+                    statement => UnvalidatedExpression::Scope(vec![statement], p.trace_from(start)),
+                }
+            }
+            TokenData::Keyword(Keyword::Make | Keyword::Alloc) => Self::consume_construction(p)?,
             TokenData::Identifier(_) => {
                 let start = p.position();
                 let symbol = p.consume_symbol()?;
 
-                if !p.has_operator(Operator::ParenOpen) {
+                if !p.has_operator(Operator::ParenOpen) || !p.continues_line() {
                     UnvalidatedExpression::VariableReference {
                         symbol,
                         trace: p.trace_from(start),
                     }
                 } else {
-                    p.advance();
-                    let mut arguments = vec![];
-
-                    while !p.is_eof() && !p.has_operator(Operator::ParenClose) {
-                        arguments.push(Self::consume_expression(p)?);
-
-                        if p.has_operator(Operator::Comma) {
-                            p.advance();
-                            continue;
-                        }
-                        break;
-                    }
-                    p.expect_operator(Operator::ParenClose)?;
-
                     UnvalidatedExpression::FunctionCall {
                         symbol,
-                        arguments,
+                        arguments: Self::consume_arguments(p)?,
                         trace: p.trace_from(start),
                     }
                 }
@@ -130,7 +188,7 @@ impl ExpressionParser {
                     let start = p.position();
 
                     p.advance();
-                    let expr = Self::consume_expression(p)?;
+                    let expr = p.with_line_breaks(LineBreaks::Ignored, Self::consume_expression)?;
                     p.expect_operator(Operator::ParenClose)?;
                     UnvalidatedExpression::Parenthetical(
                         Box::new(expr),
@@ -138,12 +196,13 @@ impl ExpressionParser {
                     )
                 }
 
+                // unary operators bind tighter than any binary operator, `-a + b` is `(-a) + b`
                 Operator::Not | Operator::BitNot | Operator::Minus => {
                     let start = p.position();
                     p.advance();
 
                     UnvalidatedExpression::Unary {
-                        expr: Box::new(Self::consume_expression(p)?),
+                        expr: Box::new(Self::consume_expression_cast(p)?),
                         op: UnaryOperator::try_from(op).expect("Non unary operator"),
                         trace: p.trace_from(start),
                     }
@@ -157,9 +216,185 @@ impl ExpressionParser {
         })
     }
 
-    fn consume_expression_cast(p: &mut Parser) -> ParserResult<UnvalidatedExpression> {
+    /// Parses the parenthesized, comma separated arguments of a call
+    fn consume_arguments(p: &mut Parser) -> ParserResult<Vec<UnvalidatedExpression>> {
+        p.expect_operator(Operator::ParenOpen)?;
+        let mut arguments = vec![];
+
+        while !p.is_eof() && !p.has_operator(Operator::ParenClose) {
+            arguments.push(p.with_line_breaks(LineBreaks::Ignored, Self::consume_expression)?);
+
+            if p.has_operator(Operator::Comma) {
+                p.advance();
+                continue;
+            }
+            break;
+        }
+        p.expect_operator(Operator::ParenClose)?;
+
+        Ok(arguments)
+    }
+
+    /// Parses a factor followed by any chain of `.field`, `.method(arguments)` and `[index]`,
+    /// the trace of every link starts at the factor
+    fn consume_postfix(p: &mut Parser) -> ParserResult<UnvalidatedExpression> {
+        let start = p.position();
         let mut expr = Self::consume_factor(p)?;
+
+        loop {
+            if p.has_operator(Operator::Dot) {
+                p.advance();
+                let identifier = p.consume_identifier()?;
+
+                expr = if p.has_operator(Operator::ParenOpen) && p.continues_line() {
+                    UnvalidatedExpression::MethodCall {
+                        object: Box::new(expr),
+                        method: identifier,
+                        arguments: Self::consume_arguments(p)?,
+                        trace: p.trace_from(start),
+                    }
+                } else {
+                    UnvalidatedExpression::ObjectProperty {
+                        object: Box::new(expr),
+                        identifier,
+                        trace: p.trace_from(start),
+                    }
+                };
+            } else if p.has_operator(Operator::BracketOpen) {
+                p.advance();
+                let index = p.with_line_breaks(LineBreaks::Ignored, Self::consume_expression)?;
+                p.expect_operator(Operator::BracketClose)?;
+
+                expr = UnvalidatedExpression::Index {
+                    object: Box::new(expr),
+                    index: Box::new(index),
+                    trace: p.trace_from(start),
+                };
+            } else {
+                break;
+            }
+        }
+
+        Ok(expr)
+    }
+
+    /// Parses `name!(..)`, `name![..]` or `name!{..}`. The tokens between the delimiters are kept
+    /// as they are for the expansion phase, only the brackets inside have to be balanced
+    fn consume_macro_call(p: &mut Parser, name: Identifier) -> ParserResult<UnvalidatedExpression> {
+        let start = p.position();
+        p.advance();
+
+        let open = p.curr().clone();
+        let Some(close) = closing_bracket(open.token()) else {
+            return Err(ParserError::ExpectedOperator(Operator::ParenOpen, *open.trace()));
+        };
+        p.advance();
+
+        let mut unclosed = vec![(close, *open.trace())];
+        let mut arguments = vec![];
+        let mut end = *open.trace();
+
+        while let Some(&(close, open_trace)) = unclosed.last() {
+            let token = p.curr().clone();
+
+            match token.token() {
+                TokenData::EOF => return Err(ParserError::OpenParenthetical(open_trace)),
+                TokenData::Operator(op) if *op == close => {
+                    unclosed.pop();
+                    end = *token.trace();
+                }
+                TokenData::Operator(Operator::ParenClose | Operator::BracketClose | Operator::CurlyClose) => {
+                    return Err(ParserError::UnexpectedToken(token));
+                }
+                data => {
+                    if let Some(close) = closing_bracket(data) {
+                        unclosed.push((close, *token.trace()));
+                    }
+                }
+            }
+
+            p.advance();
+            if !unclosed.is_empty() {
+                arguments.push(token);
+            }
+        }
+
+        // the closing delimiter ends the arguments
+        arguments.push(Token(TokenData::EOF, end));
+
+        Ok(UnvalidatedExpression::MacroCall { name, arguments, trace: p.trace_from(start) })
+    }
+
+    /// Parses `make T { .. }` or `alloc T { .. }`, fields are separated by commas or new lines
+    fn consume_construction(p: &mut Parser) -> ParserResult<UnvalidatedExpression> {
+        let start = p.position();
+        let heap = p.has_keyword(Keyword::Alloc);
+        p.advance();
+
+        let ty = p.consume_type()?;
+
+        let variant = if p.has_operator(Operator::DoubleColon) {
+            p.advance();
+            Some(p.consume_identifier()?)
+        } else {
+            None
+        };
+
+        p.expect_operator(Operator::CurlyOpen)?;
+
+        let mut fields = vec![];
+
+        while !p.is_eof() && !p.has_operator(Operator::CurlyClose) {
+            let field_start = p.position();
+            let name = p.consume_identifier()?;
+
+            let value = if p.has_operator(Operator::Colon) {
+                p.advance();
+                Self::consume_expression(p)?
+            } else {
+                UnvalidatedExpression::VariableReference {
+                    symbol: UnvalidatedSymbol {
+                        explicit_namespace: Namespace { chain: vec![] },
+                        identifier: name.clone(),
+                    },
+                    trace: p.trace_from(field_start),
+                }
+            };
+
+            fields.push(UnvalidatedFieldInit {
+                name,
+                value,
+                trace: p.trace_from(field_start),
+            });
+
+            if p.has_operator(Operator::Comma) {
+                p.advance();
+            }
+        }
+        p.expect_operator(Operator::CurlyClose)?;
+
+        Ok(UnvalidatedExpression::Construct {
+            ty,
+            variant,
+            fields,
+            heap,
+            trace: p.trace_from(start),
+        })
+    }
+
+    fn consume_format_segment(p: &mut Parser, segment: FormatSegment) -> ParserResult<UnvalidatedFormatSegment> {
+        Ok(match segment {
+            FormatSegment::Literal(text) => UnvalidatedFormatSegment::Literal(text),
+            FormatSegment::Placeholder(trace) => UnvalidatedFormatSegment::Placeholder(trace),
+            FormatSegment::Interpolation(tokens) => {
+                UnvalidatedFormatSegment::Expression(p.parse_nested(tokens, Self::consume_expression)?)
+            }
+        })
+    }
+
+    fn consume_expression_cast(p: &mut Parser) -> ParserResult<UnvalidatedExpression> {
         let start = p.position();
+        let mut expr = Self::consume_postfix(p)?;
 
         while p.has_keyword(Keyword::As) {
             p.advance();
@@ -186,21 +421,34 @@ impl ExpressionParser {
 
         let valid_ops = ORDER_OF_OPERATIONS[op_index];
 
-        let mut parse_operand = |p: &mut Parser| {
+        let parse_operand = |p: &mut Parser| {
             Self::consume_binary_expression(p, op_index - 1)
         };
 
+        let start = p.position();
+
         // get the left hand side of the expression by parsing by the operator
         let mut lhs = parse_operand(p)?;
 
-        let start = p.position();
-
         // exit loop if the next token is not an operator
         // with the precedence being looked for
         while let TokenData::Operator(op) = p.curr().token().clone() {
             if !valid_ops.contains(&op) { break; }
+
+            // `-` on a new line is more likely the start of a negated statement than a subtraction
+            if op == Operator::Minus && !p.continues_line() { break; }
             p.advance();
 
+            // ranges do not chain, `a..b..c` is left for the caller to reject
+            if matches!(op, Operator::DotDot | Operator::DotDotEquals) {
+                return Ok(UnvalidatedExpression::Range {
+                    start: Box::new(lhs),
+                    end: Box::new(parse_operand(p)?),
+                    inclusive: op == Operator::DotDotEquals,
+                    trace: p.trace_from(start),
+                });
+            }
+
             // turn the lhs to a binary operation between it and the next
             // expression of 1 less precedence
             lhs = UnvalidatedExpression::Binary {
@@ -214,3 +462,13 @@ impl ExpressionParser {
         Ok(lhs)
     }
 }
+
+/// Bracket closing the given token, if it opens one
+fn closing_bracket(token: &TokenData) -> Option<Operator> {
+    match token {
+        TokenData::Operator(Operator::ParenOpen) => Some(Operator::ParenClose),
+        TokenData::Operator(Operator::BracketOpen) => Some(Operator::BracketClose),
+        TokenData::Operator(Operator::CurlyOpen) => Some(Operator::CurlyClose),
+        _ => None,
+    }
+}
@@ -1,3 +1,4 @@
 pub mod expression_parser;
 pub mod top_level;
+pub mod pattern_parser;
 mod statement_parser;
\ No newline at end of file
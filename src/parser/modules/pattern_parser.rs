@@ -0,0 +1,300 @@
+use crate::lexer::keyword::Keyword;
+use crate::lexer::token::{Operator, TokenData};
+use crate::parser::ast::expression::UnvalidatedExpression;
+use crate::parser::ast::operations::UnaryOperator;
+use crate::parser::ast::pattern::{UnvalidatedFieldPattern, UnvalidatedMatchArm, UnvalidatedPattern};
+use crate::parser::ast::UnvalidatedSymbol;
+use crate::parser::error::{ParserError, ParserResult};
+use crate::parser::modules::expression_parser::ExpressionParser;
+use crate::parser::parser::{LineBreaks, Parser};
+
+pub struct PatternParser;
+
+impl PatternParser {
+    /// Parses a match expression, the `match` keyword is expected to be the current token
+    pub fn consume_match(p: &mut Parser) -> ParserResult<UnvalidatedExpression> {
+        let start = p.position();
+        p.expect_keyword(Keyword::Match)?;
+
+        let scrutinee = ExpressionParser::consume_expression(p)?;
+        p.expect_operator(Operator::CurlyOpen)?;
+
+        // arms can be separated by commas or just by new lines
+        let arms = p.with_line_breaks(LineBreaks::EndArm, Self::consume_arms)?;
+        p.expect_operator(Operator::CurlyClose)?;
+
+        Ok(UnvalidatedExpression::Match {
+            scrutinee: Box::new(scrutinee),
+            arms,
+            trace: p.trace_from(start),
+        })
+    }
+
+    fn consume_arms(p: &mut Parser) -> ParserResult<Vec<UnvalidatedMatchArm>> {
+        let mut arms = vec![];
+
+        while !p.is_eof() && !p.has_operator(Operator::CurlyClose) {
+            arms.push(Self::consume_arm(p)?);
+
+            if p.has_operator(Operator::Comma) {
+                p.advance();
+            }
+        }
+
+        Ok(arms)
+    }
+
+    fn consume_arm(p: &mut Parser) -> ParserResult<UnvalidatedMatchArm> {
+        let start = p.position();
+        let pattern = Self::consume_pattern(p)?;
+
+        let guard = if p.has_keyword(Keyword::If) {
+            p.advance();
+            Some(ExpressionParser::consume_expression(p)?)
+        } else {
+            None
+        };
+
+        p.expect_operator(Operator::ThickRightArrow)?;
+
+        let body = match ExpressionParser::parse_block(p)? {
+            Some(block) => block,
+            None => ExpressionParser::consume_expression(p)?,
+        };
+
+        Ok(UnvalidatedMatchArm {
+            pattern,
+            guard,
+            body,
+            trace: p.trace_from(start),
+        })
+    }
+
+    /// Parses a pattern including `|` alternatives
+    pub fn consume_pattern(p: &mut Parser) -> ParserResult<UnvalidatedPattern> {
+        let start = p.position();
+        let first = Self::consume_single_pattern(p)?;
+
+        if !p.has_operator(Operator::BitOr) {
+            return Ok(first);
+        }
+
+        let mut alternatives = vec![first];
+
+        while p.has_operator(Operator::BitOr) {
+            p.advance();
+            alternatives.push(Self::consume_single_pattern(p)?);
+        }
+
+        Ok(UnvalidatedPattern::Alternatives(alternatives, p.trace_from(start)))
+    }
+
+    fn consume_single_pattern(p: &mut Parser) -> ParserResult<UnvalidatedPattern> {
+        let start = p.position();
+
+        match p.curr().token().clone() {
+            TokenData::Identifier(ident) if ident.0 == "_" => {
+                p.advance();
+                Ok(UnvalidatedPattern::Wildcard(p.trace_from(start)))
+            }
+            TokenData::Keyword(Keyword::Mut) => {
+                p.advance();
+                let ident = p.consume_identifier()?;
+
+                Ok(UnvalidatedPattern::Binding {
+                    ident,
+                    mutable: true,
+                    trace: p.trace_from(start),
+                })
+            }
+            TokenData::Identifier(_) => {
+                let symbol = p.consume_symbol()?;
+
+                if p.has_operator(Operator::ParenOpen) {
+                    p.advance();
+                    let mut fields = vec![];
+
+                    while !p.is_eof() && !p.has_operator(Operator::ParenClose) {
+                        fields.push(Self::consume_pattern(p)?);
+
+                        if p.has_operator(Operator::Comma) {
+                            p.advance();
+                            continue;
+                        }
+                        break;
+                    }
+                    p.expect_operator(Operator::ParenClose)?;
+
+                    Ok(UnvalidatedPattern::Variant { symbol, fields, trace: p.trace_from(start) })
+                } else if p.has_operator(Operator::CurlyOpen) {
+                    Self::consume_struct_pattern(p, start, symbol)
+                } else if symbol.explicit_namespace.chain.is_empty() {
+                    Ok(UnvalidatedPattern::Binding {
+                        ident: symbol.identifier,
+                        mutable: false,
+                        trace: p.trace_from(start),
+                    })
+                } else {
+                    Ok(UnvalidatedPattern::Variant { symbol, fields: vec![], trace: p.trace_from(start) })
+                }
+            }
+
+            // `..=end`
+            TokenData::Operator(Operator::DotDotEquals) => {
+                p.advance();
+                let end = Self::consume_literal(p)?;
+
+                Ok(UnvalidatedPattern::Range {
+                    start: None,
+                    end: Some(Box::new(end)),
+                    inclusive: true,
+                    trace: p.trace_from(start),
+                })
+            }
+            _ => {
+                let literal = Self::consume_literal(p)?;
+                Self::consume_range(p, start, literal)
+            }
+        }
+    }
+
+    /// Parses the fields of a structure pattern, the current token is the opening curly brace
+    fn consume_struct_pattern(
+        p: &mut Parser,
+        start: usize,
+        symbol: UnvalidatedSymbol,
+    ) -> ParserResult<UnvalidatedPattern> {
+        p.expect_operator(Operator::CurlyOpen)?;
+
+        let mut fields = vec![];
+        let mut rest = false;
+
+        while !p.is_eof() && !p.has_operator(Operator::CurlyClose) {
+            if p.has_operator(Operator::DotDot) {
+                p.advance();
+                rest = true;
+                break;
+            }
+
+            let field_start = p.position();
+            let field = p.consume_identifier()?;
+
+            let pattern = if p.has_operator(Operator::Colon) {
+                p.advance();
+                Self::consume_pattern(p)?
+            } else {
+                UnvalidatedPattern::Binding {
+                    ident: field.clone(),
+                    mutable: false,
+                    trace: p.trace_from(field_start),
+                }
+            };
+            fields.push(UnvalidatedFieldPattern { field, pattern });
+
+            if p.has_operator(Operator::Comma) {
+                p.advance();
+                continue;
+            }
+            break;
+        }
+        p.expect_operator(Operator::CurlyClose)?;
+
+        Ok(UnvalidatedPattern::Struct {
+            symbol,
+            fields,
+            rest,
+            trace: p.trace_from(start),
+        })
+    }
+
+    /// Turns the literal into a range pattern if it is followed by `..` or `..=`
+    fn consume_range(
+        p: &mut Parser,
+        start: usize,
+        literal: UnvalidatedExpression,
+    ) -> ParserResult<UnvalidatedPattern> {
+        let inclusive = if p.has_operator(Operator::DotDotEquals) {
+            true
+        } else if p.has_operator(Operator::DotDot) {
+            false
+        } else {
+            return Ok(UnvalidatedPattern::Literal(literal));
+        };
+        p.advance();
+
+        // `start..` is open ended, it has no upper bound to include
+        let end = if !inclusive && !Self::is_literal_start(p.curr().token()) {
+            None
+        } else {
+            Some(Box::new(Self::consume_literal(p)?))
+        };
+
+        Ok(UnvalidatedPattern::Range {
+            start: Some(Box::new(literal)),
+            end,
+            inclusive,
+            trace: p.trace_from(start),
+        })
+    }
+
+    fn is_literal_start(token: &TokenData) -> bool {
+        matches!(
+            token,
+            TokenData::StringLiteral(_)
+            | TokenData::CharLiteral(_)
+            | TokenData::BoolLiteral(_)
+            | TokenData::F32Literal(_)
+            | TokenData::F64Literal(_)
+            | TokenData::IntegerLiteral(_)
+            | TokenData::I32Literal(_)
+            | TokenData::I64Literal(_)
+            | TokenData::U32Literal(_)
+            | TokenData::U64Literal(_)
+            | TokenData::UsizeLiteral(_)
+            | TokenData::Operator(Operator::Minus)
+        )
+    }
+
+    fn consume_literal(p: &mut Parser) -> ParserResult<UnvalidatedExpression> {
+        let start = p.position();
+        let trace = *p.curr().trace();
+
+        let literal = match p.curr().token().clone() {
+            TokenData::StringLiteral(s) => UnvalidatedExpression::StringLiteral(s, trace),
+            TokenData::CharLiteral(c) => UnvalidatedExpression::CharLiteral(c, trace),
+            TokenData::BoolLiteral(b) => UnvalidatedExpression::BoolLiteral(b, trace),
+            TokenData::F32Literal(n) => UnvalidatedExpression::F32Literal(n, trace),
+            TokenData::F64Literal(n) => UnvalidatedExpression::F64Literal(n, trace),
+            TokenData::IntegerLiteral(n) => UnvalidatedExpression::IntegerLiteral(n, trace),
+            TokenData::I32Literal(n) => UnvalidatedExpression::I32Literal(n, trace),
+            TokenData::I64Literal(n) => UnvalidatedExpression::I64Literal(n, trace),
+            TokenData::U32Literal(n) => UnvalidatedExpression::U32Literal(n, trace),
+            TokenData::U64Literal(n) => UnvalidatedExpression::U64Literal(n, trace),
+            TokenData::UsizeLiteral(n) => UnvalidatedExpression::UsizeLiteral(n, trace),
+
+            // negative numbers, only the literal itself may follow the minus
+            TokenData::Operator(Operator::Minus) => {
+                p.advance();
+
+                let expr = match p.curr().token() {
+                    TokenData::F32Literal(_)
+                    | TokenData::F64Literal(_)
+                    | TokenData::IntegerLiteral(_)
+                    | TokenData::I32Literal(_)
+                    | TokenData::I64Literal(_) => Self::consume_literal(p)?,
+                    _ => return Err(ParserError::ExpectedPattern(p.curr().clone())),
+                };
+
+                return Ok(UnvalidatedExpression::Unary {
+                    expr: Box::new(expr),
+                    op: UnaryOperator::Negate,
+                    trace: p.trace_from(start),
+                });
+            }
+            _ => return Err(ParserError::ExpectedPattern(p.curr().clone())),
+        };
+        p.advance();
+
+        Ok(literal)
+    }
+}
@@ -1,11 +1,12 @@
 use crate::lexer::keyword::Keyword;
-use crate::lexer::token::Operator;
+use crate::lexer::token::{Operator, TokenData};
 use crate::parser::ast::data::{UnvalidatedVariableInfo, UnvalidatedType};
 use crate::parser::ast::expression::UnvalidatedExpression;
 use crate::parser::ast::function::UnvalidatedFunctionExpression;
 use crate::parser::ast::operations::UnaryOperator;
 use crate::parser::error::{ParserError, ParserResult};
 use crate::parser::modules::expression_parser::ExpressionParser;
+use crate::parser::modules::top_level::TopLevelParser;
 use crate::parser::parser::Parser;
 
 pub struct StatementParser;
@@ -14,12 +15,36 @@ pub struct StatementParser;
 type StatementParseResult = ParserResult<Option<UnvalidatedFunctionExpression>>;
 
 impl StatementParser {
+    /// Tokens that end a statement which failed to parse, closing braces end the enclosing block
+    /// and top level items end the enclosing function. A `;` ends the statement itself
+    pub fn is_statement_boundary(token: &TokenData) -> bool {
+        matches!(
+            token,
+            TokenData::Keyword(
+                Keyword::Let | Keyword::For | Keyword::While | Keyword::Until | Keyword::If | Keyword::Unless | Keyword::Return
+            ) | TokenData::Operator(Operator::CurlyClose | Operator::Semicolon)
+        ) || TopLevelParser::is_item_start(token)
+    }
+
+    /// Parses a statement along with the optional `;` terminating it. Without one the statement
+    /// ends where its expression can not continue, a line break only ends it before tokens that
+    /// could start a new statement as well, see [`Parser::continues_line`]
     pub fn consume_function_expression(
         p: &mut Parser
     ) -> ParserResult<UnvalidatedFunctionExpression> {
+        let statement = Self::consume_statement(p)?;
+
+        if p.has_operator(Operator::Semicolon) {
+            p.advance();
+        }
+        Ok(statement)
+    }
+
+    fn consume_statement(p: &mut Parser) -> ParserResult<UnvalidatedFunctionExpression> {
         let passes = [
             Self::parse_let_statement,
             Self::parse_while,
+            Self::parse_for,
             Self::parse_if,
             Self::parse_return_statement
         ];
@@ -39,14 +64,16 @@ impl StatementParser {
         }
         p.advance();
 
+        // the value is optional, `return` can end the statement on its own
         let expr = if p.has_keyword(Keyword::Unit) {
             p.advance();
             None
+        } else if p.ends_statement() {
+            None
         } else {
             Some(ExpressionParser::consume_expression(p)?)
         };
 
-
         Ok(Some(UnvalidatedFunctionExpression::Return(expr)))
     }
 
@@ -66,7 +93,7 @@ impl StatementParser {
 
         let condition = if until {
             UnvalidatedExpression::Unary {
-                trace: condition.trace().clone(),
+                trace: condition.trace(),
                 expr: Box::new(condition),
                 op: UnaryOperator::Not,
             }
@@ -86,6 +113,33 @@ impl StatementParser {
         }
     }
 
+    pub fn parse_for(p: &mut Parser) -> StatementParseResult {
+        if !p.has_keyword(Keyword::For) {
+            return Ok(None);
+        }
+        let start = p.position();
+
+        p.advance();
+
+        let variable = p.consume_identifier()?;
+        p.expect_keyword(Keyword::In)?;
+        let iterable = ExpressionParser::consume_expression(p)?;
+
+        if let Some(expr) = ExpressionParser::parse_block(p)? {
+            Ok(Some(UnvalidatedFunctionExpression::For {
+                variable,
+                iterable,
+                then: Box::new(expr),
+                trace: p.trace_from(start),
+            }))
+        } else {
+            Err(ParserError::ExpectedOperator(
+                Operator::CurlyOpen,
+                p.trace_from(start),
+            ))
+        }
+    }
+
     pub fn parse_if(p: &mut Parser) -> StatementParseResult {
         let unless = if p.has_keyword(Keyword::If) {
             false
@@ -103,23 +157,42 @@ impl StatementParser {
         let condition = if unless {
             UnvalidatedExpression::Unary {
                 op: UnaryOperator::Not,
-                trace: condition.trace().clone(),
+                trace: condition.trace(),
                 expr: Box::new(condition),
             }
         } else { condition };
 
-        if let Some(expr) = ExpressionParser::parse_block(p)? {
-            Ok(Some(UnvalidatedFunctionExpression::If {
-                condition,
-                then: Box::new(expr),
-                trace: p.trace_from(start),
-            }))
-        } else {
-            Err(ParserError::ExpectedOperator(
+        let Some(then) = ExpressionParser::parse_block(p)? else {
+            return Err(ParserError::ExpectedOperator(
                 Operator::CurlyOpen,
                 p.trace_from(start),
-            ))
+            ));
+        };
+
+        if !p.has_keyword(Keyword::Else) {
+            return Ok(Some(UnvalidatedFunctionExpression::If {
+                condition,
+                then: Box::new(then),
+                trace: p.trace_from(start),
+            }));
         }
+        p.advance();
+
+        // `else if` and `else unless` continue the chain, anything else has to be its last block
+        let otherwise = match Self::parse_if(p)? {
+            Some(otherwise) => otherwise,
+            None => match ExpressionParser::parse_block(p)? {
+                Some(block) => block.into(),
+                None => return Err(ParserError::ExpectedOperator(Operator::CurlyOpen, p.trace_from(start))),
+            },
+        };
+
+        Ok(Some(UnvalidatedExpression::IfElse {
+            condition: Box::new(condition),
+            then: Box::new(then.into()),
+            otherwise: Box::new(otherwise),
+            trace: p.trace_from(start),
+        }.into()))
     }
 
     pub fn parse_let_statement(p: &mut Parser) -> StatementParseResult {
@@ -147,7 +220,7 @@ impl StatementParser {
             UnvalidatedType::Implicit
         };
 
-        let initial = if p.has_operator(Operator::Equals) {
+        let initial = if p.has_operator(Operator::Assign) {
             p.advance();
             Some(ExpressionParser::consume_expression(p)?)
         } else {
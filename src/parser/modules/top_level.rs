@@ -1,12 +1,13 @@
-use crate::file::trace::Trace;
+use crate::file::identifier::{Identifier, Namespace};
 use crate::ir::visibility::Visibility;
 use crate::lexer::keyword::Keyword;
-use crate::lexer::token::Operator;
+use crate::lexer::token::{Operator, TokenData};
 use crate::parser::ast::data::{UnvalidatedVariableInfo, UnvalidatedType};
-use crate::parser::ast::expression::UnvalidatedExpression;
-use crate::parser::ast::function::{UnvalidatedFunctionPrototype, UnvalidatedFunctionExpression};
-use crate::parser::ast::r#struct::{UnvalidatedEnumData, UnvalidatedEnumVariant, UnvalidatedProperty, UnvalidatedStructProperty, UnvalidatedStructPrototype};
-use crate::parser::ast::top_level::UnvalidatedTopLevel;
+use crate::parser::ast::function::{UnvalidatedFunction, UnvalidatedFunctionExpression, UnvalidatedFunctionPrototype, RECEIVER, SELF_TYPE};
+use crate::parser::ast::r#struct::{UnvalidatedEnumData, UnvalidatedEnumPrototype, UnvalidatedEnumVariant, UnvalidatedProperty, UnvalidatedStructProperty, UnvalidatedStructPrototype};
+use crate::parser::ast::r#trait::{UnvalidatedTraitMethod, UnvalidatedTraitPrototype};
+use crate::parser::ast::top_level::{UnvalidatedImport, UnvalidatedTopLevel};
+use crate::parser::ast::UnvalidatedSymbol;
 use crate::parser::error::{ParserError, ParserResult};
 use crate::parser::modules::expression_parser::ExpressionParser;
 use crate::parser::parser::Parser;
@@ -15,11 +16,29 @@ pub struct TopLevelParser;
 
 
 impl TopLevelParser {
+    /// Tokens that can start a top level item, used to resynchronize after an error
+    pub fn is_item_start(token: &TokenData) -> bool {
+        matches!(
+            token,
+            TokenData::Keyword(Keyword::Fun | Keyword::Struct | Keyword::Enum | Keyword::Impl | Keyword::Trait | Keyword::Import | Keyword::Public | Keyword::Const)
+        )
+    }
+
+    /// Tokens that can start an item at the top level of a file, `let` only starts an item outside
+    /// of function bodies
+    pub fn is_top_level_start(token: &TokenData) -> bool {
+        Self::is_item_start(token) || token == &TokenData::Keyword(Keyword::Let)
+    }
+
     pub fn parse_top_level(p: &mut Parser) -> ParserResult<Option<UnvalidatedTopLevel>> {
         let passes = [
             Self::parse_function,
             Self::parse_import,
-            Self::parse_struct
+            Self::parse_struct,
+            Self::parse_enum,
+            Self::parse_impl,
+            Self::parse_trait,
+            Self::parse_global,
         ];
 
         for pass in passes {
@@ -38,12 +57,127 @@ impl TopLevelParser {
         let start = p.position();
         p.advance();
 
-        let namespace = p.consume_symbol()?;
+        let mut imports = vec![];
+        Self::consume_import_tree(p, &[], &mut imports)?;
 
         Ok(Some(UnvalidatedTopLevel::Import {
-            namespace,
-            // TODO allow star imports
+            imports,
+            trace: p.trace_from(start),
+        }))
+    }
+
+    /// Parses a path following `prefix` that ends in a name, a `*` or a group of paths in braces,
+    /// every imported path is added to `imports`
+    fn consume_import_tree(p: &mut Parser, prefix: &[Identifier], imports: &mut Vec<UnvalidatedImport>) -> ParserResult<()> {
+        let start = p.position();
+        let mut chain = prefix.to_vec();
+
+        loop {
+            // `a::{b, c}` imports `a::b` and `a::c`
+            if !chain.is_empty() && p.has_operator(Operator::CurlyOpen) {
+                p.advance();
+
+                while !p.is_eof() && !p.has_operator(Operator::CurlyClose) {
+                    Self::consume_import_tree(p, &chain, imports)?;
+
+                    if !p.has_operator(Operator::Comma) {
+                        break;
+                    }
+                    p.advance();
+                }
+                return p.expect_operator(Operator::CurlyClose);
+            }
+
+            if !chain.is_empty() && p.has_operator(Operator::Multiply) {
+                p.advance();
+                imports.push(UnvalidatedImport {
+                    path: symbol(chain),
+                    star: true,
+                    alias: None,
+                    trace: p.trace_from(start),
+                });
+                return Ok(());
+            }
+
+            chain.push(p.consume_identifier()?);
+
+            if !p.has_operator(Operator::DoubleColon) {
+                break;
+            }
+            p.advance();
+        }
+
+        let alias = if p.has_keyword(Keyword::As) {
+            p.advance();
+            Some(p.consume_identifier()?)
+        } else {
+            None
+        };
+
+        imports.push(UnvalidatedImport {
+            path: symbol(chain),
             star: false,
+            alias,
+            trace: p.trace_from(start),
+        });
+        Ok(())
+    }
+
+    /// `[pub] let [mut] name [: type] = initial` or `[pub] const NAME [: type] = initial`
+    fn parse_global(p: &mut Parser) -> ParserResult<Option<UnvalidatedTopLevel>> {
+        let start = p.position();
+
+        let public = if p.has_keyword(Keyword::Public) {
+            p.advance();
+            true
+        } else {
+            false
+        };
+
+        let constant = match p.curr().token() {
+            TokenData::Keyword(Keyword::Let) => false,
+            TokenData::Keyword(Keyword::Const) => true,
+            _ => {
+                // rollback to before visibility keyword
+                if public {
+                    p.rollback();
+                }
+                return Ok(None);
+            }
+        };
+
+        p.advance();
+
+        // constants can never be assigned to
+        let mutable = if !constant && p.has_keyword(Keyword::Mut) {
+            p.advance();
+            true
+        } else {
+            false
+        };
+
+        let ident = p.consume_identifier()?;
+
+        let ty = if p.has_operator(Operator::Colon) {
+            p.advance();
+            p.consume_type()?
+        } else {
+            UnvalidatedType::Implicit
+        };
+
+        // globals are initialized before the program starts, they always need a value
+        p.expect_operator(Operator::Assign)?;
+        let initial = ExpressionParser::consume_expression(p)?;
+
+        Ok(Some(UnvalidatedTopLevel::GlobalVariable {
+            variable: UnvalidatedVariableInfo {
+                ident,
+                ty,
+                mutable,
+            },
+            initial,
+            constant,
+            visibility: if public { Visibility::Public } else { Visibility::Private },
             trace: p.trace_from(start),
         }))
     }
@@ -69,6 +203,7 @@ impl TopLevelParser {
         p.advance();
 
         let identifier = p.consume_identifier()?;
+        let generics = p.consume_generic_parameters()?;
 
         let mut properties = vec![];
         p.expect_operator(Operator::CurlyOpen)?;
@@ -92,7 +227,12 @@ impl TopLevelParser {
                     ty,
                 },
                 visibility,
-            })
+            });
+
+            // properties are separated by new lines, commas are optional
+            if p.has_operator(Operator::Comma) {
+                p.advance();
+            }
         }
 
 
@@ -100,6 +240,7 @@ impl TopLevelParser {
         Ok(Some(UnvalidatedTopLevel::StructDefinition {
             proto: UnvalidatedStructPrototype {
                 identifier,
+                generics,
                 properties,
                 visibility: if public { Visibility::Public } else { Visibility::Private },
             },
@@ -127,6 +268,9 @@ impl TopLevelParser {
 
         p.advance();
 
+        let identifier = p.consume_identifier()?;
+        let generics = p.consume_generic_parameters()?;
+
         let mut variants = vec![];
         p.expect_operator(Operator::CurlyOpen)?;
 
@@ -165,7 +309,11 @@ impl TopLevelParser {
                     properties.push(UnvalidatedProperty {
                         name,
                         ty,
-                    })
+                    });
+
+                    if p.has_operator(Operator::Comma) {
+                        p.advance();
+                    }
                 }
                 p.expect_operator(Operator::CurlyClose)?;
 
@@ -178,20 +326,28 @@ impl TopLevelParser {
                     name,
                     data: None,
                 }
-            })
+            });
+
+            // variants are separated by new lines, commas are optional
+            if p.has_operator(Operator::Comma) {
+                p.advance();
+            }
         }
 
 
         p.expect_operator(Operator::CurlyClose)?;
         Ok(Some(UnvalidatedTopLevel::EnumDefinition {
-            variants,
+            proto: UnvalidatedEnumPrototype {
+                identifier,
+                generics,
+                variants,
+                visibility: if public { Visibility::Public } else { Visibility::Private },
+            },
             trace: p.trace_from(start),
         }))
     }
 
     fn parse_function(p: &mut Parser) -> ParserResult<Option<UnvalidatedTopLevel>> {
-        let start = p.position();
-
         let stepped = if p.has_keyword(Keyword::Public) {
             p.advance();
             true
@@ -210,22 +366,154 @@ impl TopLevelParser {
             p.rollback();
         }
 
+        let UnvalidatedFunction { proto, body, trace } = Self::consume_function(p)?;
+
+        Ok(Some(UnvalidatedTopLevel::FunctionDefinition { proto, body, trace }))
+    }
+
+    fn consume_function(p: &mut Parser) -> ParserResult<UnvalidatedFunction> {
+        let start = p.position();
         let proto = Self::consume_function_prototype(p)?;
 
-        let body = if p.has_operator(Operator::ThickRightArrow) {
+        let Some(body) = Self::parse_function_body(p)? else {
+            return Err(ParserError::ExpectedFunctionBody(p.trace_from(start)));
+        };
+
+        Ok(UnvalidatedFunction {
+            proto,
+            body,
+            trace: p.trace_from(start),
+        })
+    }
+
+    /// Parses a `=> expression` or block body following a function prototype
+    fn parse_function_body(p: &mut Parser) -> ParserResult<Option<UnvalidatedFunctionExpression>> {
+        if p.has_operator(Operator::ThickRightArrow) {
+            p.advance();
+            return Ok(Some(ExpressionParser::consume_expression(p)?.into()));
+        }
+
+        Ok(ExpressionParser::parse_block(p)?.map(Into::into))
+    }
+
+    fn parse_trait(p: &mut Parser) -> ParserResult<Option<UnvalidatedTopLevel>> {
+        let start = p.position();
+
+        let public = if p.has_keyword(Keyword::Public) {
             p.advance();
-            ExpressionParser::consume_expression(p)?.into()
+            true
         } else {
-            if let Some(block) = ExpressionParser::parse_block(p)? {
-                block.into()
-            } else {
-                return Err(ParserError::ExpectedFunctionBody(p.trace_from(start)));
+            false
+        };
+
+        if !p.has_keyword(Keyword::Trait) {
+            // rollback to before visibility keyword
+            if public {
+                p.rollback();
+            }
+            return Ok(None);
+        }
+
+        p.advance();
+
+        let identifier = p.consume_identifier()?;
+        let generics = p.consume_generic_parameters()?;
+
+        p.expect_operator(Operator::CurlyOpen)?;
+
+        let mut methods = vec![];
+
+        while !p.is_eof() && !p.has_operator(Operator::CurlyClose) {
+            let method_start = p.position();
+
+            // required methods are only a prototype, provided ones are followed by their body
+            let method = Self::consume_function_prototype(p).and_then(|proto| Ok(UnvalidatedTraitMethod {
+                proto,
+                body: Self::parse_function_body(p)?,
+                trace: p.trace_from(method_start),
+            }));
+
+            match method {
+                Ok(method) => methods.push(method),
+                Err(err) => {
+                    p.report(err);
+                    p.synchronize(method_start, |token| {
+                        Self::is_item_start(token) || token == &TokenData::Operator(Operator::CurlyClose)
+                    });
+
+                    // the trait was never closed, leave the item to the top level
+                    if !p.has_keyword(Keyword::Fun) && !p.has_keyword(Keyword::Public) && !p.has_operator(Operator::CurlyClose) {
+                        break;
+                    }
+                }
             }
+        }
+        p.expect_operator(Operator::CurlyClose)?;
+
+        Ok(Some(UnvalidatedTopLevel::TraitDefinition {
+            proto: UnvalidatedTraitPrototype {
+                identifier,
+                generics,
+                methods,
+                visibility: if public { Visibility::Public } else { Visibility::Private },
+            },
+            trace: p.trace_from(start),
+        }))
+    }
+
+    fn parse_impl(p: &mut Parser) -> ParserResult<Option<UnvalidatedTopLevel>> {
+        if !p.has_keyword(Keyword::Impl) {
+            return Ok(None);
+        }
+
+        let start = p.position();
+        p.advance();
+
+        let generics = p.consume_generic_parameters()?;
+        let type_start = p.position();
+        let ty = p.consume_type()?;
+
+        // `impl Trait for Type`
+        let (target, implements) = if p.has_keyword(Keyword::For) {
+            // traits are named, they can not be a reference or array type
+            if !matches!(ty, UnvalidatedType::Type(..) | UnvalidatedType::Template { .. }) {
+                return Err(ParserError::ExpectedIdentifier(p.trace_from(type_start)));
+            }
+            p.advance();
+            (p.consume_type()?, Some(ty))
+        } else {
+            (ty, None)
         };
 
-        Ok(Some(UnvalidatedTopLevel::FunctionDefinition {
-            proto,
-            body,
+        p.expect_operator(Operator::CurlyOpen)?;
+
+        let mut functions = vec![];
+
+        while !p.is_eof() && !p.has_operator(Operator::CurlyClose) {
+            let function_start = p.position();
+
+            match Self::consume_function(p) {
+                Ok(function) => functions.push(function),
+                Err(err) => {
+                    p.report(err);
+                    p.synchronize(function_start, |token| {
+                        Self::is_item_start(token) || token == &TokenData::Operator(Operator::CurlyClose)
+                    });
+
+                    // the impl block was never closed, leave the item to the top level
+                    if !p.has_keyword(Keyword::Fun) && !p.has_keyword(Keyword::Public) && !p.has_operator(Operator::CurlyClose) {
+                        break;
+                    }
+                }
+            }
+        }
+        p.expect_operator(Operator::CurlyClose)?;
+
+        Ok(Some(UnvalidatedTopLevel::ImplBlock {
+            generics,
+            target,
+            implements,
+            functions,
             trace: p.trace_from(start),
         }))
     }
@@ -241,7 +529,7 @@ impl TopLevelParser {
         p.expect_keyword(Keyword::Fun)?;
 
         let name = p.consume_identifier()?;
-
+        let generics = p.consume_generic_parameters()?;
 
         let mut arguments = vec![];
 
@@ -255,6 +543,22 @@ impl TopLevelParser {
                 false
             };
 
+            if arguments.is_empty() {
+                if let Some(receiver) = Self::consume_receiver(p)? {
+                    arguments.push(UnvalidatedVariableInfo {
+                        ident: RECEIVER.into(),
+                        ty: receiver,
+                        mutable,
+                    });
+
+                    if !p.has_operator(Operator::Comma) {
+                        break;
+                    }
+                    p.advance();
+                    continue;
+                }
+            }
+
             let arg_name = p.consume_identifier()?;
 
             p.expect_operator(Operator::Colon)?;
@@ -275,18 +579,60 @@ impl TopLevelParser {
         }
         p.expect_operator(Operator::ParenClose)?;
 
+        // `=> expression` bodies return the type of their expression unless it is written out
         let returns = if p.has_operator(Operator::Colon) {
             p.expect_operator(Operator::Colon)?;
             p.consume_type()?
+        } else if p.has_operator(Operator::ThickRightArrow) {
+            UnvalidatedType::Implicit
         } else {
             UnvalidatedType::Unit
         };
 
         Ok(UnvalidatedFunctionPrototype {
             name,
+            generics,
             arguments,
             returns,
             visibility,
         })
     }
+
+    /// Parses the `self`, `ref[self]` or `[self]` receiver of a method into the type of the
+    /// receiver argument
+    fn consume_receiver(p: &mut Parser) -> ParserResult<Option<UnvalidatedType>> {
+        let is_self = |p: &Parser, offset: usize| {
+            matches!(p.peek(offset).token(), TokenData::Identifier(ident) if ident.as_str() == RECEIVER)
+        };
+        let start = p.position();
+        let self_type = |p: &Parser| UnvalidatedType::Type(SELF_TYPE.into(), p.trace_from(start));
+
+        if is_self(p, 0) && !matches!(p.peek(1).token(), TokenData::Operator(Operator::Colon)) {
+            p.advance();
+            return Ok(Some(self_type(p)));
+        }
+
+        if p.has_operator(Operator::BracketOpen) && is_self(p, 1) {
+            p.advance();
+            p.advance();
+            p.expect_operator(Operator::BracketClose)?;
+            return Ok(Some(UnvalidatedType::Array(Box::new(self_type(p)))));
+        }
+
+        if p.has_keyword(Keyword::Ref) && is_self(p, 2) {
+            p.advance();
+            p.expect_operator(Operator::BracketOpen)?;
+            p.advance();
+            p.expect_operator(Operator::BracketClose)?;
+            return Ok(Some(UnvalidatedType::Reference(Box::new(self_type(p)))));
+        }
+
+        Ok(None)
+    }
+}
+
+/// Symbol naming the last item of a non empty path
+fn symbol(mut chain: Vec<Identifier>) -> UnvalidatedSymbol {
+    let identifier = chain.pop().expect("Paths name at least one item");
+    UnvalidatedSymbol { explicit_namespace: Namespace { chain }, identifier }
 }
@@ -1,33 +1,58 @@
 use std::ops::Range;
-use std::rc::Rc;
 use crate::file::identifier::{Identifier, Namespace};
-use crate::file::source_file::SourceFile;
+use crate::file::source_map::FileId;
 use crate::file::trace::Trace;
 use crate::lexer::keyword::Keyword;
-use crate::lexer::token::{Operator, Token, TokenData};
+use crate::lexer::token::{Operator, Token, TokenData, TokenStream};
 use crate::parser::ast::data::UnvalidatedType;
 use crate::parser::ast::top_level::UnvalidatedTopLevel;
 use crate::parser::ast::UnvalidatedSymbol;
 use crate::parser::error::{ParserError, ParserResult};
 use crate::parser::modules::top_level::TopLevelParser;
 
+/// What a line break ends, see [`Parser::continues_line`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LineBreaks {
+    /// Inside of parentheses and brackets line breaks have no meaning
+    Ignored,
+
+    /// Inside of blocks a line break can end a statement
+    EndStatement,
+
+    /// In between the arms of a match a line break can end the body of an arm
+    EndArm,
+}
+
 pub struct Parser {
-    source: Vec<Token>,
-    file: Rc<SourceFile>,
+    source: TokenStream,
+    file: FileId,
     position: usize,
+
+    /// What a line break in front of an ambiguous token ends
+    line_breaks: LineBreaks,
+
+    /// Errors the parser has recovered from
+    errors: Vec<ParserError>,
 }
 
 impl Parser {
-    pub fn new(file: Rc<SourceFile>, tokens: Vec<Token>) -> Self {
+    pub fn new(file: FileId, tokens: impl Into<TokenStream>) -> Self {
         Self {
-            source: tokens,
+            source: tokens.into(),
             file,
             position: 0,
+            line_breaks: LineBreaks::EndStatement,
+            errors: vec![],
         }
     }
 
     pub fn curr(&self) -> &Token {
-        &self.source[self.position.min(self.source.len())]
+        &self.source[self.position.min(self.source.len() - 1)]
+    }
+
+    /// Token `offset` tokens after the current one, the end of file token if there is none
+    pub fn peek(&self, offset: usize) -> &Token {
+        &self.source[(self.position + offset).min(self.source.len() - 1)]
     }
 
     pub fn advance(&mut self) -> &Token {
@@ -39,28 +64,145 @@ impl Parser {
         self.position -= 1;
     }
 
+    /// Whether a line break separates the current token from the one before it
+    pub fn starts_line(&self) -> bool {
+        self.source.starts_line(self.position.min(self.source.len() - 1))
+    }
+
     #[inline]
     pub fn position(&self) -> usize {
         self.position
     }
 
     pub fn trace(&self, range: Range<usize>) -> Trace {
-        self.file.trace(range)
+        Trace::new(self.file, range)
     }
 
+    /// Trace covering every token from the given index up to the last consumed token
     pub fn trace_from(&self, token_index: usize) -> Trace {
-        let prev = self.source[token_index].trace().range.clone();
-        let curr = self.curr().trace().range.clone();
+        let first = *self.source[token_index].trace();
+
+        if self.position <= token_index {
+            return first;
+        }
 
-        let min = prev.start.min(curr.start);
-        let max = prev.end.max(curr.end);
-        self.trace(min..max)
+        let last = self.position.min(self.source.len()) - 1;
+        first.to(*self.source[last].trace())
     }
 
     pub fn is_eof(&self) -> bool {
         self.curr().token() == &TokenData::EOF
     }
 
+    /// Parses a token stream embedded into a token of this file, like the expressions inside of
+    /// a format string. The whole stream has to be consumed by `parse`
+    pub fn parse_nested<T>(
+        &mut self,
+        tokens: Vec<Token>,
+        parse: impl FnOnce(&mut Parser) -> ParserResult<T>,
+    ) -> ParserResult<T> {
+        let mut nested = Parser::new(self.file, tokens);
+        nested.line_breaks = LineBreaks::Ignored;
+        let result = parse(&mut nested);
+
+        self.errors.append(&mut nested.errors);
+
+        let value = result?;
+        if !nested.is_eof() {
+            return Err(ParserError::UnexpectedToken(nested.curr().clone()));
+        }
+        Ok(value)
+    }
+
+    /// Parses a token stream that is not embedded into any token of a file, like the arguments of
+    /// a macro call. The whole stream has to be consumed by `parse`, errors the parser recovered
+    /// from are returned as well
+    pub fn parse_detached<T>(
+        file: FileId,
+        tokens: Vec<Token>,
+        parse: impl FnOnce(&mut Parser) -> ParserResult<T>,
+    ) -> Result<T, Vec<ParserError>> {
+        let mut parser = Parser::new(file, tokens);
+        parser.line_breaks = LineBreaks::Ignored;
+        let result = parse(&mut parser);
+
+        let error = match result {
+            Ok(value) if parser.is_eof() && parser.errors.is_empty() => return Ok(value),
+            Ok(_) if !parser.is_eof() => Some(ParserError::UnexpectedToken(parser.curr().clone())),
+            Ok(_) => None,
+            Err(err) => Some(err),
+        };
+
+        parser.errors.extend(error);
+        Err(parser.errors)
+    }
+
+    /// Parses with line breaks ending statements, match arms or nothing, like inside of a block or
+    /// inside of parentheses. The previous behaviour is restored afterward
+    pub fn with_line_breaks<T>(&mut self, line_breaks: LineBreaks, parse: impl FnOnce(&mut Parser) -> T) -> T {
+        let previous = std::mem::replace(&mut self.line_breaks, line_breaks);
+        let result = parse(self);
+        self.line_breaks = previous;
+        result
+    }
+
+    /// Whether the current token, an operator that can also start an expression like `-` or `(`,
+    /// continues the expression before it. A line break in front of it ends the statement instead,
+    /// which is reported since the line could have been meant either way. In between match arms
+    /// the line break ends the arm, the next arm can not be mistaken for a statement
+    pub fn continues_line(&mut self) -> bool {
+        if self.line_breaks == LineBreaks::Ignored || !self.starts_line() {
+            return true;
+        }
+
+        if let (LineBreaks::EndStatement, TokenData::Operator(op)) = (self.line_breaks, self.curr().token()) {
+            self.report(ParserError::AmbiguousContinuation(*op, *self.curr().trace()));
+        }
+        false
+    }
+
+    /// Whether the current token ends the statement or arm before it without being part of it,
+    /// a `;`, `,`, closing brace, the end of the file or a line break where line breaks matter
+    pub fn ends_statement(&self) -> bool {
+        matches!(
+            self.curr().token(),
+            TokenData::Operator(Operator::Semicolon | Operator::Comma | Operator::CurlyClose) | TokenData::EOF
+        ) || (self.line_breaks != LineBreaks::Ignored && self.starts_line())
+    }
+
+    /// Records an error the parser is going to recover from
+    pub fn report(&mut self, error: ParserError) {
+        self.errors.push(error);
+    }
+
+    /// Panic mode recovery, skips tokens until one matching `is_boundary` is found outside of any
+    /// brackets opened while skipping. Always skips at least one token if nothing was consumed
+    /// since `start` so the parser can not get stuck on the same token.
+    pub fn synchronize(&mut self, start: usize, is_boundary: fn(&TokenData) -> bool) {
+        let mut depth = 0usize;
+
+        // a bracket skipped this way still has to be closed before a boundary counts
+        if self.position == start && !self.is_eof() {
+            if is_opening_bracket(self.curr().token()) {
+                depth += 1;
+            }
+            self.advance();
+        }
+
+        while !self.is_eof() {
+            match self.curr().token() {
+                token if is_opening_bracket(token) => depth += 1,
+                TokenData::Operator(Operator::CurlyClose | Operator::ParenClose | Operator::BracketClose) if depth > 0 => {
+                    depth -= 1;
+                }
+                token if depth == 0 && is_boundary(token) => return,
+                _ => {}
+            }
+            self.advance();
+        }
+    }
+
+    #[allow(dead_code)]
     pub fn consume_tok(&mut self, tok: TokenData) -> ParserResult<()> {
         if self.curr().token() == &tok {
             self.advance();
@@ -71,10 +213,7 @@ impl Parser {
     }
 
     pub fn has_identifier(&mut self) -> bool {
-        match self.curr().token() {
-            TokenData::Identifier(_) => true,
-            _ => false
-        }
+        matches!(self.curr().token(), TokenData::Identifier(_))
     }
 
     pub fn consume_identifier(&mut self) -> ParserResult<Identifier> {
@@ -85,10 +224,11 @@ impl Parser {
                 self.advance();
                 Ok(ident.clone())
             }
-            _ => Err(ParserError::ExpectedIdentifier(curr.trace().clone()))
+            _ => Err(ParserError::ExpectedIdentifier(*curr.trace()))
         }
     }
 
+    #[allow(dead_code)]
     pub fn consume_operator(&mut self) -> ParserResult<Operator> {
         let curr = self.curr().clone();
         self.advance();
@@ -97,34 +237,28 @@ impl Parser {
                 self.advance();
                 Ok(*op)
             }
-            _ => Err(ParserError::ExpectedAnyOperator(curr.trace().clone()))
+            _ => Err(ParserError::ExpectedAnyOperator(*curr.trace()))
         }
     }
 
     pub fn has_operator(&self, op: Operator) -> bool {
-        match self.curr().token() {
-            TokenData::Operator(curr) if curr == &op => true,
-            _ => false
-        }
+        matches!(self.curr().token(), TokenData::Operator(curr) if curr == &op)
     }
 
     pub fn expect_operator(&mut self, op: Operator) -> ParserResult<()> {
-        if self.has_operator(op.clone()) {
+        if self.has_operator(op) {
             self.advance();
             Ok(())
         } else {
             Err(ParserError::ExpectedOperator(
                 op,
-                self.curr().trace().clone(),
+                *self.curr().trace(),
             ))
         }
     }
 
     pub fn has_keyword(&self, word: Keyword) -> bool {
-        match self.curr().token() {
-            TokenData::Keyword(curr) if curr == &word => true,
-            _ => false
-        }
+        matches!(self.curr().token(), TokenData::Keyword(curr) if curr == &word)
     }
 
     pub fn expect_keyword(&mut self, word: Keyword) -> ParserResult<()> {
@@ -134,13 +268,13 @@ impl Parser {
         } else {
             Err(ParserError::ExpectedKeyword(
                 word,
-                self.curr().trace().clone(),
+                *self.curr().trace(),
             ))
         }
     }
 
     pub fn consume_symbol(&mut self) -> ParserResult<UnvalidatedSymbol> {
-        let mut identifier = self.consume_identifier()?;
+        let identifier = self.consume_identifier()?;
 
         if !self.has_operator(Operator::DoubleColon) {
             return Ok(UnvalidatedSymbol {
@@ -164,23 +298,79 @@ impl Parser {
         })
     }
 
-    pub fn parse(mut self) -> ParserResult<Vec<UnvalidatedTopLevel>> {
+    /// Parses the whole file, recovering from syntax errors so that as many as possible are
+    /// reported at once. Items that failed to parse are left as [`UnvalidatedTopLevel::Error`].
+    pub fn parse(mut self) -> (Vec<UnvalidatedTopLevel>, Vec<ParserError>) {
         let mut statements = vec![];
 
         let passes = &[TopLevelParser::parse_top_level];
 
         'file_loop: while !self.is_eof() {
+            // items do not need to be terminated, stray semicolons between them are skipped
+            if self.has_operator(Operator::Semicolon) {
+                self.advance();
+                continue;
+            }
+
+            let start = self.position();
+
             for pass in passes {
-                if let Some(statement) = pass(&mut self)? {
-                    statements.push(statement);
-                    continue 'file_loop;
+                match pass(&mut self) {
+                    Ok(Some(statement)) => {
+                        statements.push(statement);
+                        continue 'file_loop;
+                    }
+                    Ok(None) => {}
+                    Err(err) => {
+                        self.report(err);
+                        self.synchronize(start, TopLevelParser::is_top_level_start);
+                        statements.push(UnvalidatedTopLevel::Error(self.trace_from(start)));
+                        continue 'file_loop;
+                    }
                 }
             }
-            return Err(ParserError::UnexpectedToken(self.curr().clone()));
+
+            self.report(ParserError::UnexpectedToken(self.curr().clone()));
+            self.synchronize(start, TopLevelParser::is_top_level_start);
+            statements.push(UnvalidatedTopLevel::Error(self.trace_from(start)));
+        }
+
+        (statements, self.errors)
+    }
+
+    /// Parses the type parameters of a declaration like `<T, E>`, no parameters if there is no `<`
+    pub fn consume_generic_parameters(&mut self) -> ParserResult<Vec<Identifier>> {
+        if !self.has_operator(Operator::Less) {
+            return Ok(vec![]);
+        }
+        self.advance();
+
+        let mut parameters = vec![];
+
+        while !self.has_operator(Operator::Greater) && !self.is_eof() {
+            parameters.push(self.consume_identifier()?);
+
+            if !self.has_operator(Operator::Comma) {
+                break;
+            }
+            self.advance();
         }
+        self.expect_operator(Operator::Greater)?;
+
+        Ok(parameters)
+    }
 
+    /// Consumes the `>` closing a template argument list, the `>>` closing two nested lists is
+    /// lexed as a single shift operator and gets split into two tokens
+    fn expect_closing_angle(&mut self) -> ParserResult<()> {
+        if let TokenData::Operator(Operator::BitShiftRight) = self.curr().token() {
+            let Trace { lo, hi, .. } = *self.curr().trace();
+            let (lo, hi) = (lo as usize, hi as usize);
 
-        Ok(statements)
+            self.source[self.position] = Token(TokenData::Operator(Operator::Greater), self.trace(lo..lo + 1));
+            self.source.insert(self.position + 1, Token(TokenData::Operator(Operator::Greater), self.trace(lo + 1..hi)));
+        }
+        self.expect_operator(Operator::Greater)
     }
 
     pub fn consume_type(&mut self) -> ParserResult<UnvalidatedType> {
@@ -197,6 +387,7 @@ impl Parser {
             self.expect_operator(Operator::BracketClose)?;
             Ok(UnvalidatedType::Array(Box::new(ty)))
         } else if self.has_identifier() {
+            let start = self.position();
             let ty = self.consume_identifier()?;
 
             if self.has_operator(Operator::Less) {
@@ -206,22 +397,27 @@ impl Parser {
                 while !self.has_operator(Operator::Greater) && !self.is_eof() {
                     template_arguments.push(self.consume_type()?);
 
-                    if self.has_operator(Operator::Comma) {
-                        self.advance();
+                    if !self.has_operator(Operator::Comma) {
+                        break;
                     }
-                    break;
+                    self.advance();
                 }
-                self.expect_operator(Operator::Greater)?;
+                self.expect_closing_angle()?;
 
                 Ok(UnvalidatedType::Template {
                     base: ty,
                     template_arguments,
+                    trace: self.trace_from(start),
                 })
             } else {
-                Ok(UnvalidatedType::Type(ty))
+                Ok(UnvalidatedType::Type(ty, self.trace_from(start)))
             }
         } else {
             Err(ParserError::UnexpectedToken(self.curr().clone()))
         }
     }
 }
+
+fn is_opening_bracket(token: &TokenData) -> bool {
+    matches!(token, TokenData::Operator(Operator::CurlyOpen | Operator::ParenOpen | Operator::BracketOpen))
+}
@@ -1,25 +1,31 @@
-use std::rc::Rc;
 use crate::file::identifier::{Identifier, Namespace};
 use crate::file::source_file::SourceFile;
+use crate::file::source_map::{FileId, SourceMap};
 use crate::file::trace::Trace;
 use crate::ir::visibility::Visibility;
 use crate::parser::ast::data::{UnvalidatedVariableInfo, UnvalidatedType};
-use crate::parser::ast::expression::UnvalidatedExpression;
-use crate::parser::ast::function::{UnvalidatedFunctionPrototype, UnvalidatedFunctionExpression};
-use crate::parser::ast::operations::BinaryOperation;
-use crate::parser::ast::r#struct::{UnvalidatedProperty, UnvalidatedStructProperty, UnvalidatedStructPrototype};
+use crate::parser::ast::expression::{UnvalidatedExpression, UnvalidatedFormatSegment};
+use crate::parser::ast::function::{UnvalidatedFunctionExpression, UnvalidatedFunctionPrototype};
+use crate::parser::ast::operations::{BinaryOperation, UnaryOperator};
+use crate::parser::ast::pattern::{UnvalidatedFieldPattern, UnvalidatedMatchArm, UnvalidatedPattern};
+use crate::parser::ast::r#struct::{UnvalidatedEnumData, UnvalidatedEnumPrototype, UnvalidatedEnumVariant, UnvalidatedProperty, UnvalidatedStructProperty, UnvalidatedStructPrototype};
 use crate::parser::ast::top_level::UnvalidatedTopLevel;
 use crate::parser::ast::UnvalidatedSymbol;
-use crate::parser::error::ParserResult;
+use crate::parser::error::{ParserError, ParserResult};
+use crate::parser::expansion::expand_macros;
+use crate::lexer::token::{Operator, Token, TokenData};
 use crate::parser::parser::Parser;
 
 fn parse_from(source: &str) -> (ParserResult<Vec<UnvalidatedTopLevel>>, Box<dyn Fn() -> Trace>) {
-    let source = SourceFile::new(source).rc();
-    let trace = source.trace(0..0);
-    let tokens = crate::lexer::tokenize(Rc::clone(&source));
+    let mut sources = SourceMap::new();
+    let file = sources.add(SourceFile::new(source));
+    let (tokens, _) = crate::lexer::tokenize(&sources[file]);
 
-    let trace = move || trace.clone();
-    (Parser::new(source, tokens).parse(), Box::new(trace))
+    let trace = move || Trace::new(file, 0..0);
+    let (statements, mut errors) = Parser::new(file, tokens).parse();
+
+    let result = if errors.is_empty() { Ok(statements) } else { Err(errors.remove(0)) };
+    (result, Box::new(trace))
 }
 
 #[test]
@@ -34,6 +40,7 @@ fn function_proto() {
         UnvalidatedTopLevel::FunctionDefinition {
             proto: UnvalidatedFunctionPrototype {
                 name: Identifier("no_arg".into()),
+                generics: vec![],
                 arguments: vec![],
                 returns: UnvalidatedType::Unit,
                 visibility: Visibility::Private,
@@ -44,15 +51,16 @@ fn function_proto() {
         UnvalidatedTopLevel::FunctionDefinition {
             proto: UnvalidatedFunctionPrototype {
                 name: Identifier("args".into()),
+                generics: vec![],
                 arguments: vec![
                     UnvalidatedVariableInfo {
                         ident: "_0".into(),
-                        ty: UnvalidatedType::Type("i32".into()),
+                        ty: UnvalidatedType::Type("i32".into(), trace()),
                         mutable: false,
                     },
                     UnvalidatedVariableInfo {
                         ident: "_1".into(),
-                        ty: UnvalidatedType::Type("i64".into()),
+                        ty: UnvalidatedType::Type("i64".into(), trace()),
                         mutable: false,
                     },
                 ],
@@ -65,8 +73,9 @@ fn function_proto() {
         UnvalidatedTopLevel::FunctionDefinition {
             proto: UnvalidatedFunctionPrototype {
                 name: Identifier("returns".into()),
+                generics: vec![],
                 arguments: vec![],
-                returns: UnvalidatedType::Reference(Box::new(UnvalidatedType::Type("i32".into()))),
+                returns: UnvalidatedType::Reference(Box::new(UnvalidatedType::Type("i32".into(), trace()))),
                 visibility: Visibility::Private,
             },
             body: UnvalidatedExpression::Scope(vec![], trace()).into(),
@@ -85,11 +94,12 @@ fn function_oneline() {
         UnvalidatedTopLevel::FunctionDefinition {
             proto: UnvalidatedFunctionPrototype {
                 name: Identifier("funny".into()),
+                generics: vec![],
                 arguments: vec![],
-                returns: UnvalidatedType::Unit,
+                returns: UnvalidatedType::Implicit,
                 visibility: Visibility::Private,
             },
-            body: UnvalidatedExpression::I32Literal(69, trace()).into(),
+            body: UnvalidatedExpression::IntegerLiteral(69, trace()).into(),
             trace: trace(),
         },
     ]))
@@ -108,31 +118,32 @@ fn function_operator_precedence() {
         UnvalidatedTopLevel::FunctionDefinition {
             proto: UnvalidatedFunctionPrototype {
                 name: Identifier("funny".into()),
+                generics: vec![],
                 arguments: vec![],
-                returns: UnvalidatedType::Unit,
+                returns: UnvalidatedType::Implicit,
                 visibility: Visibility::Private,
             },
             body: E::Binary {
-                lhs: Box::new(E::I32Literal(2, trace())),
+                lhs: Box::new(E::IntegerLiteral(2, trace())),
                 op: BinaryOperation::Assign,
                 rhs: Box::new(E::Binary {
                     lhs: Box::new(E::Binary {
                         lhs: Box::new(E::Binary {
-                            lhs: Box::new(E::I32Literal(1, trace())),
+                            lhs: Box::new(E::IntegerLiteral(1, trace())),
                             op: BinaryOperation::Multiply,
-                            rhs: Box::new(E::I32Literal(2, trace())),
+                            rhs: Box::new(E::IntegerLiteral(2, trace())),
                             trace: trace(),
                         }),
                         op: BinaryOperation::Divide,
-                        rhs: Box::new(E::I32Literal(4, trace())),
+                        rhs: Box::new(E::IntegerLiteral(4, trace())),
                         trace: trace(),
                     }),
                     op: BinaryOperation::Plus,
                     rhs: Box::new(E::Parenthetical(
                         Box::new(E::Binary {
-                            lhs: Box::new(E::I32Literal(2, trace())),
+                            lhs: Box::new(E::IntegerLiteral(2, trace())),
                             op: BinaryOperation::Plus,
-                            rhs: Box::new(E::I32Literal(5, trace())),
+                            rhs: Box::new(E::IntegerLiteral(5, trace())),
                             trace: trace(),
                         }), trace())),
                     trace: trace(),
@@ -164,6 +175,7 @@ fn function_symbols() {
         UnvalidatedTopLevel::FunctionDefinition {
             proto: UnvalidatedFunctionPrototype {
                 name: Identifier("funny".into()),
+                generics: vec![],
                 arguments: vec![],
                 returns: UnvalidatedType::Unit,
                 visibility: Visibility::Private,
@@ -213,7 +225,6 @@ fn struct_declaration() {
     "##);
 
     use UnvalidatedTopLevel as UTL;
-    use UnvalidatedExpression as E;
 
     if let Err(ref e) = vecs {
         eprintln!("{e}");
@@ -223,25 +234,26 @@ fn struct_declaration() {
         UTL::StructDefinition {
             proto: UnvalidatedStructPrototype {
                 identifier: "Vector3".into(),
+                generics: vec![],
                 properties: vec![
                     UnvalidatedStructProperty {
                         property: UnvalidatedProperty {
                             name: "x".into(),
-                            ty: UnvalidatedType::Type("f32".into()),
+                            ty: UnvalidatedType::Type("f32".into(), trace()),
                         },
                         visibility: Visibility::Public,
                     },
                     UnvalidatedStructProperty {
                         property: UnvalidatedProperty {
                             name: "y".into(),
-                            ty: UnvalidatedType::Type("f32".into()),
+                            ty: UnvalidatedType::Type("f32".into(), trace()),
                         },
                         visibility: Visibility::Private,
                     },
                     UnvalidatedStructProperty {
                         property: UnvalidatedProperty {
                             name: "z".into(),
-                            ty: UnvalidatedType::Type("f32".into()),
+                            ty: UnvalidatedType::Type("f32".into(), trace()),
                         },
                         visibility: Visibility::Public,
                     },
@@ -253,6 +265,7 @@ fn struct_declaration() {
         UTL::StructDefinition {
             proto: UnvalidatedStructPrototype {
                 identifier: "Dummy".into(),
+                generics: vec![],
                 properties: vec![],
                 visibility: Visibility::Public,
             },
@@ -261,18 +274,19 @@ fn struct_declaration() {
         UTL::StructDefinition {
             proto: UnvalidatedStructPrototype {
                 identifier: "Vector2".into(),
+                generics: vec![],
                 properties: vec![
                     UnvalidatedStructProperty {
                         property: UnvalidatedProperty {
                             name: "x".into(),
-                            ty: UnvalidatedType::Type("f32".into()),
+                            ty: UnvalidatedType::Type("f32".into(), trace()),
                         },
                         visibility: Visibility::Public,
                     },
                     UnvalidatedStructProperty {
                         property: UnvalidatedProperty {
                             name: "y".into(),
-                            ty: UnvalidatedType::Type("f32".into()),
+                            ty: UnvalidatedType::Type("f32".into(), trace()),
                         },
                         visibility: Visibility::Public,
                     },
@@ -283,3 +297,949 @@ fn struct_declaration() {
         },
     ]))
 }
+
+#[test]
+fn serialize_ast() {
+    let (vecs, _) = parse_from("fun one() => 1");
+
+    let json = serde_json::to_value(vecs.expect("Failed to parse")).expect("Failed to serialize");
+
+    assert_eq!(json, serde_json::json!([
+        {
+            "FunctionDefinition": {
+                "proto": {
+                    "name": "one",
+                    "generics": [],
+                    "arguments": [],
+                    "returns": "Implicit",
+                    "visibility": "Private",
+                },
+                "body": {
+                    "Expression": {
+                        "IntegerLiteral": [1, { "file": 0, "lo": 13, "hi": 14 }],
+                    },
+                },
+                "trace": { "file": 0, "lo": 0, "hi": 14 },
+            },
+        },
+    ]))
+}
+
+#[test]
+fn error_recovery() {
+    let mut sources = SourceMap::new();
+    let file = sources.add(SourceFile::new(r"
+        fun first() {
+            let = 1
+            return 2
+            return )
+        }
+
+        struct Broken { x: }
+
+        fun second() {}
+    "));
+    let (tokens, _) = crate::lexer::tokenize(&sources[file]);
+    let trace = || Trace::new(file, 0..0);
+
+    let (statements, errors) = Parser::new(file, tokens).parse();
+
+    use UnvalidatedExpression as E;
+    use crate::parser::ast::function::UnvalidatedFunctionExpression as FE;
+    use crate::lexer::token::{Operator, TokenData};
+
+    assert!(matches!(errors.as_slice(), [
+        ParserError::ExpectedIdentifier(_),
+        ParserError::UnexpectedToken(Token(TokenData::Operator(Operator::ParenClose), _)),
+        ParserError::UnexpectedToken(Token(TokenData::Operator(Operator::CurlyClose), _)),
+    ]), "{errors:?}");
+
+    assert_eq!(statements, vec![
+        UnvalidatedTopLevel::FunctionDefinition {
+            proto: UnvalidatedFunctionPrototype {
+                name: Identifier("first".into()),
+                generics: vec![],
+                arguments: vec![],
+                returns: UnvalidatedType::Unit,
+                visibility: Visibility::Private,
+            },
+            body: E::Scope(vec![
+                E::Error(trace()).into(),
+                FE::Return(Some(E::IntegerLiteral(2, trace()))),
+                E::Error(trace()).into(),
+            ], trace()).into(),
+            trace: trace(),
+        },
+        UnvalidatedTopLevel::Error(trace()),
+        UnvalidatedTopLevel::FunctionDefinition {
+            proto: UnvalidatedFunctionPrototype {
+                name: Identifier("second".into()),
+                generics: vec![],
+                arguments: vec![],
+                returns: UnvalidatedType::Unit,
+                visibility: Visibility::Private,
+            },
+            body: E::Scope(vec![], trace()).into(),
+            trace: trace(),
+        },
+    ]);
+}
+
+#[test]
+fn unclosed_block_recovery() {
+    let mut sources = SourceMap::new();
+    let file = sources.add(SourceFile::new(r"
+        fun first() {
+            return )
+
+        pub fun second() {}
+    "));
+    let (tokens, _) = crate::lexer::tokenize(&sources[file]);
+
+    let (statements, errors) = Parser::new(file, tokens).parse();
+
+    // the unclosed function is reported without swallowing the function after it
+    assert!(matches!(errors.as_slice(), [
+        ParserError::UnexpectedToken(_),
+        ParserError::ExpectedOperator(_, _),
+    ]), "{errors:?}");
+    assert!(matches!(statements.as_slice(), [
+        UnvalidatedTopLevel::Error(_),
+        UnvalidatedTopLevel::FunctionDefinition { .. },
+    ]));
+}
+
+#[test]
+fn skipped_bracket_recovery() {
+    let mut sources = SourceMap::new();
+    let file = sources.add(SourceFile::new("fun f(): i32 {\n let a = 1\n { 1 }\n let b = 2\n return b\n}"));
+    let (tokens, _) = crate::lexer::tokenize(&sources[file]);
+
+    let (statements, errors) = Parser::new(file, tokens).parse();
+
+    // the brace the error is reported on is closed again before the body of the function ends
+    assert!(matches!(errors.as_slice(), [
+        ParserError::UnexpectedToken(Token(TokenData::Operator(Operator::CurlyOpen), _)),
+    ]), "{errors:?}");
+    let [UnvalidatedTopLevel::FunctionDefinition { body: UnvalidatedFunctionExpression::Expression(UnvalidatedExpression::Scope(body, _)), .. }] = statements.as_slice() else {
+        panic!("Expected a single function, got {statements:?}");
+    };
+    assert!(matches!(body.as_slice(), [
+        UnvalidatedFunctionExpression::Let { .. },
+        UnvalidatedFunctionExpression::Expression(UnvalidatedExpression::Error(_)),
+        UnvalidatedFunctionExpression::Let { .. },
+        UnvalidatedFunctionExpression::Return(Some(_)),
+    ]), "{body:?}");
+}
+
+#[test]
+fn format_string() {
+    let (vecs, trace) = parse_from(r#"
+        fun greet() => "hi {name}, {a * 2} {}"
+    "#);
+
+    use UnvalidatedExpression as E;
+    use UnvalidatedFormatSegment as S;
+
+    assert_eq!(vecs, Ok(vec![
+        UnvalidatedTopLevel::FunctionDefinition {
+            proto: UnvalidatedFunctionPrototype {
+                name: Identifier("greet".into()),
+                generics: vec![],
+                arguments: vec![],
+                returns: UnvalidatedType::Implicit,
+                visibility: Visibility::Private,
+            },
+            body: E::FormatString {
+                segments: vec![
+                    S::Literal("hi ".into()),
+                    S::Expression(E::VariableReference {
+                        symbol: UnvalidatedSymbol {
+                            explicit_namespace: Namespace { chain: vec![] },
+                            identifier: "name".into(),
+                        },
+                        trace: trace(),
+                    }),
+                    S::Literal(", ".into()),
+                    S::Expression(E::Binary {
+                        lhs: Box::new(E::VariableReference {
+                            symbol: UnvalidatedSymbol {
+                                explicit_namespace: Namespace { chain: vec![] },
+                                identifier: "a".into(),
+                            },
+                            trace: trace(),
+                        }),
+                        op: BinaryOperation::Multiply,
+                        rhs: Box::new(E::IntegerLiteral(2, trace())),
+                        trace: trace(),
+                    }),
+                    S::Literal(" ".into()),
+                    S::Placeholder(trace()),
+                ],
+                trace: trace(),
+            }.into(),
+            trace: trace(),
+        },
+    ]))
+}
+
+#[test]
+fn format_string_error() {
+    let (vecs, _) = parse_from(r#"
+        fun greet() => "{a b}"
+    "#);
+
+    match vecs {
+        Err(ParserError::UnexpectedToken(Token(_, trace))) => assert_eq!(trace.range(), 28..29),
+        other => panic!("Expected an unexpected token error, got {other:?}"),
+    }
+}
+
+#[test]
+fn match_expression() {
+    let (vecs, trace) = parse_from(r#"
+        fun unwrap_or(opt: i32, default: i32) => match opt {
+            Some(v) if v > 0 => v
+            None | Option::Empty => default,
+            _ => { 0 }
+        }
+    "#);
+
+    use UnvalidatedExpression as E;
+    use UnvalidatedPattern as P;
+
+    let symbol = |namespace: &[&str], identifier: &str| UnvalidatedSymbol {
+        explicit_namespace: Namespace { chain: namespace.iter().map(|&n| n.into()).collect() },
+        identifier: identifier.into(),
+    };
+
+    let vecs = vecs.expect("Failed to parse");
+    let UnvalidatedTopLevel::FunctionDefinition { body, .. } = &vecs[0] else {
+        panic!("Expected a function, got {vecs:?}");
+    };
+
+    assert_eq!(body, &E::Match {
+        scrutinee: Box::new(E::VariableReference { symbol: symbol(&[], "opt"), trace: trace() }),
+        arms: vec![
+            UnvalidatedMatchArm {
+                pattern: P::Variant {
+                    symbol: symbol(&[], "Some"),
+                    fields: vec![P::Binding { ident: "v".into(), mutable: false, trace: trace() }],
+                    trace: trace(),
+                },
+                guard: Some(E::Binary {
+                    lhs: Box::new(E::VariableReference { symbol: symbol(&[], "v"), trace: trace() }),
+                    op: BinaryOperation::Greater,
+                    rhs: Box::new(E::IntegerLiteral(0, trace())),
+                    trace: trace(),
+                }),
+                body: E::VariableReference { symbol: symbol(&[], "v"), trace: trace() },
+                trace: trace(),
+            },
+            UnvalidatedMatchArm {
+                pattern: P::Alternatives(vec![
+                    P::Binding { ident: "None".into(), mutable: false, trace: trace() },
+                    P::Variant { symbol: symbol(&["Option"], "Empty"), fields: vec![], trace: trace() },
+                ], trace()),
+                guard: None,
+                body: E::VariableReference { symbol: symbol(&[], "default"), trace: trace() },
+                trace: trace(),
+            },
+            UnvalidatedMatchArm {
+                pattern: P::Wildcard(trace()),
+                guard: None,
+                body: E::Scope(vec![E::IntegerLiteral(0, trace()).into()], trace()),
+                trace: trace(),
+            },
+        ],
+        trace: trace(),
+    }.into());
+}
+
+#[test]
+fn match_patterns() {
+    let (vecs, _) = parse_from(r#"
+        fun classify(shape: i32) => match shape {
+            Shape::Rect { width: 0 | 1, height, .. } => 1
+            Shape::Circle(Point { x: -5..=5, y: 'a'..'z' }, mut r) => 2
+            Wrapper(Some(..=10), 10.., "text", true) => 3
+        }
+    "#);
+
+    use UnvalidatedPattern as P;
+
+    let vecs = vecs.expect("Failed to parse");
+    let UnvalidatedTopLevel::FunctionDefinition { body, .. } = &vecs[0] else {
+        panic!("Expected a function, got {vecs:?}");
+    };
+    let UnvalidatedFunctionExpression::Expression(UnvalidatedExpression::Match { arms, .. }) = body else {
+        panic!("Expected a match expression, got {body:?}");
+    };
+    let patterns: Vec<_> = arms.iter().map(|arm| &arm.pattern).collect();
+
+    assert!(matches!(&patterns[0], P::Struct { fields, rest: true, .. } if matches!(fields.as_slice(), [
+        UnvalidatedFieldPattern { pattern: P::Alternatives(..), .. },
+        UnvalidatedFieldPattern { pattern: P::Binding { mutable: false, .. }, .. },
+    ])), "{:?}", patterns[0]);
+
+    assert!(matches!(&patterns[1], P::Variant { fields, .. } if matches!(fields.as_slice(), [
+        P::Struct { fields, rest: false, .. },
+        P::Binding { mutable: true, .. },
+    ] if matches!(fields.as_slice(), [
+        UnvalidatedFieldPattern { pattern: P::Range { start: Some(_), end: Some(_), inclusive: true, .. }, .. },
+        UnvalidatedFieldPattern { pattern: P::Range { start: Some(_), end: Some(_), inclusive: false, .. }, .. },
+    ]))), "{:?}", patterns[1]);
+
+    assert!(matches!(&patterns[2], P::Variant { fields, .. } if matches!(fields.as_slice(), [
+        P::Variant { .. },
+        P::Range { start: Some(_), end: None, inclusive: false, .. },
+        P::Literal(UnvalidatedExpression::StringLiteral(..)),
+        P::Literal(UnvalidatedExpression::BoolLiteral(true, _)),
+    ])), "{:?}", patterns[2]);
+}
+
+#[test]
+fn match_invalid_pattern() {
+    let (vecs, _) = parse_from(r#"
+        fun f(x: i32) => match x {
+            1 + 2 => 0
+        }
+    "#);
+
+    assert!(matches!(vecs, Err(ParserError::ExpectedOperator(Operator::ThickRightArrow, _))), "{vecs:?}");
+
+    let (vecs, _) = parse_from(r#"
+        fun f(x: i32) => match x {
+            (x) => 0
+        }
+    "#);
+
+    match vecs {
+        Err(err @ ParserError::ExpectedPattern(_)) => assert_eq!(err.code(), "P0011"),
+        other => panic!("Expected a pattern error, got {other:?}"),
+    }
+}
+
+#[test]
+fn impl_block() {
+    let (vecs, trace) = parse_from(r#"
+        impl Vector2 {
+            pub fun zero() => 0
+            pub fun len(ref[self]): f32 => 1
+            fun consume(mut self, by: i32) {}
+        }
+
+        impl Index for Vec<T> {
+            pub fun index([self], i: usize): ref[T] {}
+        }
+    "#);
+
+    let vecs = vecs.expect("Failed to parse");
+    let receivers: Vec<Vec<_>> = vecs
+        .iter()
+        .map(|item| match item {
+            UnvalidatedTopLevel::ImplBlock { functions, .. } => functions
+                .iter()
+                .map(|function| (function.proto.is_method(), function.proto.arguments.first()))
+                .collect(),
+            other => panic!("Expected an impl block, got {other:?}"),
+        })
+        .collect();
+
+    let self_type = || UnvalidatedType::Type("Self".into(), trace());
+    let receiver = |ty, mutable| UnvalidatedVariableInfo { ident: "self".into(), ty, mutable };
+
+    assert_eq!(receivers, vec![
+        vec![
+            (false, None),
+            (true, Some(&receiver(UnvalidatedType::Reference(Box::new(self_type())), false))),
+            (true, Some(&receiver(self_type(), true))),
+        ],
+        vec![
+            (true, Some(&receiver(UnvalidatedType::Array(Box::new(self_type())), false))),
+        ],
+    ]);
+
+    assert!(matches!(&vecs[0], UnvalidatedTopLevel::ImplBlock {
+        target: UnvalidatedType::Type(target, _),
+        implements: None,
+        ..
+    } if target.as_str() == "Vector2"));
+    assert!(matches!(&vecs[1], UnvalidatedTopLevel::ImplBlock {
+        target: UnvalidatedType::Template { .. },
+        implements: Some(UnvalidatedType::Type(implements, _)),
+        ..
+    } if implements.as_str() == "Index"));
+}
+
+#[test]
+fn impl_block_recovery() {
+    let mut sources = SourceMap::new();
+    let file = sources.add(SourceFile::new(r#"
+        impl Vector2 {
+            fun broken( => 1
+            fun fine() => 2
+        }
+        fun after() {}
+    "#));
+    let (tokens, _) = crate::lexer::tokenize(&sources[file]);
+    let (statements, errors) = Parser::new(file, tokens).parse();
+
+    assert_eq!(errors.len(), 1, "{errors:?}");
+    assert!(matches!(statements.as_slice(), [
+        UnvalidatedTopLevel::ImplBlock { functions, .. },
+        UnvalidatedTopLevel::FunctionDefinition { .. },
+    ] if functions.len() == 1));
+}
+
+#[test]
+fn construction() {
+    let (vecs, trace) = parse_from(r#"
+        fun f() {
+            make Vector2 { x: 0 y: 1 }
+            alloc Node<T> {
+                value,
+                next: 2,
+            }
+        }
+    "#);
+
+    use UnvalidatedExpression as E;
+
+    let vecs = vecs.expect("Failed to parse");
+    let UnvalidatedTopLevel::FunctionDefinition { body: UnvalidatedFunctionExpression::Expression(E::Scope(statements, _)), .. } = &vecs[0] else {
+        panic!("Expected a function, got {vecs:?}");
+    };
+
+    let constructions: Vec<_> = statements
+        .iter()
+        .map(|statement| match statement {
+            UnvalidatedFunctionExpression::Expression(E::Construct { ty, fields, heap, .. }) => {
+                let fields: Vec<_> = fields.iter().map(|field| (field.name.as_str(), &field.value)).collect();
+                (ty, fields, *heap)
+            }
+            other => panic!("Expected a construction, got {other:?}"),
+        })
+        .collect();
+
+    let value = E::VariableReference {
+        symbol: UnvalidatedSymbol {
+            explicit_namespace: Namespace { chain: vec![] },
+            identifier: "value".into(),
+        },
+        trace: trace(),
+    };
+
+    assert_eq!(constructions, vec![
+        (
+            &UnvalidatedType::Type("Vector2".into(), trace()),
+            vec![("x", &E::IntegerLiteral(0, trace())), ("y", &E::IntegerLiteral(1, trace()))],
+            false,
+        ),
+        (
+            &UnvalidatedType::Template {
+                base: "Node".into(),
+                template_arguments: vec![UnvalidatedType::Type("T".into(), trace())],
+                trace: trace(),
+            },
+            vec![("value", &value), ("next", &E::IntegerLiteral(2, trace()))],
+            true,
+        ),
+    ]);
+}
+
+#[test]
+fn enum_declaration() {
+    let (vecs, trace) = parse_from(r#"
+        pub enum Result<T, E> {
+            Ok(T),
+            Err(E)
+        }
+
+        enum Shape {
+            Circle(f32)
+            Rect { width: f32, height: f32 }
+            Empty,
+        }
+    "#);
+
+    assert_eq!(vecs, Ok(vec![
+        UnvalidatedTopLevel::EnumDefinition {
+            proto: UnvalidatedEnumPrototype {
+                identifier: "Result".into(),
+                generics: vec!["T".into(), "E".into()],
+                variants: vec![
+                    UnvalidatedEnumVariant {
+                        name: "Ok".into(),
+                        data: Some(UnvalidatedEnumData::Positional(vec![UnvalidatedType::Type("T".into(), trace())])),
+                    },
+                    UnvalidatedEnumVariant {
+                        name: "Err".into(),
+                        data: Some(UnvalidatedEnumData::Positional(vec![UnvalidatedType::Type("E".into(), trace())])),
+                    },
+                ],
+                visibility: Visibility::Public,
+            },
+            trace: Trace::new(FileId(0), 0..0),
+        },
+        UnvalidatedTopLevel::EnumDefinition {
+            proto: UnvalidatedEnumPrototype {
+                identifier: "Shape".into(),
+                generics: vec![],
+                variants: vec![
+                    UnvalidatedEnumVariant {
+                        name: "Circle".into(),
+                        data: Some(UnvalidatedEnumData::Positional(vec![UnvalidatedType::Type("f32".into(), trace())])),
+                    },
+                    UnvalidatedEnumVariant {
+                        name: "Rect".into(),
+                        data: Some(UnvalidatedEnumData::StructLike(vec![
+                            UnvalidatedProperty { name: "width".into(), ty: UnvalidatedType::Type("f32".into(), trace()) },
+                            UnvalidatedProperty { name: "height".into(), ty: UnvalidatedType::Type("f32".into(), trace()) },
+                        ])),
+                    },
+                    UnvalidatedEnumVariant { name: "Empty".into(), data: None },
+                ],
+                visibility: Visibility::Private,
+            },
+            trace: Trace::new(FileId(0), 0..0),
+        },
+    ]));
+}
+
+#[test]
+fn generic_declarations() {
+    let (vecs, trace) = parse_from(r#"
+        struct Pair<A, B> { first: A, second: B }
+        fun flatten<T, E>(nested: Result<Result<T, E>, E>): Result<T, E> => nested
+        impl<T> Rc<T> {}
+    "#);
+
+    let vecs = vecs.expect("Failed to parse");
+    let ty = |name: &str| UnvalidatedType::Type(name.into(), trace());
+    let result = |ok, err| UnvalidatedType::Template { base: "Result".into(), template_arguments: vec![ok, err], trace: trace() };
+
+    assert!(matches!(&vecs[0], UnvalidatedTopLevel::StructDefinition { proto, .. }
+        if proto.generics == vec!["A".into(), "B".into()]));
+
+    let UnvalidatedTopLevel::FunctionDefinition { proto, .. } = &vecs[1] else {
+        panic!("Expected a function, got {:?}", vecs[1]);
+    };
+    assert_eq!(proto.generics, vec!["T".into(), "E".into()]);
+    // the `>>` closing both argument lists is lexed as a shift
+    assert_eq!(proto.arguments[0].ty, result(result(ty("T"), ty("E")), ty("E")));
+    assert_eq!(proto.returns, result(ty("T"), ty("E")));
+
+    assert!(matches!(&vecs[2], UnvalidatedTopLevel::ImplBlock { generics, target: UnvalidatedType::Template { .. }, .. }
+        if generics == &vec!["T".into()]));
+}
+
+#[test]
+fn trait_declaration() {
+    let (vecs, _) = parse_from(r#"
+        pub trait Index<T> {
+            fun index(self, at: usize): T
+            fun first(self): T => Self::index(self, 0)
+            fun len(self): usize
+        }
+        impl<T> Index<T> for Vec<T> {}
+    "#);
+
+    let vecs = vecs.expect("Failed to parse");
+
+    let UnvalidatedTopLevel::TraitDefinition { proto, .. } = &vecs[0] else {
+        panic!("Expected a trait, got {:?}", vecs[0]);
+    };
+    assert_eq!(proto.identifier, "Index".into());
+    assert_eq!(proto.generics, vec!["T".into()]);
+    assert_eq!(proto.visibility, Visibility::Public);
+
+    // required methods end at their prototype
+    let provided: Vec<(&str, bool)> = proto.methods
+        .iter()
+        .map(|method| (method.proto.name.as_str(), method.body.is_some()))
+        .collect();
+    assert_eq!(provided, vec![("index", false), ("first", true), ("len", false)]);
+
+    assert!(matches!(&vecs[1], UnvalidatedTopLevel::ImplBlock { implements: Some(UnvalidatedType::Template { base, .. }), .. }
+        if base == &"Index".into()));
+
+    let (errors, _) = parse_from("impl [Index] for Vec {}");
+    assert!(matches!(errors, Err(ParserError::ExpectedIdentifier(_))));
+}
+
+#[test]
+fn postfix_chain() {
+    let source = "fun f() => line.start.offset(1, 2)[0] as i64";
+    let (vecs, _) = parse_from(source);
+
+    use UnvalidatedExpression as E;
+
+    let vecs = vecs.expect("Failed to parse");
+    let UnvalidatedTopLevel::FunctionDefinition { body, .. } = &vecs[0] else {
+        panic!("Expected a function, got {vecs:?}");
+    };
+    let UnvalidatedFunctionExpression::Expression(E::Cast { expr, .. }) = body else {
+        panic!("Expected a cast, got {body:?}");
+    };
+
+    // postfix operators bind tighter than casts and apply left to right
+    let E::Index { object, index, trace } = expr.as_ref() else {
+        panic!("Expected an index, got {expr:?}");
+    };
+    assert!(matches!(index.as_ref(), E::IntegerLiteral(0, _)));
+    assert_eq!(&source[trace.range()], "line.start.offset(1, 2)[0]");
+
+    assert!(matches!(object.as_ref(), E::MethodCall { object, method, arguments, .. }
+        if method == &"offset".into()
+        && arguments.len() == 2
+        && matches!(object.as_ref(), E::ObjectProperty { object, identifier, .. }
+            if identifier == &"start".into() && matches!(object.as_ref(), E::VariableReference { .. }))
+    ), "{object:?}");
+}
+
+#[test]
+fn for_loop() {
+    let (vecs, _) = parse_from(r#"
+        fun f(n: usize) {
+            for i in 0..n + 1 {}
+            for item in items {}
+        }
+    "#);
+
+    use UnvalidatedExpression as E;
+    use UnvalidatedFunctionExpression as F;
+
+    let vecs = vecs.expect("Failed to parse");
+    let UnvalidatedTopLevel::FunctionDefinition { body: F::Expression(E::Scope(statements, _)), .. } = &vecs[0] else {
+        panic!("Expected a function with a block, got {vecs:?}");
+    };
+
+    // ranges bind looser than arithmetic
+    assert!(matches!(&statements[0], F::For { variable, iterable: E::Range { start, end, inclusive: false, .. }, .. }
+        if variable == &"i".into()
+        && matches!(start.as_ref(), E::IntegerLiteral(0, _))
+        && matches!(end.as_ref(), E::Binary { op: BinaryOperation::Plus, .. })
+    ), "{:?}", statements[0]);
+    assert!(matches!(&statements[1], F::For { iterable: E::VariableReference { .. }, .. }), "{:?}", statements[1]);
+
+    let (inclusive, _) = parse_from("fun f() => 1..=2");
+    assert!(matches!(&inclusive.expect("Failed to parse")[0], UnvalidatedTopLevel::FunctionDefinition {
+        body: F::Expression(E::Range { inclusive: true, .. }), ..
+    }));
+
+    let (errors, _) = parse_from("fun f() { for i 0..2 {} }");
+    assert!(matches!(errors, Err(ParserError::ExpectedKeyword(..))), "{errors:?}");
+}
+
+#[test]
+fn if_else_chain() {
+    let (vecs, _) = parse_from(r#"
+        fun sign(n: i32): i32 {
+            if n > 0 { 1 } else if n == 0 { 0 } else unless n > 0 { -1 }
+        }
+        fun pick(c: bool): i32 => if c { 1 } else { 2 }
+        fun skip(c: bool) => if c { f() }
+    "#);
+
+    use UnvalidatedExpression as E;
+    use UnvalidatedFunctionExpression as F;
+
+    let vecs = vecs.expect("Failed to parse");
+    let UnvalidatedTopLevel::FunctionDefinition { body: F::Expression(E::Scope(statements, _)), .. } = &vecs[0] else {
+        panic!("Expected a function with a block, got {vecs:?}");
+    };
+
+    // every `else if` nests the rest of the chain in the `else` branch, the last `else unless`
+    // has no else of its own
+    let F::Expression(E::IfElse { otherwise, .. }) = &statements[0] else {
+        panic!("Expected an if-else, got {:?}", statements[0]);
+    };
+    let F::Expression(E::IfElse { otherwise, .. }) = otherwise.as_ref() else {
+        panic!("Expected an else if, got {otherwise:?}");
+    };
+    assert!(matches!(otherwise.as_ref(), F::If { condition: E::Unary { op: UnaryOperator::Not, .. }, .. }), "{otherwise:?}");
+
+    assert!(matches!(&vecs[1], UnvalidatedTopLevel::FunctionDefinition { body: F::Expression(E::IfElse { .. }), .. }));
+    assert!(matches!(&vecs[2], UnvalidatedTopLevel::FunctionDefinition { body: F::Expression(E::Scope(statements, _)), .. }
+        if matches!(statements.as_slice(), [F::If { .. }])));
+
+    let (errors, _) = parse_from("fun f(c: bool) { if c {} else 2 }");
+    assert!(matches!(errors, Err(ParserError::ExpectedOperator(Operator::CurlyOpen, _))), "{errors:?}");
+}
+
+#[test]
+fn macro_call() {
+    let (vecs, _) = parse_from(r#"fun f() => println!("{} {}", g(a, [b]), { c })"#);
+
+    let vecs = vecs.expect("Failed to parse");
+    let UnvalidatedTopLevel::FunctionDefinition { body: UnvalidatedFunctionExpression::Expression(body), .. } = &vecs[0] else {
+        panic!("Expected a function, got {vecs:?}");
+    };
+    let UnvalidatedExpression::MacroCall { name, arguments, .. } = body else {
+        panic!("Expected a macro call, got {body:?}");
+    };
+
+    // the arguments are kept as tokens up to the closing parenthesis
+    assert_eq!(name, &"println!".into());
+    assert_eq!(arguments.len(), 15);
+    assert!(matches!(arguments[0].token(), TokenData::FormatString(_)));
+    assert_eq!(arguments[13].token(), &TokenData::Operator(Operator::CurlyClose));
+    assert_eq!(arguments[14].token(), &TokenData::EOF);
+
+    let (errors, _) = parse_from(r#"fun f() => println!("{}", (a)"#);
+    assert!(matches!(errors, Err(ParserError::OpenParenthetical(_))), "{errors:?}");
+
+    let (errors, _) = parse_from("fun f() => assert!(a])");
+    assert!(matches!(errors, Err(ParserError::UnexpectedToken(Token(TokenData::Operator(Operator::BracketClose), _)))), "{errors:?}");
+
+    let (errors, _) = parse_from("fun f() => assert! a");
+    assert!(matches!(errors, Err(ParserError::ExpectedOperator(Operator::ParenOpen, _))), "{errors:?}");
+}
+
+#[test]
+fn macro_expansion() {
+    let expand = |source: &str| {
+        let (vecs, _) = parse_from(source);
+        let mut vecs = vecs.expect("Failed to parse");
+        let errors = expand_macros(&mut vecs);
+        (vecs, errors)
+    };
+    let body = |vecs: &[UnvalidatedTopLevel]| match &vecs[0] {
+        UnvalidatedTopLevel::FunctionDefinition { body: UnvalidatedFunctionExpression::Expression(body), .. } => format!("{body:?}"),
+        other => panic!("Expected a function, got {other:?}"),
+    };
+
+    use UnvalidatedExpression as E;
+    use UnvalidatedFormatSegment as S;
+
+    // placeholders are filled in order, the values of nested macros are expanded as well
+    let (vecs, errors) = expand(r#"fun f() => format!("{} and {}", a, format!("{x}"))"#);
+    assert_eq!(errors, vec![]);
+    let UnvalidatedTopLevel::FunctionDefinition { body: UnvalidatedFunctionExpression::Expression(E::FormatString { segments, .. }), .. } = &vecs[0] else {
+        panic!("Expected a format string, got {vecs:?}");
+    };
+    assert!(matches!(segments.as_slice(), [
+        S::Expression(E::VariableReference { .. }),
+        S::Literal(and),
+        S::Expression(E::FormatString { .. }),
+    ] if and == " and "), "{segments:?}");
+
+    let (vecs, errors) = expand(r#"fun f() => println!("done")"#);
+    assert_eq!(errors, vec![]);
+    assert!(matches!(&vecs[0], UnvalidatedTopLevel::FunctionDefinition {
+        body: UnvalidatedFunctionExpression::Expression(E::FunctionCall { symbol, arguments, .. }), ..
+    } if symbol.identifier == "print".into() && matches!(arguments.as_slice(), [E::StringLiteral(text, _)] if text == "done\n")),
+        "{}", body(&vecs));
+
+    let (vecs, errors) = expand("fun f(x: i32) => assert!(x > 0)");
+    assert_eq!(errors, vec![]);
+    assert!(matches!(&vecs[0], UnvalidatedTopLevel::FunctionDefinition {
+        body: UnvalidatedFunctionExpression::Expression(E::Scope(statements, _)), ..
+    } if matches!(statements.as_slice(), [UnvalidatedFunctionExpression::If { .. }])), "{}", body(&vecs));
+
+    let codes = |source: &str| expand(source).1.iter().map(ParserError::code).collect::<Vec<_>>();
+    assert_eq!(codes(r#"fun f() => println!("{} {}", 1)"#), vec!["P0015"]);
+    assert_eq!(codes(r#"fun f() => format!("{}")"#), vec!["P0015"]);
+    assert_eq!(codes("fun f() => format!(1)"), vec!["P0014"]);
+    assert_eq!(codes("fun f() => assert!()"), vec!["P0013"]);
+    assert_eq!(codes("fun f() => vec!(1, 2)"), vec!["P0012"]);
+    assert_eq!(codes("fun f() => print!(\"a\" 1)"), vec!["P0005"]);
+}
+
+#[test]
+fn global_declarations() {
+    let (vecs, trace) = parse_from(r"
+        pub const MAX: usize = 10
+        let mut count = MAX
+    ");
+
+    assert_eq!(vecs, Ok(vec![
+        UnvalidatedTopLevel::GlobalVariable {
+            variable: UnvalidatedVariableInfo {
+                ident: "MAX".into(),
+                ty: UnvalidatedType::Type("usize".into(), trace()),
+                mutable: false,
+            },
+            initial: UnvalidatedExpression::IntegerLiteral(10, trace()),
+            constant: true,
+            visibility: Visibility::Public,
+            trace: trace(),
+        },
+        UnvalidatedTopLevel::GlobalVariable {
+            variable: UnvalidatedVariableInfo {
+                ident: "count".into(),
+                ty: UnvalidatedType::Implicit,
+                mutable: true,
+            },
+            initial: UnvalidatedExpression::VariableReference {
+                symbol: UnvalidatedSymbol {
+                    explicit_namespace: Namespace { chain: vec![] },
+                    identifier: "MAX".into(),
+                },
+                trace: trace(),
+            },
+            constant: false,
+            visibility: Visibility::Private,
+            trace: trace(),
+        },
+    ]));
+
+    // globals always need an initial value, parsing continues at the next global
+    let mut sources = SourceMap::new();
+    let file = sources.add(SourceFile::new("let uninit: i32\nconst mut X = 1\nlet after = 2\nfun f() {}"));
+    let (tokens, _) = crate::lexer::tokenize(&sources[file]);
+    let (statements, errors) = Parser::new(file, tokens).parse();
+
+    assert_eq!(errors.iter().map(ParserError::code).collect::<Vec<_>>(), vec!["P0005", "P0003"]);
+    assert!(matches!(statements.as_slice(), [
+        UnvalidatedTopLevel::Error(_),
+        UnvalidatedTopLevel::Error(_),
+        UnvalidatedTopLevel::GlobalVariable { .. },
+        UnvalidatedTopLevel::FunctionDefinition { .. },
+    ]), "{statements:?}");
+}
+
+#[test]
+fn import_forms() {
+    let (vecs, _) = parse_from(r"
+        import std::ops::*
+        import gs::math as m
+        import std::{option::Option, result::{Result as Res, *}}
+    ");
+    let vecs = vecs.expect("Failed to parse");
+
+    let imports: Vec<String> = vecs
+        .iter()
+        .flat_map(|statement| match statement {
+            UnvalidatedTopLevel::Import { imports, .. } => imports.iter(),
+            other => panic!("Expected an import, got {other:?}"),
+        })
+        .map(|import| {
+            let path = import.path.explicit_namespace.module(import.path.identifier.clone());
+            match (import.star, &import.alias) {
+                (true, _) => format!("{path}.*"),
+                (false, Some(alias)) => format!("{path} as {alias}"),
+                (false, None) => path.to_string(),
+            }
+        })
+        .collect();
+
+    assert_eq!(imports, vec![
+        "std.ops.*",
+        "gs.math as m",
+        "std.option.Option",
+        "std.result.Result as Res",
+        "std.result.*",
+    ]);
+
+    let codes = |source: &str| match parse_from(source).0 {
+        Ok(_) => None,
+        Err(err) => Some(err.code()),
+    };
+    assert_eq!(codes("import *"), Some("P0003"));
+    assert_eq!(codes("import a::{b, c"), Some("P0005"));
+    assert_eq!(codes("import a::b as *"), Some("P0003"));
+}
+
+#[test]
+fn statement_terminators() {
+    let body = |source: &str| {
+        let mut sources = SourceMap::new();
+        let file = sources.add(SourceFile::new(source));
+        let (tokens, _) = crate::lexer::tokenize(&sources[file]);
+        let (mut statements, errors) = Parser::new(file, tokens).parse();
+
+        let codes: Vec<&str> = errors.iter().map(ParserError::code).collect();
+        match statements.pop() {
+            Some(UnvalidatedTopLevel::FunctionDefinition { body: UnvalidatedFunctionExpression::Expression(UnvalidatedExpression::Scope(body, _)), .. }) => (body, codes),
+            other => panic!("Expected a function, got {other:?}"),
+        }
+    };
+    let is_binary = |statement: &UnvalidatedFunctionExpression, expected: BinaryOperation| {
+        matches!(statement, UnvalidatedFunctionExpression::Expression(UnvalidatedExpression::Binary { op, .. }) if *op == expected)
+    };
+
+    // semicolons are optional, empty statements are skipped
+    let (statements, codes) = body("fun f() { let a = 1; let b = 2;; a; b }");
+    assert_eq!(codes, Vec::<&str>::new());
+    assert_eq!(statements.len(), 4);
+
+    // a line starting with `-` or `(` is a new statement, which is reported unless a `;` ends the
+    // previous one
+    let (statements, codes) = body("fun f() {\n a\n -b\n g\n (b)\n}");
+    assert_eq!(codes, vec!["P0016", "P0016"]);
+    assert!(matches!(statements.as_slice(), [
+        UnvalidatedFunctionExpression::Expression(UnvalidatedExpression::VariableReference { .. }),
+        UnvalidatedFunctionExpression::Expression(UnvalidatedExpression::Unary { op: UnaryOperator::Negate, .. }),
+        UnvalidatedFunctionExpression::Expression(UnvalidatedExpression::VariableReference { .. }),
+        UnvalidatedFunctionExpression::Expression(UnvalidatedExpression::Parenthetical(..)),
+    ]), "{statements:?}");
+
+    let (statements, codes) = body("fun f() {\n a;\n -b\n g;\n (b)\n}");
+    assert_eq!(codes, Vec::<&str>::new());
+    assert_eq!(statements.len(), 4);
+
+    // operators that can not start an expression continue the previous line, as does any operator
+    // ending a line
+    let (statements, codes) = body("fun f() {\n a\n + b\n a -\n b\n a\n .c()\n a\n [0]\n}");
+    assert_eq!(codes, Vec::<&str>::new());
+    assert!(is_binary(&statements[0], BinaryOperation::Plus), "{statements:?}");
+    assert!(is_binary(&statements[1], BinaryOperation::Minus), "{statements:?}");
+    assert!(matches!(statements.as_slice(), [
+        _,
+        _,
+        UnvalidatedFunctionExpression::Expression(UnvalidatedExpression::MethodCall { .. }),
+        UnvalidatedFunctionExpression::Expression(UnvalidatedExpression::Index { .. }),
+    ]), "{statements:?}");
+
+    // line breaks do not matter inside of parentheses and brackets, blocks inside of them are
+    // newline sensitive again
+    let (statements, codes) = body("fun f() {\n let x = (a\n - b)\n g(a\n - b, c\n (d))\n x[a\n - 1]\n h(if c { a\n -b } else { 0 })\n}");
+    assert_eq!(codes, vec!["P0016"]);
+    assert_eq!(statements.len(), 4);
+
+    // a bare `return` ends at a `;`, a closing brace or a line break
+    let (statements, codes) = body("fun f() {\n return;\n if a { return }\n return\n g()\n}");
+    assert_eq!(codes, Vec::<&str>::new());
+    assert!(matches!(statements.as_slice(), [
+        UnvalidatedFunctionExpression::Return(None),
+        UnvalidatedFunctionExpression::If { then, .. },
+        UnvalidatedFunctionExpression::Return(None),
+        UnvalidatedFunctionExpression::Expression(UnvalidatedExpression::FunctionCall { .. }),
+    ] if matches!(then.as_ref(), UnvalidatedExpression::Scope(body, _) if matches!(body.as_slice(), [UnvalidatedFunctionExpression::Return(None)]))
+    ), "{statements:?}");
+
+    // a line break ends the body of a match arm before a `-` starting the next pattern, which is
+    // not ambiguous
+    let (statements, codes) = body("fun f() {\n match x {\n 0 => 1\n -1 => 2\n _ => 3\n }\n}");
+    assert_eq!(codes, Vec::<&str>::new());
+    assert!(matches!(statements.as_slice(), [
+        UnvalidatedFunctionExpression::Expression(UnvalidatedExpression::Match { arms, .. })
+    ] if arms.len() == 3 && matches!(arms[0].body, UnvalidatedExpression::IntegerLiteral(1, _))), "{statements:?}");
+
+    // stray semicolons between items are skipped
+    let (vecs, _) = parse_from("import a::b;\nconst X = 1;\nfun f() {};");
+    assert_eq!(vecs.map(|items| items.len()), Ok(3));
+}
+
+#[test]
+fn unary_precedence() {
+    let (vecs, _) = parse_from("fun f() => !a && -b + 1");
+
+    use UnvalidatedExpression as E;
+
+    let vecs = vecs.expect("Failed to parse");
+    let UnvalidatedTopLevel::FunctionDefinition { body: UnvalidatedFunctionExpression::Expression(body), .. } = &vecs[0] else {
+        panic!("Expected a function, got {vecs:?}");
+    };
+
+    // unary operators only apply to the operand directly after them
+    let E::Binary { lhs, op: BinaryOperation::And, rhs, .. } = body else {
+        panic!("Expected `&&`, got {body:?}");
+    };
+    assert!(matches!(lhs.as_ref(), E::Unary { op: UnaryOperator::Not, expr, .. }
+        if matches!(expr.as_ref(), E::VariableReference { .. })), "{lhs:?}");
+    assert!(matches!(rhs.as_ref(), E::Binary { lhs, op: BinaryOperation::Plus, .. }
+        if matches!(lhs.as_ref(), E::Unary { op: UnaryOperator::Negate, .. })), "{rhs:?}");
+}
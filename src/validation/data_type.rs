@@ -1,12 +1,28 @@
-#[derive(Debug, Hash, Eq, PartialEq, Clone)]
+use std::collections::HashMap;
+use std::fmt::{Display, Formatter};
+use serde::Serialize;
+use crate::file::identifier::{GlobalIdentifier, Identifier, Namespace};
+
+#[derive(Debug, Hash, Eq, PartialEq, Ord, PartialOrd, Clone, Serialize)]
 pub enum DataType {
+    Unit,
+
+    /// Type of expressions that never produce a value, like `return`
+    Never,
     Pointer(Box<DataType>),
+    Array(Box<DataType>),
     Primitive(PrimitiveType),
-    Structure(/* TODO */),
-    Enumeration(),
+
+    /// Structure together with its type arguments, eg. `Vec<i32>`
+    Structure(GlobalIdentifier, Vec<DataType>),
+    Enumeration(GlobalIdentifier, Vec<DataType>),
+
+    /// Type parameter of the generic item being validated, it stands for any type and is only
+    /// equal to itself
+    Parameter(Identifier),
 }
 
-#[derive(Debug, Hash, Eq, PartialEq, Clone, Copy)]
+#[derive(Debug, Hash, Eq, PartialEq, Ord, PartialOrd, Clone, Copy, Serialize)]
 pub enum PrimitiveType {
     U32,
     U64,
@@ -16,6 +32,148 @@ pub enum PrimitiveType {
     F32,
     F64,
     Char,
+    Bool,
+}
+
+impl DataType {
+    /// Type of string literals
+    pub fn string() -> Self {
+        DataType::Pointer(Box::new(DataType::Primitive(PrimitiveType::Char)))
+    }
+
+    /// Whether a value of this type can be used where `other` is expected
+    pub fn is_assignable_to(&self, other: &DataType) -> bool {
+        self == other || self == &DataType::Never
+    }
+
+    /// Namespace of the functions associated to this type by impl blocks. Primitives have a
+    /// namespace of their name in the global module and every reference shares the `ref` one
+    pub fn namespace(&self) -> Option<Namespace> {
+        match self {
+            DataType::Structure(GlobalIdentifier(namespace, name), _)
+            | DataType::Enumeration(GlobalIdentifier(namespace, name), _) => Some(namespace.module(name.clone())),
+            DataType::Primitive(primitive) => Some(Namespace::global().module(primitive.as_str().into())),
+            DataType::Pointer(_) => Some(Namespace::global().module("ref".into())),
+            _ => None,
+        }
+    }
+
+    /// Replaces the type parameters that have a binding, every parameter is replaced at once so a
+    /// binding can refer to a parameter of the same name
+    pub fn substitute(&self, bindings: &HashMap<Identifier, DataType>) -> DataType {
+        match self {
+            DataType::Parameter(name) => bindings.get(name).cloned().unwrap_or_else(|| self.clone()),
+            DataType::Pointer(ty) => DataType::Pointer(Box::new(ty.substitute(bindings))),
+            DataType::Array(ty) => DataType::Array(Box::new(ty.substitute(bindings))),
+            DataType::Structure(name, arguments) => DataType::Structure(
+                name.clone(),
+                arguments.iter().map(|ty| ty.substitute(bindings)).collect(),
+            ),
+            DataType::Enumeration(name, arguments) => DataType::Enumeration(
+                name.clone(),
+                arguments.iter().map(|ty| ty.substitute(bindings)).collect(),
+            ),
+            _ => self.clone(),
+        }
+    }
+
+    /// How deeply types are nested inside of this type, `i32` has a depth of 1 and `ref[[i32]]` of 3
+    pub fn depth(&self) -> usize {
+        match self {
+            DataType::Pointer(ty) | DataType::Array(ty) => ty.depth() + 1,
+            DataType::Structure(_, arguments) | DataType::Enumeration(_, arguments) => {
+                arguments.iter().map(DataType::depth).max().unwrap_or(0) + 1
+            }
+            _ => 1,
+        }
+    }
+
+    /// Whether the type refers to the given type parameter
+    pub fn mentions(&self, parameter: &Identifier) -> bool {
+        match self {
+            DataType::Parameter(name) => name == parameter,
+            DataType::Pointer(ty) | DataType::Array(ty) => ty.mentions(parameter),
+            DataType::Structure(_, arguments) | DataType::Enumeration(_, arguments) => {
+                arguments.iter().any(|ty| ty.mentions(parameter))
+            }
+            _ => false,
+        }
+    }
+
+    /// Infers the type `parameters` mentioned by this type from a value of type `found` that is
+    /// used where this type is expected. `bindings` holds the parameters inferred so far, false is
+    /// returned if the value can not be used here.
+    pub fn bind(
+        &self,
+        found: &DataType,
+        parameters: &[Identifier],
+        bindings: &mut HashMap<Identifier, DataType>,
+    ) -> bool {
+        match (self, found) {
+            (_, DataType::Never) => true,
+            (DataType::Parameter(name), _) if parameters.contains(name) => match bindings.get(name) {
+                Some(bound) => found.is_assignable_to(bound),
+                None => {
+                    bindings.insert(name.clone(), found.clone());
+                    true
+                }
+            },
+            (DataType::Pointer(expected), DataType::Pointer(found))
+            | (DataType::Array(expected), DataType::Array(found)) => expected.bind(found, parameters, bindings),
+            (DataType::Structure(expected, expected_arguments), DataType::Structure(found, found_arguments))
+            | (DataType::Enumeration(expected, expected_arguments), DataType::Enumeration(found, found_arguments)) => {
+                expected == found
+                    && expected_arguments.len() == found_arguments.len()
+                    && expected_arguments
+                        .iter()
+                        .zip(found_arguments)
+                        .all(|(expected, found)| expected.bind(found, parameters, bindings))
+            }
+            _ => found.is_assignable_to(self),
+        }
+    }
+
+    pub fn primitive(&self) -> Option<PrimitiveType> {
+        match self {
+            DataType::Primitive(primitive) => Some(*primitive),
+            _ => None,
+        }
+    }
+}
+
+impl PrimitiveType {
+    pub fn is_integer(self) -> bool {
+        matches!(
+            self,
+            PrimitiveType::U32 | PrimitiveType::U64 | PrimitiveType::Usize | PrimitiveType::I32 | PrimitiveType::I64
+        )
+    }
+
+    pub fn is_signed(self) -> bool {
+        matches!(self, PrimitiveType::I32 | PrimitiveType::I64) || self.is_float()
+    }
+
+    pub fn is_float(self) -> bool {
+        matches!(self, PrimitiveType::F32 | PrimitiveType::F64)
+    }
+
+    pub fn is_numeric(self) -> bool {
+        self.is_integer() || self.is_float()
+    }
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            PrimitiveType::U32 => "u32",
+            PrimitiveType::U64 => "u64",
+            PrimitiveType::Usize => "usize",
+            PrimitiveType::I32 => "i32",
+            PrimitiveType::I64 => "i64",
+            PrimitiveType::F32 => "f32",
+            PrimitiveType::F64 => "f64",
+            PrimitiveType::Char => "char",
+            PrimitiveType::Bool => "bool",
+        }
+    }
 }
 
 impl TryFrom<&str> for PrimitiveType {
@@ -31,7 +189,37 @@ impl TryFrom<&str> for PrimitiveType {
             "f32" => PrimitiveType::F32,
             "f64" => PrimitiveType::F64,
             "char" => PrimitiveType::Char,
+            "bool" => PrimitiveType::Bool,
             _ => return Err(())
         })
     }
-}
\ No newline at end of file
+}
+
+impl Display for PrimitiveType {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl Display for DataType {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            DataType::Unit => f.write_str("unit"),
+            DataType::Never => f.write_str("never"),
+            DataType::Pointer(ty) => write!(f, "ref[{ty}]"),
+            DataType::Array(ty) => write!(f, "[{ty}]"),
+            DataType::Primitive(primitive) => write!(f, "{primitive}"),
+            DataType::Structure(GlobalIdentifier(_, name), arguments)
+            | DataType::Enumeration(GlobalIdentifier(_, name), arguments) => {
+                write!(f, "{name}")?;
+
+                if !arguments.is_empty() {
+                    let arguments: Vec<String> = arguments.iter().map(ToString::to_string).collect();
+                    write!(f, "<{}>", arguments.join(", "))?;
+                }
+                Ok(())
+            }
+            DataType::Parameter(name) => write!(f, "{name}"),
+        }
+    }
+}
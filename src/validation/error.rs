@@ -1,5 +1,11 @@
-use crate::file::identifier::Namespace;
+use std::fmt::{Display, Formatter};
+use crate::diagnostic::Diagnostic;
+use crate::file::identifier::{GlobalIdentifier, Identifier, Namespace};
 use crate::file::trace::Trace;
+use crate::parser::ast::operations::{BinaryOperation, UnaryOperator};
+use crate::lexer::error::integer_range;
+use crate::validation::data_type::{DataType, PrimitiveType};
+use crate::validation::monomorphize::MAX_TYPE_DEPTH;
 
 pub type CompilerResult<T> = Result<T, CompilerError>;
 
@@ -7,6 +13,247 @@ pub type CompilerResult<T> = Result<T, CompilerError>;
 pub enum CompilerError {
     UnknownNamespace(Namespace, Trace),
     DuplicateNamespace(Namespace),
+    DuplicateFunction(GlobalIdentifier, Trace),
+    DuplicateType(GlobalIdentifier, Trace),
+    UnknownType(Identifier, Trace),
+    UnknownVariable(Identifier, Trace),
+    UnknownFunction(GlobalIdentifier, Trace),
+    TypeMismatch {
+        expected: DataType,
+        found: DataType,
+        trace: Trace,
+    },
+    InvalidOperands {
+        op: BinaryOperation,
+        lhs: DataType,
+        rhs: DataType,
+        trace: Trace,
+    },
+    InvalidUnaryOperand {
+        op: UnaryOperator,
+        ty: DataType,
+        trace: Trace,
+    },
+    InvalidCast {
+        from: DataType,
+        to: DataType,
+        trace: Trace,
+    },
+    ArgumentCount {
+        function: GlobalIdentifier,
+        expected: usize,
+        found: usize,
+        trace: Trace,
+    },
+    NoMatchingOverload {
+        function: GlobalIdentifier,
+        arguments: Vec<DataType>,
+        trace: Trace,
+    },
+    AssignToImmutable(Identifier, Trace),
+    InvalidAssignTarget(Trace),
+    TypeAnnotationNeeded(Identifier, Trace),
+    NotFormattable(DataType, Trace),
+    UnfilledPlaceholder(Trace),
+    Unsupported(&'static str, Trace),
+
+    /// Impl block for a type that can not have associated functions
+    InvalidImplTarget(DataType, Trace),
+    MissingFields {
+        ty: DataType,
+        fields: Vec<Identifier>,
+        trace: Trace,
+    },
+    DuplicateField(Identifier, Trace),
+    NotAStructure(DataType, Trace),
+    DuplicateVariant(Identifier, Trace),
+    UnknownVariant {
+        ty: DataType,
+        variant: Identifier,
+        trace: Trace,
+    },
+
+    /// Variant constructed with a different syntax than it was declared with, `expected` is how
+    /// it is constructed
+    InvalidVariantConstruction {
+        variant: GlobalIdentifier,
+        expected: String,
+        trace: Trace,
+    },
+    UnknownField {
+        ty: DataType,
+        field: Identifier,
+        trace: Trace,
+    },
+
+    /// Type written with a different number of type arguments than it declares
+    TypeArgumentCount {
+        ty: Identifier,
+        expected: usize,
+        found: usize,
+        trace: Trace,
+    },
+
+    /// Type argument of an impl target that is neither a type nor declared with `impl<T>`
+    UnknownTypeParameter(Identifier, Trace),
+    DuplicateTypeParameter(Identifier, Trace),
+
+    /// Type parameter of a generic item that neither the values nor the context determine
+    UninferredTypeParameter {
+        item: GlobalIdentifier,
+        parameter: Identifier,
+        trace: Trace,
+    },
+
+    /// Generic item whose instances keep nesting their type arguments deeper, `trace` is where
+    /// the item is declared
+    RecursiveInstantiation {
+        item: GlobalIdentifier,
+        trace: Trace,
+    },
+    MissingMain,
+    GenericMain(Trace),
+    UnknownTrait(Identifier, Trace),
+
+    /// Function of an impl block that is not a method of the trait it implements
+    MethodNotInTrait {
+        method: Identifier,
+        trait_name: GlobalIdentifier,
+        trace: Trace,
+    },
+
+    /// Method implemented with a different signature than the trait declares, both signatures
+    /// are written out with the type arguments of the impl block substituted
+    TraitSignatureMismatch {
+        method: Identifier,
+        expected: String,
+        found: String,
+        trace: Trace,
+    },
+    MissingTraitMethods {
+        trait_name: GlobalIdentifier,
+        methods: Vec<Identifier>,
+        trace: Trace,
+    },
+
+    /// Trait implemented in a module that declares neither the trait nor the type
+    OrphanImpl {
+        trait_name: GlobalIdentifier,
+        ty: DataType,
+        trace: Trace,
+    },
+
+    /// Second implementation of a trait for types that can be the same, `previous` is where the
+    /// first one is written
+    OverlappingImpls {
+        trait_name: GlobalIdentifier,
+        ty: DataType,
+        trace: Trace,
+        previous: Trace,
+    },
+    TraitNotImplemented {
+        trait_name: GlobalIdentifier,
+        ty: DataType,
+        trace: Trace,
+    },
+
+    /// Import whose name is already used by an item of the module or by another import
+    ImportConflict(Identifier, Trace),
+
+    /// Field access on a value that is not a structure, like `count.value` on an integer
+    NoFields(DataType, Trace),
+    NotIndexable(DataType, Trace),
+    UnknownMethod {
+        ty: DataType,
+        method: Identifier,
+        trace: Trace,
+    },
+
+    /// Value of a `for` loop that is neither an integer range nor an iterator
+    NotIterable(DataType, Trace),
+
+    /// Branches of an `if`-`else` evaluating to different types, `trace` is the value of the
+    /// `else` branch
+    IncompatibleBranches {
+        then: DataType,
+        otherwise: DataType,
+        trace: Trace,
+        then_trace: Trace,
+    },
+    DuplicateGlobal(GlobalIdentifier, Trace),
+
+    /// Part of the initializer of a `const` that can only be evaluated when the program runs
+    NotConstant(&'static str, Trace),
+
+    /// Globals whose initializers refer to each other, `cycle` starts and ends with the global
+    /// at `trace`
+    CyclicGlobals {
+        cycle: Vec<GlobalIdentifier>,
+        trace: Trace,
+    },
+
+    /// Name that glob imports bring into scope from more than one item
+    AmbiguousImport {
+        name: Identifier,
+        candidates: Vec<Namespace>,
+        trace: Trace,
+    },
+
+    /// Pattern of a `match` that names a different type than the one being matched, or a range
+    /// pattern for a value that is not ordered
+    PatternMismatch {
+        ty: DataType,
+        trace: Trace,
+    },
+
+    /// Arms of a `match` evaluating to different types, `first_trace` is the value of the first
+    /// arm that does not diverge
+    IncompatibleArms {
+        first: DataType,
+        found: DataType,
+        trace: Trace,
+        first_trace: Trace,
+    },
+
+    /// Variable that only some of the alternatives of a `|` pattern bind
+    InconsistentBinding(Identifier, Trace),
+    DuplicateBinding(Identifier, Trace),
+
+    /// Variant matched with a different syntax than it was declared with, `expected` is how it
+    /// is matched
+    InvalidVariantPattern {
+        variant: GlobalIdentifier,
+        expected: String,
+        trace: Trace,
+    },
+    MissingPatternFields {
+        ty: DataType,
+        fields: Vec<Identifier>,
+        trace: Trace,
+    },
+
+    /// `main` function declared in more than one module, `previous` is the first declaration
+    DuplicateMain {
+        trace: Trace,
+        previous: Trace,
+    },
+
+    /// Integer literal without a suffix that does not fit into the type it was given
+    LiteralOutOfRange {
+        value: i128,
+        ty: PrimitiveType,
+        trace: Trace,
+    },
+
+    /// Item without `pub` used or imported outside of the module declaring it
+    PrivateItem(GlobalIdentifier, Trace),
+
+    /// Item of the standard library the compiler relies on is not part of the program
+    MissingStdItem(GlobalIdentifier, Trace),
+
+    /// Function without a written return type whose body depends on what it returns, or method
+    /// of a trait that leaves it out
+    UninferredReturnType(GlobalIdentifier, Trace),
 }
 
 impl CompilerError {
@@ -14,6 +261,411 @@ impl CompilerError {
         match self {
             CompilerError::UnknownNamespace(_, trace) => Some(trace),
             CompilerError::DuplicateNamespace(_) => None,
+            CompilerError::DuplicateFunction(_, trace) => Some(trace),
+            CompilerError::UnknownType(_, trace) => Some(trace),
+            CompilerError::UnknownVariable(_, trace) => Some(trace),
+            CompilerError::UnknownFunction(_, trace) => Some(trace),
+            CompilerError::TypeMismatch { trace, .. } => Some(trace),
+            CompilerError::InvalidOperands { trace, .. } => Some(trace),
+            CompilerError::InvalidUnaryOperand { trace, .. } => Some(trace),
+            CompilerError::InvalidCast { trace, .. } => Some(trace),
+            CompilerError::ArgumentCount { trace, .. } => Some(trace),
+            CompilerError::NoMatchingOverload { trace, .. } => Some(trace),
+            CompilerError::AssignToImmutable(_, trace) => Some(trace),
+            CompilerError::InvalidAssignTarget(trace) => Some(trace),
+            CompilerError::TypeAnnotationNeeded(_, trace) => Some(trace),
+            CompilerError::NotFormattable(_, trace) => Some(trace),
+            CompilerError::UnfilledPlaceholder(trace) => Some(trace),
+            CompilerError::Unsupported(_, trace) => Some(trace),
+            CompilerError::DuplicateType(_, trace) => Some(trace),
+            CompilerError::InvalidImplTarget(_, trace) => Some(trace),
+            CompilerError::MissingFields { trace, .. } => Some(trace),
+            CompilerError::DuplicateField(_, trace) => Some(trace),
+            CompilerError::NotAStructure(_, trace) => Some(trace),
+            CompilerError::DuplicateVariant(_, trace) => Some(trace),
+            CompilerError::UnknownVariant { trace, .. } => Some(trace),
+            CompilerError::InvalidVariantConstruction { trace, .. } => Some(trace),
+            CompilerError::UnknownField { trace, .. } => Some(trace),
+            CompilerError::TypeArgumentCount { trace, .. } => Some(trace),
+            CompilerError::UnknownTypeParameter(_, trace) => Some(trace),
+            CompilerError::DuplicateTypeParameter(_, trace) => Some(trace),
+            CompilerError::UninferredTypeParameter { trace, .. } => Some(trace),
+            CompilerError::RecursiveInstantiation { trace, .. } => Some(trace),
+            CompilerError::MissingMain => None,
+            CompilerError::GenericMain(trace) => Some(trace),
+            CompilerError::UnknownTrait(_, trace) => Some(trace),
+            CompilerError::MethodNotInTrait { trace, .. } => Some(trace),
+            CompilerError::TraitSignatureMismatch { trace, .. } => Some(trace),
+            CompilerError::MissingTraitMethods { trace, .. } => Some(trace),
+            CompilerError::OrphanImpl { trace, .. } => Some(trace),
+            CompilerError::OverlappingImpls { trace, .. } => Some(trace),
+            CompilerError::TraitNotImplemented { trace, .. } => Some(trace),
+            CompilerError::ImportConflict(_, trace) => Some(trace),
+            CompilerError::NoFields(_, trace) => Some(trace),
+            CompilerError::NotIndexable(_, trace) => Some(trace),
+            CompilerError::UnknownMethod { trace, .. } => Some(trace),
+            CompilerError::NotIterable(_, trace) => Some(trace),
+            CompilerError::IncompatibleBranches { trace, .. } => Some(trace),
+            CompilerError::DuplicateGlobal(_, trace) => Some(trace),
+            CompilerError::NotConstant(_, trace) => Some(trace),
+            CompilerError::CyclicGlobals { trace, .. } => Some(trace),
+            CompilerError::AmbiguousImport { trace, .. } => Some(trace),
+            CompilerError::PatternMismatch { trace, .. } => Some(trace),
+            CompilerError::IncompatibleArms { trace, .. } => Some(trace),
+            CompilerError::InconsistentBinding(_, trace) => Some(trace),
+            CompilerError::DuplicateBinding(_, trace) => Some(trace),
+            CompilerError::InvalidVariantPattern { trace, .. } => Some(trace),
+            CompilerError::MissingPatternFields { trace, .. } => Some(trace),
+            CompilerError::DuplicateMain { trace, .. } => Some(trace),
+            CompilerError::LiteralOutOfRange { trace, .. } => Some(trace),
+            CompilerError::PrivateItem(_, trace) => Some(trace),
+            CompilerError::MissingStdItem(_, trace) => Some(trace),
+            CompilerError::UninferredReturnType(_, trace) => Some(trace),
+        }
+    }
+
+    /// Stable code identifying the kind of error
+    pub fn code(&self) -> &'static str {
+        match self {
+            CompilerError::UnknownNamespace(..) => "E0001",
+            CompilerError::DuplicateNamespace(_) => "E0002",
+            CompilerError::DuplicateFunction(..) => "E0003",
+            CompilerError::UnknownType(..) => "E0004",
+            CompilerError::UnknownVariable(..) => "E0005",
+            CompilerError::UnknownFunction(..) => "E0006",
+            CompilerError::TypeMismatch { .. } => "E0007",
+            CompilerError::InvalidOperands { .. } => "E0008",
+            CompilerError::InvalidUnaryOperand { .. } => "E0009",
+            CompilerError::InvalidCast { .. } => "E0010",
+            CompilerError::ArgumentCount { .. } => "E0011",
+            CompilerError::NoMatchingOverload { .. } => "E0012",
+            CompilerError::AssignToImmutable(..) => "E0013",
+            CompilerError::InvalidAssignTarget(_) => "E0014",
+            CompilerError::TypeAnnotationNeeded(..) => "E0015",
+            CompilerError::NotFormattable(..) => "E0016",
+            CompilerError::UnfilledPlaceholder(_) => "E0017",
+            CompilerError::Unsupported(..) => "E0018",
+            CompilerError::DuplicateType(..) => "E0019",
+            CompilerError::InvalidImplTarget(..) => "E0020",
+            CompilerError::MissingFields { .. } => "E0021",
+            CompilerError::DuplicateField(..) => "E0022",
+            CompilerError::UnknownField { .. } => "E0023",
+            CompilerError::NotAStructure(..) => "E0024",
+            CompilerError::DuplicateVariant(..) => "E0025",
+            CompilerError::InvalidVariantConstruction { .. } => "E0026",
+            CompilerError::UnknownVariant { .. } => "E0027",
+            CompilerError::TypeArgumentCount { .. } => "E0028",
+            CompilerError::UnknownTypeParameter(..) => "E0029",
+            CompilerError::DuplicateTypeParameter(..) => "E0030",
+            CompilerError::UninferredTypeParameter { .. } => "E0031",
+            CompilerError::RecursiveInstantiation { .. } => "E0032",
+            CompilerError::MissingMain => "E0033",
+            CompilerError::GenericMain(_) => "E0034",
+            CompilerError::UnknownTrait(..) => "E0035",
+            CompilerError::MethodNotInTrait { .. } => "E0036",
+            CompilerError::TraitSignatureMismatch { .. } => "E0037",
+            CompilerError::MissingTraitMethods { .. } => "E0038",
+            CompilerError::OrphanImpl { .. } => "E0039",
+            CompilerError::OverlappingImpls { .. } => "E0040",
+            CompilerError::TraitNotImplemented { .. } => "E0041",
+            CompilerError::ImportConflict(..) => "E0042",
+            CompilerError::NoFields(..) => "E0043",
+            CompilerError::NotIndexable(..) => "E0044",
+            CompilerError::UnknownMethod { .. } => "E0045",
+            CompilerError::NotIterable(..) => "E0046",
+            CompilerError::IncompatibleBranches { .. } => "E0047",
+            CompilerError::DuplicateGlobal(..) => "E0048",
+            CompilerError::NotConstant(..) => "E0049",
+            CompilerError::CyclicGlobals { .. } => "E0050",
+            CompilerError::AmbiguousImport { .. } => "E0051",
+            CompilerError::PatternMismatch { .. } => "E0052",
+            CompilerError::IncompatibleArms { .. } => "E0053",
+            CompilerError::InconsistentBinding(..) => "E0054",
+            CompilerError::DuplicateBinding(..) => "E0055",
+            CompilerError::InvalidVariantPattern { .. } => "E0056",
+            CompilerError::MissingPatternFields { .. } => "E0057",
+            CompilerError::DuplicateMain { .. } => "E0058",
+            CompilerError::LiteralOutOfRange { .. } => "E0059",
+            CompilerError::PrivateItem(..) => "E0060",
+            CompilerError::MissingStdItem(..) => "E0061",
+            CompilerError::UninferredReturnType(..) => "E0062",
+        }
+    }
+
+    /// Short explanation shown underneath the primary trace
+    fn label(&self) -> Option<String> {
+        Some(match self {
+            CompilerError::UnknownNamespace(..) => "not found".into(),
+            CompilerError::DuplicateFunction(..) => "redefined here".into(),
+            CompilerError::UnknownType(..) => "not found in this scope".into(),
+            CompilerError::UnknownVariable(..) => "not found in this scope".into(),
+            CompilerError::UnknownFunction(..) => "not found".into(),
+            CompilerError::TypeMismatch { expected, found, .. } => format!("expected `{expected}`, found `{found}`"),
+            CompilerError::InvalidOperands { lhs, rhs, .. } => format!("`{lhs}` and `{rhs}`"),
+            CompilerError::InvalidUnaryOperand { ty, .. } => format!("this is `{ty}`"),
+            CompilerError::ArgumentCount { expected, .. } => format!("expected {expected} argument(s)"),
+            CompilerError::AssignToImmutable(..) => "cannot assign twice to immutable variable".into(),
+            CompilerError::InvalidAssignTarget(_) => "cannot assign to this expression".into(),
+            CompilerError::TypeAnnotationNeeded(..) => "type must be known at this point".into(),
+            CompilerError::NotFormattable(ty, _) => format!("this is `{ty}`"),
+            CompilerError::UnfilledPlaceholder(_) => "no value for this placeholder".into(),
+            CompilerError::DuplicateType(..) => "redefined here".into(),
+            CompilerError::InvalidImplTarget(ty, _) => format!("`{ty}` is not a structure, enum, primitive or reference"),
+            CompilerError::MissingFields { fields, .. } => {
+                let fields: Vec<String> = fields.iter().map(|field| format!("`{field}`")).collect();
+                format!("missing {}", fields.join(", "))
+            }
+            CompilerError::DuplicateField(..) => "used more than once".into(),
+            CompilerError::UnknownField { ty, .. } => format!("`{ty}` does not have this field"),
+            CompilerError::NotAStructure(ty, _) => format!("this is `{ty}`"),
+            CompilerError::DuplicateVariant(..) => "redefined here".into(),
+            CompilerError::UnknownVariant { ty, .. } => format!("`{ty}` does not have this variant"),
+            CompilerError::TypeArgumentCount { expected, .. } => format!("expected {expected} type argument(s)"),
+            CompilerError::UnknownTypeParameter(..) => "not declared by the impl block".into(),
+            CompilerError::DuplicateTypeParameter(..) => "already declared".into(),
+            CompilerError::UninferredTypeParameter { parameter, .. } => format!("cannot infer `{parameter}`"),
+            CompilerError::RecursiveInstantiation { .. } => "instantiated with ever deeper type arguments".into(),
+            CompilerError::GenericMain(_) => "declared with type parameters".into(),
+            CompilerError::UnknownTrait(..) => "not found in this scope".into(),
+            CompilerError::MethodNotInTrait { trait_name, .. } => format!("not a method of `{}`", trait_name.1),
+            CompilerError::TraitSignatureMismatch { found, .. } => format!("found `{found}`"),
+            CompilerError::MissingTraitMethods { methods, .. } => {
+                let methods: Vec<String> = methods.iter().map(|method| format!("`{method}`")).collect();
+                format!("missing {}", methods.join(", "))
+            }
+            CompilerError::OrphanImpl { .. } => "neither the trait nor the type is declared in this module".into(),
+            CompilerError::OverlappingImpls { ty, .. } => format!("conflicting implementation for `{ty}`"),
+            CompilerError::TraitNotImplemented { trait_name, .. } => format!("does not implement `{}`", trait_name.1),
+            CompilerError::ImportConflict(..) => "already defined in this module".into(),
+            CompilerError::NoFields(ty, _) => format!("this is `{ty}`"),
+            CompilerError::NotIndexable(ty, _) => format!("this is `{ty}`"),
+            CompilerError::UnknownMethod { ty, .. } => format!("method not found for `{ty}`"),
+            CompilerError::NotIterable(ty, _) => format!("this is `{ty}`"),
+            CompilerError::IncompatibleBranches { otherwise, .. } => format!("this is `{otherwise}`"),
+            CompilerError::DuplicateGlobal(..) => "redefined here".into(),
+            CompilerError::NotConstant(..) => "not known at compile time".into(),
+            CompilerError::CyclicGlobals { .. } => "its initial value depends on itself".into(),
+            CompilerError::AmbiguousImport { .. } => "brought into scope by more than one glob import".into(),
+            CompilerError::PatternMismatch { ty, .. } => format!("the matched value is `{ty}`"),
+            CompilerError::IncompatibleArms { found, .. } => format!("this is `{found}`"),
+            CompilerError::InconsistentBinding(..) => "not bound by this alternative".into(),
+            CompilerError::DuplicateBinding(..) => "used more than once".into(),
+            CompilerError::MissingPatternFields { fields, .. } => {
+                let fields: Vec<String> = fields.iter().map(|field| format!("`{field}`")).collect();
+                format!("missing {}", fields.join(", "))
+            }
+            CompilerError::DuplicateMain { .. } => "redefined here".into(),
+            CompilerError::PrivateItem(..) => "private item".into(),
+            CompilerError::MissingStdItem(..) => "needed here".into(),
+            _ => return None,
+        })
+    }
+
+    pub fn diagnostic(&self) -> Diagnostic {
+        let mut diagnostic = Diagnostic::error(self.code(), self.to_string());
+
+        if let Some(trace) = self.trace() {
+            diagnostic = diagnostic.with_primary(*trace, self.label());
+        }
+
+        match self {
+            CompilerError::DuplicateNamespace(_) => diagnostic
+                .with_note("every source file defines a module named after its file name"),
+            CompilerError::AssignToImmutable(name, _) => diagnostic
+                .with_help(format!("declare the variable as mutable: `let mut {name}`")),
+            CompilerError::TypeAnnotationNeeded(name, _) => diagnostic
+                .with_help(format!("give `{name}` a type, eg. `let {name}: i32`")),
+            CompilerError::NotFormattable(..) => diagnostic
+                .with_note("only primitives and strings can be formatted"),
+            CompilerError::UnfilledPlaceholder(_) => diagnostic
+                .with_help("write the value inside of the braces instead, eg. `{value}`"),
+            CompilerError::InvalidImplTarget(..) => diagnostic
+                .with_note("only structures, enums, primitives and references can have associated functions"),
+            CompilerError::InvalidVariantConstruction { expected, .. } => diagnostic
+                .with_help(format!("construct it as `{expected}`")),
+            CompilerError::UnknownTypeParameter(name, _) => diagnostic
+                .with_help(format!("declare it as a type parameter of the impl block: `impl<{name}>`")),
+            CompilerError::UninferredTypeParameter { .. } => diagnostic
+                .with_help("give the variable the value is assigned to a type, eg. `let value: Option<i32>`"),
+            CompilerError::RecursiveInstantiation { .. } => diagnostic
+                .with_note("a generic item can not use itself with its type parameters wrapped into another type, like `f<[T]>` inside of `f<T>`"),
+            CompilerError::MissingMain => diagnostic
+                .with_help("add a function to start the program at: `fun main() {}`"),
+            CompilerError::PrivateItem(..) => diagnostic
+                .with_help("declare it as `pub` to use it outside of its module"),
+            CompilerError::MissingStdItem(..) => diagnostic
+                .with_help("compile the program together with the `std` directory"),
+            CompilerError::UninferredReturnType(function, _) => diagnostic
+                .with_help(format!("write out the return type, eg. `fun {}(): i32`", function.1)),
+            CompilerError::LiteralOutOfRange { ty, .. } => match integer_range(ty.as_str()) {
+                Some((min, max)) => diagnostic.with_note(format!("`{ty}` ranges from {min} to {max}")),
+                None => diagnostic,
+            },
+            CompilerError::DuplicateMain { previous, .. } => diagnostic
+                .with_secondary(*previous, Some("first defined here".into()))
+                .with_note("a program starts at a single function named `main`"),
+            CompilerError::TraitSignatureMismatch { expected, .. } => diagnostic
+                .with_note(format!("the trait declares it as `{expected}`")),
+            CompilerError::OrphanImpl { .. } => diagnostic
+                .with_note("a trait can only be implemented in the module of the trait or in the module of the type, so no two modules implement it differently"),
+            CompilerError::OverlappingImpls { previous, .. } => diagnostic
+                .with_secondary(*previous, Some("first implementation here".into())),
+            CompilerError::IncompatibleBranches { then, then_trace, .. } => diagnostic
+                .with_secondary(*then_trace, Some(format!("this is `{then}`"))),
+            CompilerError::NoFields(..) => diagnostic
+                .with_note("only structures have fields"),
+            CompilerError::NotIndexable(..) => diagnostic
+                .with_note("only arrays and types implementing `std::ops::Index` can be indexed"),
+            CompilerError::UnknownMethod { .. } => diagnostic
+                .with_note("methods are functions of impl blocks taking `self`, `ref[self]` or `[self]` as their first argument"),
+            CompilerError::NotIterable(..) => diagnostic
+                .with_note("loops go over integer ranges like `0..n` and over values implementing `Iterator` of `std::iter`"),
+            CompilerError::NotConstant(..) => diagnostic
+                .with_note("constants are computed from literals, operators, casts, constructions and other constants"),
+            CompilerError::CyclicGlobals { cycle, .. } => {
+                let cycle: Vec<String> = cycle.iter().map(|global| format!("'{}'", global.1)).collect();
+                diagnostic.with_note(format!("the initializers refer to each other: {}", cycle.join(" -> ")))
+            }
+            CompilerError::AmbiguousImport { candidates, .. } => {
+                // paths written in code leave out the global module
+                let path: Vec<&str> = candidates[0].chain.iter().skip(1).map(|name| name.as_str()).collect();
+                let candidates: Vec<String> = candidates.iter().map(|candidate| format!("'{candidate}'")).collect();
+                diagnostic
+                    .with_note(format!("it could refer to {}", candidates.join(" or ")))
+                    .with_help(format!("import the one that is meant explicitly, eg. `import {}`", path.join("::")))
+            }
+            CompilerError::IncompatibleArms { first, first_trace, .. } => diagnostic
+                .with_secondary(*first_trace, Some(format!("this is `{first}`"))),
+            CompilerError::InconsistentBinding(..) => diagnostic
+                .with_note("every alternative of a `|` pattern has to bind the same variables"),
+            CompilerError::InvalidVariantPattern { expected, .. } => diagnostic
+                .with_help(format!("match it as `{expected}`")),
+            CompilerError::MissingPatternFields { .. } => diagnostic
+                .with_help("ignore the remaining fields with `..`"),
+            CompilerError::TraitNotImplemented { ty: DataType::Parameter(_), .. } => diagnostic
+                .with_note("type parameters have no bounds, the methods of a trait can only be called on concrete types"),
+            _ => diagnostic,
+        }
+    }
+}
+
+impl std::error::Error for CompilerError {}
+
+impl Display for CompilerError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            CompilerError::UnknownNamespace(namespace, _) => write!(f, "unknown namespace '{namespace}'"),
+            CompilerError::DuplicateNamespace(namespace) => write!(f, "namespace '{namespace}' is defined more than once"),
+            CompilerError::DuplicateFunction(function, _) => write!(f, "function '{function}' is defined more than once"),
+            CompilerError::UnknownType(name, _) => write!(f, "cannot find type '{name}'"),
+            CompilerError::UnknownVariable(name, _) => write!(f, "cannot find variable '{name}'"),
+            CompilerError::UnknownFunction(function, _) => write!(f, "cannot find function '{function}'"),
+            CompilerError::TypeMismatch { .. } => f.write_str("mismatched types"),
+            CompilerError::InvalidOperands { op, lhs, rhs, .. } => {
+                write!(f, "cannot apply `{}` to `{lhs}` and `{rhs}`", op.as_str())
+            }
+            CompilerError::InvalidUnaryOperand { op, ty, .. } => {
+                write!(f, "cannot apply unary `{}` to `{ty}`", op.as_str())
+            }
+            CompilerError::InvalidCast { from, to, .. } => write!(f, "cannot cast `{from}` as `{to}`"),
+            CompilerError::ArgumentCount { function, expected, found, .. } => {
+                write!(f, "function '{function}' takes {expected} argument(s) but {found} were supplied")
+            }
+            CompilerError::NoMatchingOverload { function, arguments, .. } => {
+                let arguments: Vec<String> = arguments.iter().map(DataType::to_string).collect();
+                write!(f, "no overload of '{function}' takes the arguments ({})", arguments.join(", "))
+            }
+            CompilerError::AssignToImmutable(name, _) => write!(f, "cannot assign to immutable variable '{name}'"),
+            CompilerError::InvalidAssignTarget(_) => f.write_str("invalid left hand side of assignment"),
+            CompilerError::TypeAnnotationNeeded(name, _) => write!(f, "type annotations needed for '{name}'"),
+            CompilerError::NotFormattable(ty, _) => write!(f, "`{ty}` cannot be formatted"),
+            CompilerError::UnfilledPlaceholder(_) => f.write_str("placeholder `{}` outside of a formatting macro"),
+            CompilerError::Unsupported(what, _) => write!(f, "{what} are not supported yet"),
+            CompilerError::DuplicateType(ty, _) => write!(f, "type '{ty}' is defined more than once"),
+            CompilerError::InvalidImplTarget(ty, _) => write!(f, "cannot define an impl block for `{ty}`"),
+            CompilerError::MissingFields { ty, fields, .. } => {
+                let fields: Vec<String> = fields.iter().map(|field| format!("'{field}'")).collect();
+                write!(f, "missing field(s) {} in construction of `{ty}`", fields.join(", "))
+            }
+            CompilerError::DuplicateField(field, _) => write!(f, "field '{field}' is specified more than once"),
+            CompilerError::UnknownField { ty, field, .. } => write!(f, "`{ty}` has no field named '{field}'"),
+            CompilerError::NotAStructure(ty, _) => write!(f, "`{ty}` is not a structure and cannot be constructed"),
+            CompilerError::DuplicateVariant(variant, _) => write!(f, "variant '{variant}' is defined more than once"),
+            CompilerError::UnknownVariant { ty, variant, .. } => write!(f, "`{ty}` has no variant named '{variant}'"),
+            CompilerError::InvalidVariantConstruction { variant, .. } => {
+                write!(f, "variant '{variant}' is not constructed like it is declared")
+            }
+            CompilerError::TypeArgumentCount { ty, expected, found, .. } => {
+                write!(f, "type '{ty}' takes {expected} type argument(s) but {found} were supplied")
+            }
+            CompilerError::UnknownTypeParameter(name, _) => write!(f, "cannot find type parameter '{name}'"),
+            CompilerError::DuplicateTypeParameter(name, _) => {
+                write!(f, "type parameter '{name}' is declared more than once")
+            }
+            CompilerError::UninferredTypeParameter { item, parameter, .. } => {
+                write!(f, "cannot infer type parameter '{parameter}' of '{item}'")
+            }
+            CompilerError::RecursiveInstantiation { item, .. } => {
+                write!(f, "reached the type nesting limit of {MAX_TYPE_DEPTH} while instantiating '{item}'")
+            }
+            CompilerError::MissingMain => f.write_str("`main` function not found"),
+            CompilerError::GenericMain(_) => f.write_str("`main` function cannot have type parameters"),
+            CompilerError::UnknownTrait(name, _) => write!(f, "cannot find trait '{name}'"),
+            CompilerError::MethodNotInTrait { method, trait_name, .. } => {
+                write!(f, "method '{method}' is not a member of trait '{trait_name}'")
+            }
+            CompilerError::TraitSignatureMismatch { method, .. } => {
+                write!(f, "method '{method}' has an incompatible signature for the trait")
+            }
+            CompilerError::MissingTraitMethods { trait_name, methods, .. } => {
+                let methods: Vec<String> = methods.iter().map(|method| format!("'{method}'")).collect();
+                write!(f, "not all methods of trait '{trait_name}' are implemented, missing {}", methods.join(", "))
+            }
+            CompilerError::OrphanImpl { trait_name, ty, .. } => {
+                write!(f, "cannot implement trait '{trait_name}' for `{ty}` outside of their modules")
+            }
+            CompilerError::OverlappingImpls { trait_name, ty, .. } => {
+                write!(f, "conflicting implementations of trait '{trait_name}' for `{ty}`")
+            }
+            CompilerError::TraitNotImplemented { trait_name, ty, .. } => {
+                write!(f, "trait '{trait_name}' is not implemented for `{ty}`")
+            }
+            CompilerError::ImportConflict(name, _) => write!(f, "the name '{name}' is defined more than once"),
+            CompilerError::NoFields(ty, _) => write!(f, "`{ty}` has no fields"),
+            CompilerError::NotIndexable(ty, _) => write!(f, "cannot index into a value of type `{ty}`"),
+            CompilerError::UnknownMethod { ty, method, .. } => write!(f, "no method named '{method}' found for `{ty}`"),
+            CompilerError::NotIterable(ty, _) => write!(f, "`{ty}` is not iterable"),
+            CompilerError::IncompatibleBranches { .. } => write!(f, "`if` and `else` have incompatible types"),
+            CompilerError::DuplicateGlobal(global, _) => write!(f, "global '{global}' is defined more than once"),
+            CompilerError::NotConstant(what, _) => write!(f, "{what} cannot be evaluated at compile time"),
+            CompilerError::CyclicGlobals { cycle, .. } => {
+                write!(f, "cycle detected while initializing '{}'", cycle[0])
+            }
+            CompilerError::AmbiguousImport { name, .. } => write!(f, "'{name}' is ambiguous"),
+            CompilerError::PatternMismatch { .. } => f.write_str("pattern can never match the matched value"),
+            CompilerError::IncompatibleArms { .. } => write!(f, "`match` arms have incompatible types"),
+            CompilerError::InconsistentBinding(name, _) => {
+                write!(f, "variable '{name}' is not bound in every alternative")
+            }
+            CompilerError::DuplicateBinding(name, _) => {
+                write!(f, "identifier '{name}' is bound more than once in the same pattern")
+            }
+            CompilerError::InvalidVariantPattern { variant, .. } => {
+                write!(f, "variant '{variant}' is not matched like it is declared")
+            }
+            CompilerError::MissingPatternFields { ty, fields, .. } => {
+                let fields: Vec<String> = fields.iter().map(|field| format!("'{field}'")).collect();
+                write!(f, "pattern of `{ty}` does not mention field(s) {}", fields.join(", "))
+            }
+            CompilerError::DuplicateMain { .. } => f.write_str("`main` function is defined more than once"),
+            CompilerError::PrivateItem(item, _) => write!(f, "'{item}' is private"),
+            CompilerError::MissingStdItem(item, _) => write!(f, "'{item}' of the standard library is not part of the program"),
+            CompilerError::UninferredReturnType(function, _) => {
+                write!(f, "cannot infer the return type of '{function}'")
+            }
+            CompilerError::LiteralOutOfRange { value, ty, .. } => {
+                write!(f, "literal `{value}` does not fit into the type `{ty}`")
+            }
         }
     }
 }
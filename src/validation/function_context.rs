@@ -1,22 +1,21 @@
-use std::collections::HashMap;
-use std::rc::Rc;
+use std::collections::BTreeMap;
 use uuid::Uuid;
 use crate::file::identifier::Identifier;
 use crate::validation::error::CompilerResult;
 use crate::validation::registry::ModuleRegistry;
 use crate::validation::registry::variable::VariableInfo;
 
-pub type LocalVariableTable = HashMap<Uuid, VariableInfo>;
+pub type LocalVariableTable = BTreeMap<Uuid, VariableInfo>;
 
 #[derive(Debug)]
-pub struct FunctionContext {
-    pub global_registry: Rc<ModuleRegistry>,
+pub struct FunctionContext<'a> {
+    pub global_registry: &'a ModuleRegistry,
     local_map: LocalVariableTable,
     scopes: Vec<Vec<(Identifier, Uuid)>>,
 }
 
-impl FunctionContext {
-    pub fn new(registry: Rc<ModuleRegistry>) -> Self {
+impl<'a> FunctionContext<'a> {
+    pub fn new(registry: &'a ModuleRegistry) -> Self {
         Self {
             global_registry: registry,
             local_map: Default::default(),
@@ -26,8 +25,9 @@ impl FunctionContext {
 
     pub fn declare_variable(&mut self, local: VariableInfo) -> CompilerResult<Uuid> {
 
-        // generate a new UUID for hygienic name creation
-        let uuid = Uuid::new_v4();
+        // locals are numbered in declaration order, which keeps names hygienic within the function
+        // and the validated output the same from one run to the next
+        let uuid = Uuid::from_u128(self.local_map.len() as u128);
 
         self.scopes
             .last_mut()
@@ -62,7 +62,12 @@ impl FunctionContext {
         self.scopes.pop().expect("Empty function context stack");
     }
 
+    #[allow(dead_code)]
     pub fn get_locals_table(&self) -> &LocalVariableTable {
         &self.local_map
     }
+
+    pub fn into_locals_table(self) -> LocalVariableTable {
+        self.local_map
+    }
 }
\ No newline at end of file
@@ -0,0 +1,2185 @@
+use std::collections::HashMap;
+use uuid::Uuid;
+use crate::file::identifier::{GlobalIdentifier, Identifier, Namespace};
+use crate::file::trace::Trace;
+use crate::ir::visibility::Visibility;
+use crate::parser::ast::data::{UnvalidatedType, UnvalidatedVariableInfo};
+use crate::parser::ast::expression::{UnvalidatedExpression, UnvalidatedFieldInit, UnvalidatedFormatSegment};
+use crate::parser::ast::function::{UnvalidatedFunctionExpression, RECEIVER, SELF_TYPE};
+use crate::parser::ast::operations::{BinaryOperation, UnaryOperator};
+use crate::parser::ast::pattern::{UnvalidatedFieldPattern, UnvalidatedMatchArm, UnvalidatedPattern};
+use crate::parser::ast::UnvalidatedSymbol;
+use crate::validation::data_type::{DataType, PrimitiveType};
+use crate::validation::error::{CompilerError, CompilerResult};
+use crate::validation::function_context::FunctionContext;
+use crate::validation::registry::enums::{EnumPrototype, EnumVariant, EnumVariantData};
+use crate::validation::registry::expression::{Expression, FormatArgument, FunctionStatement, MatchArm, Pattern};
+use crate::validation::registry::functions::{FunctionPrototype, ValidatedFunction};
+use crate::validation::registry::globals::{GlobalVariable, ValidatedGlobal};
+use crate::validation::registry::variable::VariableInfo;
+use crate::validation::registry::{ModuleRegistry, TypeScope};
+
+/// Type checks the body of a single function and lowers it into validated expressions.
+///
+/// An error inside of a statement only aborts that statement so every statement of the function
+/// gets checked. Values whose type could not be determined get the type [DataType::Never], which
+/// is accepted everywhere so the same mistake is not reported twice.
+pub struct FunctionValidator<'a> {
+    context: FunctionContext<'a>,
+
+    /// Module the function is declared in, unqualified names are looked up here
+    module: &'a ModuleRegistry,
+
+    /// `Self` and the type parameters of the function
+    scope: &'a TypeScope,
+
+    /// Function whose body is checked, `None` while checking the initializer of a global
+    prototype: Option<&'a FunctionPrototype>,
+
+    /// Where the checked function or global is declared
+    trace: Trace,
+
+    /// Set while checking the initializer of a `const`, only values known at compile time are
+    /// accepted then
+    constant: bool,
+
+    /// Every global that was referred to, in the order of the references
+    globals: Vec<GlobalIdentifier>,
+
+    /// Every called function whose return type was not inferred yet
+    functions: Vec<FunctionPrototype>,
+    errors: Vec<CompilerError>,
+}
+
+/// Items a function body or the initializer of a global referred to, values of those whose type
+/// was not known yet were checked with placeholder types
+pub struct References {
+    pub globals: Vec<GlobalIdentifier>,
+    pub functions: Vec<FunctionPrototype>,
+}
+
+/// Validated node together with the type of the value it produces
+type Typed<T> = CompilerResult<(T, DataType)>;
+
+/// Module of the standard library declaring [ITERATOR] and [RANGE], relative to the global module
+const ITER_MODULE: [&str; 2] = ["std", "iter"];
+
+/// Module of the standard library declaring [INDEX], relative to the global module
+const OPS_MODULE: [&str; 2] = ["std", "ops"];
+
+/// Trait of the values besides arrays that can be indexed, `value[i]` calls its `index` method
+const INDEX: &str = "Index";
+
+/// Trait of the values `for` loops can go over besides ranges
+const ITERATOR: &str = "Iterator";
+
+/// Structure ranges like `0..n` are when they are used as values
+const RANGE: &str = "Range";
+
+/// Variant a pattern matches, with the parameters of its enum bound to the matched type arguments
+type MatchedVariant<'a> = (&'a EnumPrototype, &'a EnumVariant, HashMap<Identifier, DataType>);
+
+impl<'a> FunctionValidator<'a> {
+    /// Type checks the body of a function. Functions without a return type get the type of their
+    /// body, which is only final once every function and global it refers to has a known type
+    pub fn validate(
+        global_registry: &'a ModuleRegistry,
+        module: &'a ModuleRegistry,
+        scope: &'a TypeScope,
+        prototype: &'a FunctionPrototype,
+        body: &UnvalidatedFunctionExpression,
+    ) -> (Result<ValidatedFunction, Vec<CompilerError>>, References) {
+        let mut validator = Self {
+            context: FunctionContext::new(global_registry),
+            module,
+            scope,
+            prototype: Some(prototype),
+            trace: prototype.trace.expect("Validated function without a trace"),
+            constant: false,
+            globals: vec![],
+            functions: vec![],
+            errors: vec![],
+        };
+
+        validator.context.push_scope();
+        for argument in &prototype.arguments {
+            validator.context
+                .declare_variable(argument.clone())
+                .expect("Declaring an argument can not fail");
+        }
+
+        let (validated, ty) = match validator.check_statement(body, prototype.returns.as_ref()) {
+            Ok((FunctionStatement::Expression(validated), ty)) => {
+                match &prototype.returns {
+                    Some(returns) if !ty.is_assignable_to(returns) => {
+                        validator.errors.push(CompilerError::TypeMismatch {
+                            expected: returns.clone(),
+                            found: ty.clone(),
+                            trace: match body {
+                                UnvalidatedFunctionExpression::Expression(expr) => tail_trace(expr),
+                                _ => validator.trace,
+                            },
+                        });
+                    }
+                    _ => {}
+                }
+                (validated, ty)
+            }
+            Ok((statement, _)) => (Expression::Scope(vec![statement]), DataType::Unit),
+            Err(err) => {
+                validator.errors.push(err);
+                (Expression::Scope(vec![]), DataType::Never)
+            }
+        };
+        validator.context.pop_scope();
+
+        let references = References { globals: validator.globals, functions: validator.functions };
+        if !validator.errors.is_empty() {
+            return (Err(validator.errors), references);
+        }
+
+        let validated = ValidatedFunction {
+            prototype: FunctionPrototype {
+                returns: Some(prototype.returns.clone().unwrap_or(ty)),
+                ..prototype.clone()
+            },
+            locals: validator.context.into_locals_table(),
+            body: validated,
+        };
+        (Ok(validated), references)
+    }
+
+    /// Type checks the initializer of a global, it has to evaluate to the type of the global if
+    /// one was written. Also returns every global the initializer refers to, even if it is
+    /// invalid, so that cycles between initializers can be found. Globals and functions whose
+    /// type is not known yet are treated as [DataType::Never], the initializer has to be checked
+    /// again once their type is known
+    pub fn validate_global(
+        global_registry: &'a ModuleRegistry,
+        module: &'a ModuleRegistry,
+        scope: &'a TypeScope,
+        global: &GlobalVariable,
+        initial: &UnvalidatedExpression,
+    ) -> (Result<ValidatedGlobal, Vec<CompilerError>>, References) {
+        let mut validator = Self {
+            context: FunctionContext::new(global_registry),
+            module,
+            scope,
+            prototype: None,
+            trace: global.trace,
+            constant: global.constant,
+            globals: vec![],
+            functions: vec![],
+            errors: vec![],
+        };
+
+        validator.context.push_scope();
+        let validated = validator
+            .check_expression(initial, global.data_type.as_ref())
+            .and_then(|(expr, ty)| match &global.data_type {
+                Some(declared) => {
+                    validator.expect_type(declared, &ty, tail_trace(initial))?;
+                    Ok((expr, declared.clone()))
+                }
+                None => Ok((expr, ty)),
+            });
+        validator.context.pop_scope();
+
+        let result = match validated {
+            Ok((initial, data_type)) if validator.errors.is_empty() => Ok(ValidatedGlobal {
+                variable: GlobalVariable { data_type: Some(data_type), ..global.clone() },
+                locals: validator.context.into_locals_table(),
+                initial,
+            }),
+            Ok(_) => Err(validator.errors),
+            Err(err) => {
+                validator.errors.push(err);
+                Err(validator.errors)
+            }
+        };
+
+        (result, References { globals: validator.globals, functions: validator.functions })
+    }
+
+    fn resolve_type(&self, ty: &UnvalidatedType) -> CompilerResult<DataType> {
+        self.module.resolve_type(ty, self.scope)
+    }
+
+    /// Checks that a value of type `found` can be used where `expected` is required
+    fn expect_type(&self, expected: &DataType, found: &DataType, trace: Trace) -> CompilerResult<()> {
+        if found.is_assignable_to(expected) {
+            Ok(())
+        } else {
+            Err(CompilerError::TypeMismatch {
+                expected: expected.clone(),
+                found: found.clone(),
+                trace,
+            })
+        }
+    }
+
+    /// Validates a statement, the returned type is the value the statement evaluates to when it
+    /// ends a block. `expected` is only used to infer the type of literals.
+    fn check_statement(
+        &mut self,
+        statement: &UnvalidatedFunctionExpression,
+        expected: Option<&DataType>,
+    ) -> Typed<FunctionStatement> {
+        use UnvalidatedFunctionExpression as E;
+
+        Ok(match statement {
+            E::Expression(expr) => {
+                let (expr, ty) = self.check_expression(expr, expected)?;
+                (FunctionStatement::Expression(expr), ty)
+            }
+            E::If { condition, then, .. } => {
+                let condition = self.check_condition(condition)?;
+                let (then, _) = self.check_expression(then, None)?;
+
+                (FunctionStatement::If {
+                    condition,
+                    then: Box::new(FunctionStatement::Expression(then)),
+                    otherwise: None,
+                }, DataType::Unit)
+            }
+            E::While { condition, then, .. } => {
+                let condition = self.check_condition(condition)?;
+                let (then, _) = self.check_expression(then, None)?;
+
+                (FunctionStatement::While {
+                    condition,
+                    then: Box::new(FunctionStatement::Expression(then)),
+                }, DataType::Unit)
+            }
+            E::For { variable, iterable, then, .. } => {
+                self.context.push_scope();
+                let result = match iterable {
+                    UnvalidatedExpression::Range { start, end, inclusive, trace } => {
+                        self.check_range_loop(variable, start, end, *inclusive, then, *trace)
+                    }
+                    iterable => self.check_iterator_loop(variable, iterable, then),
+                };
+                self.context.pop_scope();
+
+                (result?, DataType::Unit)
+            }
+            E::Let { variable, initial, trace } => {
+                (self.check_let(variable, initial.as_ref(), *trace)?, DataType::Unit)
+            }
+            E::Return(value) => {
+                let Some(prototype) = self.prototype else {
+                    return Err(CompilerError::Unsupported("returns outside of functions", self.trace));
+                };
+
+                // the type of the body is what an inferred function returns, a `return` inside
+                // of it would have to agree with it
+                let Some(returns) = prototype.returns.clone() else {
+                    return Err(CompilerError::UninferredReturnType(prototype.name.clone(), self.trace));
+                };
+
+                let value = match value {
+                    Some(value) => {
+                        let (expr, ty) = self.check_expression(value, Some(&returns))?;
+                        self.expect_type(&returns, &ty, value.trace())?;
+                        Some(expr)
+                    }
+                    None => {
+                        self.expect_type(&returns, &DataType::Unit, self.trace)?;
+                        None
+                    }
+                };
+
+                (FunctionStatement::Return(value), DataType::Never)
+            }
+        })
+    }
+
+    fn check_condition(&mut self, condition: &UnvalidatedExpression) -> CompilerResult<Expression> {
+        let bool = DataType::Primitive(PrimitiveType::Bool);
+
+        let (expr, ty) = self.check_expression(condition, Some(&bool))?;
+        self.expect_type(&bool, &ty, condition.trace())?;
+        Ok(expr)
+    }
+
+    /// Integer ranges count up in a `while` loop, the loop variable is a copy of the counter so
+    /// the body can not skip iterations
+    fn check_range_loop(
+        &mut self,
+        variable: &Identifier,
+        start: &UnvalidatedExpression,
+        end: &UnvalidatedExpression,
+        inclusive: bool,
+        then: &UnvalidatedExpression,
+        trace: Trace,
+    ) -> CompilerResult<FunctionStatement> {
+        let (start, end_expr, ty) = self.check_range_bounds(start, end, None, trace)?;
+
+        let primitive = match &ty {
+            DataType::Primitive(primitive) => *primitive,
+
+            // a bound diverges before the loop starts
+            DataType::Never => {
+                self.declare_local(variable, DataType::Never, false)?;
+                self.check_expression(then, None)?;
+
+                return Ok(FunctionStatement::Scope(vec![
+                    FunctionStatement::Expression(start),
+                    FunctionStatement::Expression(end_expr),
+                ]));
+            }
+            _ => unreachable!("Range bounds are integers"),
+        };
+
+        let counter = self.declare_local(&"for.counter".into(), ty.clone(), true)?;
+        let last = self.declare_local(&"for.end".into(), ty.clone(), false)?;
+        let value = self.declare_local(variable, ty, false)?;
+        let (body, _) = self.check_expression(then, None)?;
+
+        let local = |variable| Box::new(Expression::LocalVariableReference(variable));
+        let compare = |op| Expression::Binary { lhs: local(counter), op, rhs: local(last) };
+        let increment = FunctionStatement::Expression(Expression::Binary {
+            lhs: local(counter),
+            op: BinaryOperation::PlusAssign,
+            rhs: Box::new(one(primitive)),
+        });
+
+        let mut statements = vec![
+            FunctionStatement::Let { variable: counter, initial: Some(start) },
+            FunctionStatement::Let { variable: last, initial: Some(end_expr) },
+        ];
+        let mut iteration = vec![
+            FunctionStatement::Let { variable: value, initial: Some(*local(counter)) },
+            FunctionStatement::Expression(body),
+        ];
+
+        // counting past the end of an inclusive range could overflow, so whether to go on is
+        // decided before incrementing
+        let condition = if inclusive {
+            let going = self.declare_local(&"for.going".into(), DataType::Primitive(PrimitiveType::Bool), true)?;
+            statements.push(FunctionStatement::Let {
+                variable: going,
+                initial: Some(compare(BinaryOperation::LesserOrEquals)),
+            });
+            iteration.push(FunctionStatement::Expression(Expression::Binary {
+                lhs: local(going),
+                op: BinaryOperation::Assign,
+                rhs: Box::new(compare(BinaryOperation::Less)),
+            }));
+            iteration.push(FunctionStatement::If {
+                condition: *local(going),
+                then: Box::new(increment),
+                otherwise: None,
+            });
+            *local(going)
+        } else {
+            iteration.push(increment);
+            compare(BinaryOperation::Less)
+        };
+
+        statements.push(FunctionStatement::While {
+            condition,
+            then: Box::new(FunctionStatement::Scope(iteration)),
+        });
+        Ok(FunctionStatement::Scope(statements))
+    }
+
+    /// Bounds of a range, literals take the type of the other bound. `element` is the type the
+    /// bounds are expected to have, the bounds have to be integers of the same type
+    fn check_range_bounds(
+        &mut self,
+        start: &UnvalidatedExpression,
+        end: &UnvalidatedExpression,
+        element: Option<&DataType>,
+        trace: Trace,
+    ) -> CompilerResult<(Expression, Expression, DataType)> {
+        let ((start, start_ty), (end_expr, end_ty)) = if is_literal(start) && !is_literal(end) {
+            let end = self.check_expression(end, element)?;
+            (self.check_expression(start, Some(&end.1))?, end)
+        } else {
+            let start = self.check_expression(start, element)?;
+            let end = self.check_expression(end, Some(&start.1))?;
+            (start, end)
+        };
+
+        let ty = if start_ty == DataType::Never { end_ty } else {
+            self.expect_type(&start_ty, &end_ty, end.trace())?;
+            start_ty
+        };
+
+        match &ty {
+            DataType::Primitive(primitive) if primitive.is_integer() => Ok((start, end_expr, ty)),
+            DataType::Never => Ok((start, end_expr, ty)),
+            _ => Err(CompilerError::NotIterable(ty, trace)),
+        }
+    }
+
+    /// Range used as a value rather than as the head of a `for` loop, it is a `Range` of
+    /// `std::iter` which iterators go over
+    fn check_range(
+        &mut self,
+        start: &UnvalidatedExpression,
+        end: &UnvalidatedExpression,
+        inclusive: bool,
+        expected: Option<&DataType>,
+        trace: Trace,
+    ) -> Typed<Expression> {
+        let name = self.std_item(ITER_MODULE, RANGE);
+        let registry = self.context.global_registry;
+        if registry.get_struct(&name).is_none() {
+            return Err(CompilerError::MissingStdItem(name, trace));
+        }
+
+        let element = match expected {
+            Some(DataType::Structure(structure, arguments)) if *structure == name => arguments.first(),
+            _ => None,
+        };
+        let (start, end, ty) = self.check_range_bounds(start, end, element, trace)?;
+
+        let construction = Expression::Construct {
+            structure: name.clone(),
+            type_arguments: vec![ty.clone()],
+            fields: vec![start, end, Expression::BoolLiteral(inclusive)],
+        };
+        Ok((construction, DataType::Structure(name, vec![ty])))
+    }
+
+    /// Item of the given module of the standard library
+    fn std_item(&self, module: [&str; 2], name: &str) -> GlobalIdentifier {
+        let module = module
+            .iter()
+            .fold(self.context.global_registry.path().clone(), |module, name| module.module((*name).into()));
+        GlobalIdentifier(module, name.into())
+    }
+
+    /// Any other value has to implement `Iterator` of `std::iter`, the loop calls `has_next`
+    /// before every iteration and binds the result of `next` to the variable
+    fn check_iterator_loop(
+        &mut self,
+        variable: &Identifier,
+        iterable: &UnvalidatedExpression,
+        then: &UnvalidatedExpression,
+    ) -> CompilerResult<FunctionStatement> {
+        let trace = iterable.trace();
+        let (initial, ty) = self.check_expression(iterable, None)?;
+
+        // references to iterators are iterated over like the iterators themselves
+        let mut iterated = &ty;
+        while let DataType::Pointer(inner) = iterated {
+            iterated = inner;
+        }
+
+        let iterator_trait = self.std_item(ITER_MODULE, ITERATOR);
+        if *iterated != DataType::Never && self.context.global_registry.implemented_arguments(&iterator_trait, iterated).is_none() {
+            return Err(CompilerError::NotIterable(ty, trace));
+        }
+
+        let iterator = self.declare_local(&"for.iterator".into(), ty.clone(), true)?;
+        let local = || Expression::LocalVariableReference(iterator);
+
+        let (condition, _) = self.call_method(local(), ty.clone(), trace, &"has_next".into(), &[], None, trace)?;
+        let (next, item_ty) = self.call_method(local(), ty, trace, &"next".into(), &[], None, trace)?;
+
+        let value = self.declare_local(variable, item_ty, false)?;
+        let (body, _) = self.check_expression(then, None)?;
+
+        Ok(FunctionStatement::Scope(vec![
+            FunctionStatement::Let { variable: iterator, initial: Some(initial) },
+            FunctionStatement::While {
+                condition,
+                then: Box::new(FunctionStatement::Scope(vec![
+                    FunctionStatement::Let { variable: value, initial: Some(next) },
+                    FunctionStatement::Expression(body),
+                ])),
+            },
+        ]))
+    }
+
+    /// Declares a variable the validator introduces itself, the names of hidden ones can not be
+    /// written in source code
+    fn declare_local(&mut self, name: &Identifier, data_type: DataType, mutable: bool) -> CompilerResult<Uuid> {
+        self.context.declare_variable(VariableInfo { name: name.clone(), data_type, mutable })
+    }
+
+    fn check_let(
+        &mut self,
+        variable: &UnvalidatedVariableInfo,
+        initial: Option<&UnvalidatedExpression>,
+        trace: Trace,
+    ) -> CompilerResult<FunctionStatement> {
+        let declared = match &variable.ty {
+            UnvalidatedType::Implicit => None,
+            ty => Some(self.resolve_type(ty)?),
+        };
+
+        let initial = initial.map(|initial| {
+            self.check_expression(initial, declared.as_ref()).and_then(|(expr, ty)| {
+                if let Some(declared) = &declared {
+                    self.expect_type(declared, &ty, initial.trace())?;
+                }
+                Ok((expr, ty))
+            })
+        });
+
+        // the variable is declared even if its initializer is invalid, otherwise every use of it
+        // would be reported as well
+        let (initial, data_type) = match initial {
+            Some(Ok((expr, ty))) => (Some(expr), declared.unwrap_or(ty)),
+            Some(Err(err)) => {
+                self.errors.push(err);
+                (None, declared.unwrap_or(DataType::Never))
+            }
+            None => match declared {
+                Some(ty) => (None, ty),
+                None => {
+                    self.errors.push(CompilerError::TypeAnnotationNeeded(variable.ident.clone(), trace));
+                    (None, DataType::Never)
+                }
+            },
+        };
+
+        let variable = self.context.declare_variable(VariableInfo {
+            name: variable.ident.clone(),
+            data_type,
+            mutable: variable.mutable,
+        })?;
+
+        Ok(FunctionStatement::Let { variable, initial })
+    }
+
+    fn check_expression(&mut self, expr: &UnvalidatedExpression, expected: Option<&DataType>) -> Typed<Expression> {
+        use UnvalidatedExpression as E;
+
+        let primitive = |primitive| DataType::Primitive(primitive);
+
+        if self.constant {
+            self.check_constant(expr)?;
+        }
+
+        Ok(match expr {
+            E::BoolLiteral(value, _) => (Expression::BoolLiteral(*value), primitive(PrimitiveType::Bool)),
+            E::CharLiteral(value, _) => (Expression::CharLiteral(*value), primitive(PrimitiveType::Char)),
+            E::IntegerLiteral(value, trace) => coerce_integer((*value).into(), expected, *trace)?,
+            E::I32Literal(value, _) => (Expression::I32Literal(*value), primitive(PrimitiveType::I32)),
+            E::F32Literal(value, _) => coerce_float(*value, expected),
+            E::F64Literal(value, _) => (Expression::F64Literal(*value), primitive(PrimitiveType::F64)),
+            E::I64Literal(value, _) => (Expression::I64Literal(*value), primitive(PrimitiveType::I64)),
+            E::U32Literal(value, _) => (Expression::U32Literal(*value), primitive(PrimitiveType::U32)),
+            E::U64Literal(value, _) => (Expression::U64Literal(*value), primitive(PrimitiveType::U64)),
+            E::UsizeLiteral(value, _) => (Expression::UsizeLiteral(*value), primitive(PrimitiveType::Usize)),
+            E::StringLiteral(value, _) => (Expression::StringLiteral(value.clone()), DataType::string()),
+            E::FormatString { segments, .. } => (self.check_format_string(segments)?, DataType::string()),
+
+            E::Scope(statements, _) => self.check_scope(statements, expected),
+            E::Parenthetical(expr, _) => {
+                let (expr, ty) = self.check_expression(expr, expected)?;
+                (Expression::Parenthetical(Box::new(expr)), ty)
+            }
+            E::Binary { lhs, op, rhs, trace } => self.check_binary(lhs, *op, rhs, *trace, expected)?,
+            E::Unary { expr, op, trace } => self.check_unary(expr, *op, *trace, expected)?,
+            E::Cast { expr, ty, trace } => {
+                let target = self.resolve_type(ty)?;
+                let (expr, from) = self.check_expression(expr, None)?;
+
+                if !is_valid_cast(&from, &target) {
+                    return Err(CompilerError::InvalidCast { from, to: target, trace: *trace });
+                }
+                (Expression::Cast { expr: Box::new(expr), ty: target.clone() }, target)
+            }
+            E::VariableReference { symbol, trace } => match self.get_variant(symbol, *trace) {
+                Some((enumeration, variant)) => self.check_variant(enumeration, variant, None, expected, *trace)?,
+                None => self.check_variable(symbol, *trace)?,
+            },
+            E::FunctionCall { symbol, arguments, trace } => match self.get_variant(symbol, *trace) {
+                Some((enumeration, variant)) => {
+                    self.check_variant(enumeration, variant, Some(arguments), expected, *trace)?
+                }
+                None => self.check_call(symbol, arguments, expected, *trace)?,
+            },
+            E::Construct { ty, variant, fields, heap, trace } => {
+                self.check_construction(ty, variant.as_ref(), fields, *heap, expected, *trace)?
+            }
+
+            E::IfElse { condition, then, otherwise, .. } => self.check_if_else(condition, then, otherwise, expected)?,
+            E::Match { scrutinee, arms, .. } => self.check_match(scrutinee, arms, expected)?,
+            E::ObjectProperty { object, identifier, trace } => {
+                let (object, ty) = self.check_expression(object, None)?;
+                self.check_field(object, ty, identifier, *trace)?
+            }
+            E::MethodCall { object, method, arguments, trace } => {
+                self.check_method_call(object, method, arguments, expected, *trace)?
+            }
+            E::MacroCall { .. } => unreachable!("Macros are expanded before validation"),
+            E::Range { start, end, inclusive, trace } => self.check_range(start, end, *inclusive, expected, *trace)?,
+            E::Index { object, index, trace } => {
+                let (object, ty) = self.check_expression(object, None)?;
+                self.check_index(object, ty, index, *trace)?
+            }
+
+            // only reachable if the parser reported an error already
+            E::Error(_) => (Expression::Scope(vec![]), DataType::Never),
+        })
+    }
+
+    /// Validates every statement in a new scope, the block evaluates to its last statement
+    fn check_scope(
+        &mut self,
+        statements: &[UnvalidatedFunctionExpression],
+        expected: Option<&DataType>,
+    ) -> (Expression, DataType) {
+        self.context.push_scope();
+
+        let mut validated = vec![];
+        let mut ty = DataType::Unit;
+
+        for (i, statement) in statements.iter().enumerate() {
+            let expected = if i + 1 == statements.len() { expected } else { None };
+
+            ty = match self.check_statement(statement, expected) {
+                Ok((statement, ty)) => {
+                    validated.push(statement);
+                    ty
+                }
+                Err(err) => {
+                    self.errors.push(err);
+                    DataType::Never
+                }
+            };
+        }
+
+        self.context.pop_scope();
+        (Expression::Scope(validated), ty)
+    }
+
+    /// A branch that diverges takes the type of the other one
+    fn check_if_else(
+        &mut self,
+        condition: &UnvalidatedExpression,
+        then: &UnvalidatedFunctionExpression,
+        otherwise: &UnvalidatedFunctionExpression,
+        expected: Option<&DataType>,
+    ) -> Typed<Expression> {
+        let condition = self.check_condition(condition)?;
+        let (then_statement, then_ty) = self.check_statement(then, expected)?;
+
+        let hint = if then_ty == DataType::Never { expected } else { Some(&then_ty) };
+        let (otherwise_statement, otherwise_ty) = self.check_statement(otherwise, hint)?;
+
+        let ty = match (then_ty, otherwise_ty) {
+            (DataType::Never, ty) | (ty, DataType::Never) => ty,
+            (then_ty, otherwise_ty) if then_ty == otherwise_ty => then_ty,
+            (then_ty, otherwise_ty) => return Err(CompilerError::IncompatibleBranches {
+                then: then_ty,
+                otherwise: otherwise_ty,
+                trace: branch_trace(otherwise),
+                then_trace: branch_trace(then),
+            }),
+        };
+
+        Ok((Expression::IfElse {
+            condition: Box::new(condition),
+            then: Box::new(then_statement),
+            otherwise: Box::new(otherwise_statement),
+        }, ty))
+    }
+
+    /// Arms that diverge take the type of the others, like the branches of an `if`-`else`. An
+    /// error inside of an arm only aborts that arm so every arm gets checked
+    fn check_match(
+        &mut self,
+        scrutinee: &UnvalidatedExpression,
+        arms: &[UnvalidatedMatchArm],
+        expected: Option<&DataType>,
+    ) -> Typed<Expression> {
+        let (scrutinee, ty) = self.check_expression(scrutinee, None)?;
+
+        // references to structures and enums like `ref[self]` are matched through, references to
+        // anything else are compared as they are
+        let (scrutinee, ty) = match dereference(scrutinee.clone(), ty.clone()) {
+            behind @ (_, DataType::Structure(..) | DataType::Enumeration(..)) => behind,
+            _ => (scrutinee, ty),
+        };
+
+        let mut validated = vec![];
+        let mut first: Option<(DataType, Trace)> = None;
+
+        for arm in arms {
+            let hint = first.as_ref().map(|(ty, _)| ty).or(expected);
+
+            self.context.push_scope();
+            let checked = self.check_arm(arm, &ty, hint);
+            self.context.pop_scope();
+
+            let (arm_validated, arm_ty) = match checked {
+                Ok(checked) => checked,
+                Err(err) => {
+                    self.errors.push(err);
+                    continue;
+                }
+            };
+            validated.push(arm_validated);
+
+            match &first {
+                _ if arm_ty == DataType::Never => {}
+                None => first = Some((arm_ty, tail_trace(&arm.body))),
+                Some((first, _)) if *first == arm_ty => {}
+                Some((first, first_trace)) => self.errors.push(CompilerError::IncompatibleArms {
+                    first: first.clone(),
+                    found: arm_ty,
+                    trace: tail_trace(&arm.body),
+                    first_trace: *first_trace,
+                }),
+            }
+        }
+
+        let ty = first.map_or(DataType::Never, |(ty, _)| ty);
+        Ok((Expression::Match { scrutinee: Box::new(scrutinee), arms: validated }, ty))
+    }
+
+    /// Checks an arm inside of its own scope, which holds the variables its pattern binds
+    fn check_arm(&mut self, arm: &UnvalidatedMatchArm, ty: &DataType, expected: Option<&DataType>) -> Typed<MatchArm> {
+        let pattern = self.check_pattern(&arm.pattern, ty, &mut vec![], None)?;
+        let guard = arm.guard.as_ref().map(|guard| self.check_condition(guard)).transpose()?;
+        let (body, body_ty) = self.check_expression(&arm.body, expected)?;
+
+        Ok((MatchArm { pattern, guard, body }, body_ty))
+    }
+
+    /// Checks a pattern against the type of the value it matches and declares the variables it
+    /// binds, they are added to `bound`. `first` holds the variables the first alternative of a
+    /// `|` pattern bound while checking the other alternatives, which have to bind the same ones
+    fn check_pattern(
+        &mut self,
+        pattern: &UnvalidatedPattern,
+        ty: &DataType,
+        bound: &mut Vec<PatternBinding>,
+        first: Option<&[PatternBinding]>,
+    ) -> CompilerResult<Pattern> {
+        use UnvalidatedPattern as P;
+
+        match pattern {
+            P::Wildcard(_) => Ok(Pattern::Wildcard),
+            P::Binding { ident, mutable, trace } => {
+                // a lone name of a variant of the matched enum matches that variant
+                let variant = self
+                    .pattern_enum(ty)
+                    .and_then(|(enumeration, _)| Some((enumeration, enumeration.get_variant(ident)?)));
+
+                match variant {
+                    Some((_, variant)) if matches!(variant.data, EnumVariantData::Unit) => {
+                        Ok(Pattern::Variant { variant: variant.name.clone(), fields: vec![] })
+                    }
+                    Some((enumeration, variant)) => Err(invalid_variant_pattern(enumeration, variant, *trace)),
+                    None => self.bind(ident, ty, *mutable, *trace, bound, first),
+                }
+            }
+            P::Literal(literal) => Ok(Pattern::Literal(self.check_pattern_literal(literal, ty)?)),
+            P::Range { start, end, inclusive, trace } => {
+                let ordered = ty.primitive().is_some_and(|p| p.is_numeric() || p == PrimitiveType::Char);
+                if !ordered && *ty != DataType::Never {
+                    return Err(CompilerError::PatternMismatch { ty: ty.clone(), trace: *trace });
+                }
+
+                let start = start.as_deref().map(|start| self.check_pattern_literal(start, ty)).transpose()?;
+                let end = end.as_deref().map(|end| self.check_pattern_literal(end, ty)).transpose()?;
+                Ok(Pattern::Range { start, end, inclusive: *inclusive })
+            }
+            P::Variant { symbol, fields, trace } => {
+                let Some((enumeration, variant, bindings)) = self.pattern_variant(symbol, ty, *trace)? else {
+                    return self.bind_unknown(fields.iter(), bound, first);
+                };
+
+                let types = match &variant.data {
+                    EnumVariantData::Positional(types) if types.len() == fields.len() => types.as_slice(),
+                    EnumVariantData::Unit if fields.is_empty() => &[],
+                    _ => return Err(invalid_variant_pattern(enumeration, variant, *trace)),
+                };
+
+                let fields = fields
+                    .iter()
+                    .zip(types)
+                    .map(|(field, declared)| self.check_pattern(field, &declared.substitute(&bindings), bound, first))
+                    .collect::<CompilerResult<_>>()?;
+                Ok(Pattern::Variant { variant: variant.name.clone(), fields })
+            }
+            P::Struct { symbol, fields, rest, trace } => {
+                if let DataType::Structure(name, arguments) = ty {
+                    let named = symbol.identifier == name.1 && (symbol.explicit_namespace.chain.is_empty()
+                        || self.get_symbol_module(symbol, *trace)?.path() == &name.0);
+                    if !named {
+                        return Err(CompilerError::PatternMismatch { ty: ty.clone(), trace: *trace });
+                    }
+
+                    // the structure failed to be defined, which was already reported
+                    let Some(prototype) = self.context.global_registry.get_struct(name) else {
+                        return self.bind_unknown(fields.iter().map(|field| &field.pattern), bound, first);
+                    };
+
+                    let bindings = prototype.generics.iter().cloned().zip(arguments.iter().cloned()).collect();
+                    let declared: Vec<_> = prototype.fields.iter().map(|field| (&field.name, &field.data_type)).collect();
+                    let fields = self.check_field_patterns(ty, &declared, &bindings, fields, *rest, bound, first, *trace)?;
+                    return Ok(Pattern::Structure(fields));
+                }
+
+                let Some((enumeration, variant, bindings)) = self.pattern_variant(symbol, ty, *trace)? else {
+                    return self.bind_unknown(fields.iter().map(|field| &field.pattern), bound, first);
+                };
+                let EnumVariantData::StructLike(declared) = &variant.data else {
+                    return Err(invalid_variant_pattern(enumeration, variant, *trace));
+                };
+
+                let declared: Vec<_> = declared.iter().map(|(name, ty)| (name, ty)).collect();
+                let fields = self.check_field_patterns(ty, &declared, &bindings, fields, *rest, bound, first, *trace)?;
+                Ok(Pattern::Variant { variant: variant.name.clone(), fields })
+            }
+            P::Alternatives(alternatives, _) => {
+                let outer = bound.len();
+                let mut validated = vec![];
+
+                for (i, alternative) in alternatives.iter().enumerate() {
+                    if i == 0 {
+                        validated.push(self.check_pattern(alternative, ty, bound, first)?);
+                        continue;
+                    }
+
+                    let declared = bound[outer..].to_vec();
+                    let mut alternative_bound = bound[..outer].to_vec();
+                    validated.push(self.check_pattern(alternative, ty, &mut alternative_bound, Some(&declared))?);
+
+                    let missing = declared.iter().find(|binding| {
+                        !alternative_bound.iter().any(|other| other.name == binding.name)
+                    });
+                    if let Some(missing) = missing {
+                        return Err(CompilerError::InconsistentBinding(missing.name.clone(), alternative.trace()));
+                    }
+                }
+                Ok(Pattern::Alternatives(validated))
+            }
+        }
+    }
+
+    /// Declares the variable of a binding pattern, the other alternatives of a `|` pattern reuse
+    /// the one the first alternative declared
+    fn bind(
+        &mut self,
+        ident: &Identifier,
+        ty: &DataType,
+        mutable: bool,
+        trace: Trace,
+        bound: &mut Vec<PatternBinding>,
+        first: Option<&[PatternBinding]>,
+    ) -> CompilerResult<Pattern> {
+        if bound.iter().any(|binding| &binding.name == ident) {
+            return Err(CompilerError::DuplicateBinding(ident.clone(), trace));
+        }
+
+        let variable = match first {
+            Some(first) => {
+                let declared = first
+                    .iter()
+                    .find(|binding| &binding.name == ident)
+                    .ok_or_else(|| CompilerError::InconsistentBinding(ident.clone(), trace))?;
+                self.expect_type(&declared.data_type, ty, trace)?;
+                declared.variable
+            }
+            None => self.declare_local(ident, ty.clone(), mutable)?,
+        };
+
+        bound.push(PatternBinding { name: ident.clone(), variable, data_type: ty.clone() });
+        Ok(Pattern::Binding(variable))
+    }
+
+    /// Patterns matched against a value whose type is unknown, only their variables are declared
+    /// so that their uses are not reported as well
+    fn bind_unknown<'p>(
+        &mut self,
+        patterns: impl Iterator<Item = &'p UnvalidatedPattern>,
+        bound: &mut Vec<PatternBinding>,
+        first: Option<&[PatternBinding]>,
+    ) -> CompilerResult<Pattern> {
+        for pattern in patterns {
+            self.check_pattern(pattern, &DataType::Never, bound, first)?;
+        }
+        Ok(Pattern::Wildcard)
+    }
+
+    /// Literal or bound of a range pattern, it is compared to values of type `ty`
+    fn check_pattern_literal(&mut self, literal: &UnvalidatedExpression, ty: &DataType) -> CompilerResult<Expression> {
+        let (value, found) = self.check_expression(literal, Some(ty))?;
+
+        if *ty != DataType::Never {
+            self.expect_type(ty, &found, literal.trace())?;
+        }
+        if found == DataType::string() {
+            return Err(CompilerError::Unsupported("string patterns", literal.trace()));
+        }
+        Ok(value)
+    }
+
+    /// Enum a value of type `ty` is an instance of, with its parameters bound to the type arguments
+    fn pattern_enum(&self, ty: &DataType) -> Option<(&'a EnumPrototype, HashMap<Identifier, DataType>)> {
+        let DataType::Enumeration(name, arguments) = ty else {
+            return None;
+        };
+
+        let enumeration = self.context.global_registry.get_enum(name)?;
+        let bindings = enumeration.generics.iter().cloned().zip(arguments.iter().cloned()).collect();
+        Some((enumeration, bindings))
+    }
+
+    /// Variant a pattern names, it has to be a variant of the matched enum. A variant written
+    /// without its enum like `Some(v)` is looked up in the matched enum. [None] if the type of the
+    /// matched value is unknown
+    fn pattern_variant(
+        &self,
+        symbol: &UnvalidatedSymbol,
+        ty: &DataType,
+        trace: Trace,
+    ) -> CompilerResult<Option<MatchedVariant<'a>>> {
+        if !matches!(ty, DataType::Enumeration(..)) {
+            return match ty {
+                DataType::Never => Ok(None),
+                _ => Err(CompilerError::PatternMismatch { ty: ty.clone(), trace }),
+            };
+        }
+
+        // the enum failed to be defined, which was already reported
+        let Some((enumeration, bindings)) = self.pattern_enum(ty) else {
+            return Ok(None);
+        };
+
+        // `Option::Some(v)` has to name the matched enum
+        if !symbol.explicit_namespace.chain.is_empty() {
+            let module = self.get_symbol_module(symbol, trace)?;
+            if Some(module.path()) != ty.namespace().as_ref() {
+                return Err(CompilerError::PatternMismatch { ty: ty.clone(), trace });
+            }
+        }
+
+        let variant = enumeration.get_variant(&symbol.identifier).ok_or_else(|| CompilerError::UnknownVariant {
+            ty: ty.clone(),
+            variant: symbol.identifier.clone(),
+            trace,
+        })?;
+        Ok(Some((enumeration, variant, bindings)))
+    }
+
+    /// Checks the patterns of named fields, returns a pattern for every declared field in
+    /// declaration order
+    #[allow(clippy::too_many_arguments)]
+    fn check_field_patterns(
+        &mut self,
+        ty: &DataType,
+        declared: &[(&Identifier, &DataType)],
+        bindings: &HashMap<Identifier, DataType>,
+        fields: &[UnvalidatedFieldPattern],
+        rest: bool,
+        bound: &mut Vec<PatternBinding>,
+        first: Option<&[PatternBinding]>,
+        trace: Trace,
+    ) -> CompilerResult<Vec<Pattern>> {
+        let mut validated: Vec<Option<Pattern>> = declared.iter().map(|_| None).collect();
+
+        for field in fields {
+            let field_trace = field.pattern.trace();
+            let index = declared
+                .iter()
+                .position(|(name, _)| **name == field.field)
+                .ok_or_else(|| CompilerError::UnknownField {
+                    ty: ty.clone(),
+                    field: field.field.clone(),
+                    trace: field_trace,
+                })?;
+
+            if validated[index].is_some() {
+                return Err(CompilerError::DuplicateField(field.field.clone(), field_trace));
+            }
+
+            let field_ty = declared[index].1.substitute(bindings);
+            validated[index] = Some(self.check_pattern(&field.pattern, &field_ty, bound, first)?);
+        }
+
+        let missing: Vec<Identifier> = declared
+            .iter()
+            .zip(&validated)
+            .filter(|(_, pattern)| pattern.is_none())
+            .map(|((name, _), _)| (*name).clone())
+            .collect();
+
+        if !rest && !missing.is_empty() {
+            return Err(CompilerError::MissingPatternFields { ty: ty.clone(), fields: missing, trace });
+        }
+        Ok(validated.into_iter().map(|pattern| pattern.unwrap_or(Pattern::Wildcard)).collect())
+    }
+
+    fn check_format_string(&mut self, segments: &[UnvalidatedFormatSegment]) -> CompilerResult<Expression> {
+        let mut arguments = vec![];
+
+        for segment in segments {
+            match segment {
+                UnvalidatedFormatSegment::Literal(text) => arguments.push(FormatArgument::Literal(text.clone())),
+                UnvalidatedFormatSegment::Placeholder(trace) => {
+                    self.errors.push(CompilerError::UnfilledPlaceholder(*trace));
+                }
+                UnvalidatedFormatSegment::Expression(expr) => match self.check_format_argument(expr) {
+                    Ok(argument) => arguments.push(argument),
+                    Err(err) => self.errors.push(err),
+                },
+            }
+        }
+
+        Ok(Expression::Format(arguments))
+    }
+
+    fn check_format_argument(&mut self, expr: &UnvalidatedExpression) -> CompilerResult<FormatArgument> {
+        let (value, ty) = self.check_expression(expr, None)?;
+
+        if !is_formattable(&ty) {
+            return Err(CompilerError::NotFormattable(ty, expr.trace()));
+        }
+        Ok(FormatArgument::Value { value, ty })
+    }
+
+    /// Checks a `make` or `alloc` construction of a structure or of a struct-like enum variant
+    fn check_construction(
+        &mut self,
+        ty: &UnvalidatedType,
+        variant: Option<&Identifier>,
+        fields: &[UnvalidatedFieldInit],
+        heap: bool,
+        expected: Option<&DataType>,
+        trace: Trace,
+    ) -> Typed<Expression> {
+        // type arguments left out of `make Pair { .. }` are inferred from the fields
+        let generic = match ty {
+            UnvalidatedType::Type(name, _) if !self.scope.parameters.contains(name) => self.module.get_type(name).cloned(),
+            _ => None,
+        };
+        let data_type = match &generic {
+            Some(generic) => generic.clone(),
+            None => self.resolve_type(ty)?,
+        };
+
+        let expected = match expected {
+            Some(DataType::Pointer(inner)) if heap => Some(&**inner),
+            _ if heap => None,
+            expected => expected,
+        };
+
+        let registry = self.context.global_registry;
+        let inference = |generics, arguments| match generic {
+            Some(_) => Inference::new(generics),
+            None => Inference::given(generics, arguments),
+        };
+
+        let (construction, data_type) = match (&data_type, variant) {
+            (DataType::Structure(name, arguments), None) => {
+                // the structure failed to be defined, which was already reported
+                let Some(prototype) = registry.get_struct(name) else {
+                    return Ok((Expression::Scope(vec![]), DataType::Never));
+                };
+
+                let mut inference = inference(&prototype.generics, arguments);
+                inference.expect(&data_type, expected);
+
+                let declared: Vec<_> = prototype.fields.iter().map(|field| (&field.name, &field.data_type)).collect();
+                let fields = self.check_fields(&data_type, &declared, fields, &mut inference, trace)?;
+                let type_arguments = inference.finish(name, trace)?;
+
+                (Expression::Construct {
+                    structure: name.clone(),
+                    type_arguments: type_arguments.clone(),
+                    fields,
+                }, DataType::Structure(name.clone(), type_arguments))
+            }
+            (DataType::Enumeration(name, arguments), Some(variant)) => {
+                let Some(enumeration) = registry.get_enum(name) else {
+                    return Ok((Expression::Scope(vec![]), DataType::Never));
+                };
+
+                let unknown = || CompilerError::UnknownVariant { ty: data_type.clone(), variant: variant.clone(), trace };
+                let variant = enumeration.get_variant(variant).ok_or_else(unknown)?;
+
+                let EnumVariantData::StructLike(declared) = &variant.data else {
+                    return Err(CompilerError::InvalidVariantConstruction {
+                        variant: GlobalIdentifier(data_type.namespace().expect("Enums have a namespace"), variant.name.clone()),
+                        expected: variant.syntax(name),
+                        trace,
+                    });
+                };
+
+                let mut inference = inference(&enumeration.generics, arguments);
+                inference.expect(&data_type, expected);
+
+                let declared: Vec<_> = declared.iter().map(|(name, ty)| (name, ty)).collect();
+                let fields = self.check_fields(&data_type, &declared, fields, &mut inference, trace)?;
+                let type_arguments = inference.finish(name, trace)?;
+
+                (Expression::Variant {
+                    enumeration: name.clone(),
+                    type_arguments: type_arguments.clone(),
+                    variant: variant.name.clone(),
+                    fields,
+                }, DataType::Enumeration(name.clone(), type_arguments))
+            }
+            (DataType::Structure(..), Some(variant)) => {
+                return Err(CompilerError::UnknownVariant { ty: data_type.clone(), variant: variant.clone(), trace });
+            }
+            _ => return Err(CompilerError::NotAStructure(data_type, trace)),
+        };
+
+        if heap {
+            Ok((Expression::Alloc(Box::new(construction)), DataType::Pointer(Box::new(data_type))))
+        } else {
+            Ok((construction, data_type))
+        }
+    }
+
+    /// Checks that every declared field is given exactly once, the values are returned in the
+    /// order the fields were declared in
+    fn check_fields(
+        &mut self,
+        ty: &DataType,
+        declared: &[(&Identifier, &DataType)],
+        fields: &[UnvalidatedFieldInit],
+        inference: &mut Inference,
+        trace: Trace,
+    ) -> CompilerResult<Vec<Expression>> {
+        let mut values: Vec<Option<Expression>> = declared.iter().map(|_| None).collect();
+
+        for init in fields {
+            let Some(index) = declared.iter().position(|(name, _)| *name == &init.name) else {
+                self.errors.push(CompilerError::UnknownField {
+                    ty: ty.clone(),
+                    field: init.name.clone(),
+                    trace: init.trace,
+                });
+                continue;
+            };
+
+            if values[index].is_some() {
+                self.errors.push(CompilerError::DuplicateField(init.name.clone(), init.trace));
+                continue;
+            }
+
+            let expected = declared[index].1;
+            let value = self.check_expression(&init.value, inference.hint(expected).as_ref()).and_then(|(value, ty)| {
+                inference.check(expected, &ty, init.value.trace())?;
+                Ok(value)
+            });
+
+            // an invalid value still counts as given, it is not missing
+            values[index] = Some(value.unwrap_or_else(|err| {
+                self.errors.push(err);
+                Expression::Scope(vec![])
+            }));
+        }
+
+        let missing: Vec<Identifier> = declared
+            .iter()
+            .zip(&values)
+            .filter(|(_, value)| value.is_none())
+            .map(|((name, _), _)| (*name).clone())
+            .collect();
+
+        if !missing.is_empty() {
+            return Err(CompilerError::MissingFields { ty: ty.clone(), fields: missing, trace });
+        }
+
+        Ok(values.into_iter().flatten().collect())
+    }
+
+    fn check_binary(
+        &mut self,
+        lhs: &UnvalidatedExpression,
+        op: BinaryOperation,
+        rhs: &UnvalidatedExpression,
+        trace: Trace,
+        expected: Option<&DataType>,
+    ) -> Typed<Expression> {
+        if op == BinaryOperation::Assign || op.compound().is_some() {
+            return self.check_assignment(lhs, op, rhs, trace);
+        }
+
+        // arithmetic passes the expected type on to its operands, comparisons can not
+        let operand_expected = if is_comparison(op) { None } else { expected };
+
+        // literals take the type of the other operand
+        let ((lhs, lhs_ty), (rhs, rhs_ty)) = if is_literal(lhs) && !is_literal(rhs) {
+            let rhs = self.check_expression(rhs, operand_expected)?;
+            (self.check_expression(lhs, Some(&rhs.1))?, rhs)
+        } else {
+            let lhs = self.check_expression(lhs, operand_expected)?;
+            let rhs = self.check_expression(rhs, Some(&lhs.1))?;
+            (lhs, rhs)
+        };
+
+        let ty = binary_result(op, &lhs_ty, &rhs_ty)
+            .ok_or(CompilerError::InvalidOperands { op, lhs: lhs_ty, rhs: rhs_ty, trace })?;
+
+        Ok((Expression::Binary { lhs: Box::new(lhs), op, rhs: Box::new(rhs) }, ty))
+    }
+
+    fn check_assignment(
+        &mut self,
+        target: &UnvalidatedExpression,
+        op: BinaryOperation,
+        value: &UnvalidatedExpression,
+        trace: Trace,
+    ) -> Typed<Expression> {
+        let (target_expr, target_ty) = self.check_place(target, trace)?;
+        let (value_expr, value_ty) = self.check_expression(value, Some(&target_ty))?;
+
+        match op.compound() {
+            Some(operation) => {
+                binary_result(operation, &target_ty, &value_ty).ok_or(CompilerError::InvalidOperands {
+                    op,
+                    lhs: target_ty,
+                    rhs: value_ty,
+                    trace,
+                })?;
+            }
+            None => self.expect_type(&target_ty, &value_ty, value.trace())?,
+        }
+
+        Ok((Expression::Binary {
+            lhs: Box::new(target_expr),
+            op,
+            rhs: Box::new(value_expr),
+        }, DataType::Unit))
+    }
+
+    /// Validates the target of an assignment. Variables have to be mutable to be changed, unless
+    /// the value is reached through a reference or an array
+    fn check_place(&mut self, target: &UnvalidatedExpression, trace: Trace) -> Typed<Expression> {
+        match target {
+            UnvalidatedExpression::VariableReference { symbol, trace: target_trace } => {
+                if let Ok((variable, info)) = self.get_variable(symbol, *target_trace) {
+                    if !info.mutable {
+                        return Err(CompilerError::AssignToImmutable(info.name.clone(), trace));
+                    }
+                    return Ok((Expression::LocalVariableReference(variable), info.data_type.clone()));
+                }
+
+                let global = self.get_global(symbol, *target_trace)?;
+                if !global.mutable {
+                    return Err(CompilerError::AssignToImmutable(global.name.1.clone(), trace));
+                }
+                Ok((Expression::GlobalVariableReference(global.name.clone()), self.global_type(global)))
+            }
+            UnvalidatedExpression::ObjectProperty { object, identifier, trace: access_trace } => {
+                let (object, ty) = self.check_place_object(object, trace)?;
+                self.check_field(object, ty, identifier, *access_trace)
+            }
+            UnvalidatedExpression::Index { object, index, trace: index_trace } => {
+                let (object, ty) = self.check_place_object(object, trace)?;
+                self.check_array_index(object, ty, index, *index_trace)
+            }
+            UnvalidatedExpression::Parenthetical(target, _) => self.check_place(target, trace),
+            _ => Err(CompilerError::InvalidAssignTarget(target.trace())),
+        }
+    }
+
+    /// Object whose field or element is assigned to, it only has to be assignable itself if it
+    /// is not a reference or an array
+    fn check_place_object(&mut self, object: &UnvalidatedExpression, trace: Trace) -> Typed<Expression> {
+        let (expr, ty) = self.check_expression(object, None)?;
+
+        match ty {
+            DataType::Pointer(_) | DataType::Array(_) | DataType::Never => Ok((expr, ty)),
+            _ => self.check_place(object, trace),
+        }
+    }
+
+    /// Field of a checked object, the object is dereferenced as often as needed
+    fn check_field(&self, object: Expression, ty: DataType, field: &Identifier, trace: Trace) -> Typed<Expression> {
+        let (object, ty) = dereference(object, ty);
+        let access = |object| Expression::FieldAccess { object: Box::new(object), field: field.clone() };
+
+        let DataType::Structure(name, arguments) = &ty else {
+            return match ty {
+                DataType::Never => Ok((access(object), DataType::Never)),
+                ty => Err(CompilerError::NoFields(ty, trace)),
+            };
+        };
+
+        // the fields are missing if the structure failed to validate, which was reported already
+        let Some(structure) = self.context.global_registry.get_struct(name) else {
+            return Ok((access(object), DataType::Never));
+        };
+
+        let declared = structure
+            .get_field(field)
+            .ok_or_else(|| CompilerError::UnknownField { ty: ty.clone(), field: field.clone(), trace })?;
+        let bindings = structure.generics.iter().cloned().zip(arguments.iter().cloned()).collect();
+
+        Ok((access(object), declared.data_type.substitute(&bindings)))
+    }
+
+    /// Element of a checked array, the array is dereferenced as often as needed
+    /// Indexing of a value, values that are not arrays are indexed by the `index` method of their
+    /// implementation of `Index` from `std::ops`
+    fn check_index(
+        &mut self,
+        object: Expression,
+        ty: DataType,
+        index: &UnvalidatedExpression,
+        trace: Trace,
+    ) -> Typed<Expression> {
+        let mut indexed = &ty;
+        while let DataType::Pointer(inner) = indexed {
+            indexed = inner;
+        }
+
+        let index_trait = self.std_item(OPS_MODULE, INDEX);
+        match indexed {
+            DataType::Array(_) | DataType::Never => self.check_array_index(object, ty, index, trace),
+            indexed if self.context.global_registry.implemented_arguments(&index_trait, indexed).is_some() => {
+                self.call_method(object, ty, trace, &"index".into(), std::slice::from_ref(index), None, trace)
+            }
+            indexed => Err(CompilerError::NotIndexable(indexed.clone(), trace)),
+        }
+    }
+
+    /// Indexing of an array, which can be assigned to as well
+    fn check_array_index(
+        &mut self,
+        array: Expression,
+        ty: DataType,
+        index: &UnvalidatedExpression,
+        trace: Trace,
+    ) -> Typed<Expression> {
+        let (array, ty) = dereference(array, ty);
+        let element = match ty {
+            DataType::Array(element) => *element,
+            DataType::Never => DataType::Never,
+            ty => return Err(CompilerError::NotIndexable(ty, trace)),
+        };
+
+        let usize = DataType::Primitive(PrimitiveType::Usize);
+        let (index_expr, index_ty) = self.check_expression(index, Some(&usize))?;
+
+        if !(index_ty == DataType::Never || index_ty.primitive().is_some_and(PrimitiveType::is_integer)) {
+            return Err(CompilerError::TypeMismatch { expected: usize, found: index_ty, trace: index.trace() });
+        }
+
+        Ok((Expression::Index { array: Box::new(array), index: Box::new(index_expr) }, element))
+    }
+
+    fn check_unary(
+        &mut self,
+        operand: &UnvalidatedExpression,
+        op: UnaryOperator,
+        trace: Trace,
+        expected: Option<&DataType>,
+    ) -> Typed<Expression> {
+        // the minus belongs to the literal, so `-2147483648` is the smallest `i32`
+        if let (UnaryOperator::Negate, UnvalidatedExpression::IntegerLiteral(value, _)) = (op, operand) {
+            if literal_type(expected).is_signed() {
+                return coerce_integer(-i128::from(*value), expected, trace);
+            }
+        }
+
+        let (operand, ty) = self.check_expression(operand, expected)?;
+
+        let result = match (op, &ty) {
+            (_, DataType::Never) => Some(DataType::Never),
+            (UnaryOperator::Negate, DataType::Primitive(p)) if p.is_signed() => Some(ty.clone()),
+            (UnaryOperator::Not, DataType::Primitive(PrimitiveType::Bool)) => Some(ty.clone()),
+            (UnaryOperator::BitNot, DataType::Primitive(p)) if p.is_integer() => Some(ty.clone()),
+            (UnaryOperator::Ref, _) => Some(DataType::Pointer(Box::new(ty.clone()))),
+            (UnaryOperator::Deref, DataType::Pointer(inner)) => Some(*inner.clone()),
+            _ => None,
+        };
+
+        let ty = result.ok_or(CompilerError::InvalidUnaryOperand { op, ty, trace })?;
+        Ok((Expression::Unary { op, operand: Box::new(operand) }, ty))
+    }
+
+    /// Rejects the parts of a `const` initializer that only have a value once the program runs,
+    /// the operands are checked when they are validated themselves
+    fn check_constant(&self, expr: &UnvalidatedExpression) -> CompilerResult<()> {
+        use UnvalidatedExpression as E;
+
+        let what = match expr {
+            E::FunctionCall { symbol, trace, .. } if self.get_variant(symbol, *trace).is_none() => "function calls",
+            E::MethodCall { .. } => "method calls",
+            E::FormatString { .. } => "format strings",
+            E::Scope(..) => "blocks",
+            E::IfElse { .. } => "conditionals",
+            E::Match { .. } => "match expressions",
+            E::Binary { op, .. } if *op == BinaryOperation::Assign || op.compound().is_some() => "assignments",
+            E::Unary { op: UnaryOperator::Ref | UnaryOperator::Deref, .. } => "references",
+            E::Construct { heap: true, .. } => "heap allocations",
+            _ => return Ok(()),
+        };
+        Err(CompilerError::NotConstant(what, expr.trace()))
+    }
+
+    /// Local variable, or global if no local has the name
+    fn check_variable(&mut self, symbol: &UnvalidatedSymbol, trace: Trace) -> Typed<Expression> {
+        if let Ok((variable, info)) = self.get_variable(symbol, trace) {
+            return Ok((Expression::LocalVariableReference(variable), info.data_type.clone()));
+        }
+
+        let global = self.get_global(symbol, trace)?;
+        if self.constant && !global.constant {
+            return Err(CompilerError::NotConstant("globals that are not constants", trace));
+        }
+        Ok((Expression::GlobalVariableReference(global.name.clone()), self.global_type(global)))
+    }
+
+    fn get_global(&self, symbol: &UnvalidatedSymbol, trace: Trace) -> CompilerResult<&'a GlobalVariable> {
+        let unknown = || CompilerError::UnknownVariable(symbol.identifier.clone(), trace);
+        let (module, name) = self.get_item_module(symbol, ModuleRegistry::has_global, trace).map_err(|err| match err {
+            CompilerError::AmbiguousImport { .. } => err,
+            _ => unknown(),
+        })?;
+
+        let global = self.context.global_registry
+            .get_global(&module.create_identifier(name))
+            .ok_or_else(unknown)?;
+
+        self.check_visible(&global.name, &global.visibility, trace)?;
+        Ok(global)
+    }
+
+    /// Fails if the item is private to a module the function is not part of
+    fn check_visible(&self, item: &GlobalIdentifier, visibility: &Visibility, trace: Trace) -> CompilerResult<()> {
+        if self.context.global_registry.is_visible(item, visibility, self.module.path()) {
+            Ok(())
+        } else {
+            Err(CompilerError::PrivateItem(item.clone(), trace))
+        }
+    }
+
+    /// Type of a referenced global, [DataType::Never] if its initializer still has to be validated
+    /// to know it
+    fn global_type(&mut self, global: &GlobalVariable) -> DataType {
+        self.globals.push(global.name.clone());
+        global.data_type.clone().unwrap_or(DataType::Never)
+    }
+
+    /// Type a called function returns, [DataType::Never] if its body still has to be validated
+    /// to know it
+    fn return_type(&mut self, function: &FunctionPrototype) -> DataType {
+        if function.returns.is_none() {
+            self.functions.push(function.clone());
+        }
+        function.return_type()
+    }
+
+    fn get_variable(&self, symbol: &UnvalidatedSymbol, trace: Trace) -> CompilerResult<(uuid::Uuid, &VariableInfo)> {
+        if !symbol.explicit_namespace.chain.is_empty() {
+            return Err(CompilerError::UnknownVariable(symbol.identifier.clone(), trace));
+        }
+
+        self.context
+            .get_local(&symbol.identifier)
+            .ok_or_else(|| CompilerError::UnknownVariable(symbol.identifier.clone(), trace))
+    }
+
+    /// Module declaring the function or global a symbol refers to, together with the name it is
+    /// declared under. Unqualified names the current module does not declare can be imported
+    fn get_item_module(
+        &self,
+        symbol: &UnvalidatedSymbol,
+        declares: fn(&ModuleRegistry, &Identifier) -> bool,
+        trace: Trace,
+    ) -> CompilerResult<(&'a ModuleRegistry, Identifier)> {
+        let module = self.get_symbol_module(symbol, trace)?;
+        let name = &symbol.identifier;
+
+        if !symbol.explicit_namespace.chain.is_empty() || declares(module, name) {
+            return Ok((module, name.clone()));
+        }
+
+        module.check_ambiguous(name, trace)?;
+        let imported = module.get_import(name).and_then(|import| {
+            let (name, parent) = import.chain.split_last()?;
+            let parent = self.context.global_registry.get_module(&Namespace { chain: parent.to_vec() })?;
+            declares(parent, name).then(|| (parent, name.clone()))
+        });
+
+        Ok(imported.unwrap_or((module, name.clone())))
+    }
+
+    /// Module a symbol refers to, namespaces written in code are relative to the global module
+    fn get_symbol_module(&self, symbol: &UnvalidatedSymbol, trace: Trace) -> CompilerResult<&'a ModuleRegistry> {
+        let chain = &symbol.explicit_namespace.chain;
+        if chain.is_empty() {
+            return Ok(self.module);
+        }
+
+        let registry = self.context.global_registry;
+
+        // `Self::function` names a function of the current impl block or trait
+        if chain[0].as_str() == SELF_TYPE {
+            if let Some(namespace) = &self.scope.namespace {
+                let namespace = Namespace { chain: chain[1..].to_vec() }.prefix(namespace.clone());
+                return registry
+                    .get_module(&namespace)
+                    .ok_or(CompilerError::UnknownNamespace(namespace, trace));
+            }
+        }
+
+        // paths are relative to the current module first, so that types declared next to the
+        // function can be used without naming the module
+        let relative = symbol.explicit_namespace.prefix(self.module.path().clone());
+        if let Some(module) = registry.get_module(&relative) {
+            return Ok(module);
+        }
+
+        self.module.check_ambiguous(&chain[0], trace)?;
+        if let Some(import) = self.module.get_import(&chain[0]) {
+            let namespace = Namespace { chain: chain[1..].to_vec() }.prefix(import.clone());
+            return registry
+                .get_module(&namespace)
+                .ok_or(CompilerError::UnknownNamespace(namespace, trace));
+        }
+
+        let namespace = symbol.explicit_namespace.prefix(registry.path().clone());
+
+        registry
+            .get_module(&namespace)
+            .ok_or(CompilerError::UnknownNamespace(namespace, trace))
+    }
+
+    /// Enum variant a symbol like `Option::Some` refers to
+    fn get_variant(&self, symbol: &UnvalidatedSymbol, trace: Trace) -> Option<(&'a EnumPrototype, &'a EnumVariant)> {
+        if symbol.explicit_namespace.chain.is_empty() {
+            return None;
+        }
+
+        let module = self.get_symbol_module(symbol, trace).ok()?;
+        let (name, namespace) = module.path().chain.split_last()?;
+        let enumeration = self.context.global_registry.get_enum(&GlobalIdentifier(
+            Namespace { chain: namespace.to_vec() },
+            name.clone(),
+        ))?;
+
+        Some((enumeration, enumeration.get_variant(&symbol.identifier)?))
+    }
+
+    /// Checks the construction of an enum variant, `arguments` are the values given in
+    /// parentheses, there are none for unit variants
+    fn check_variant(
+        &mut self,
+        enumeration: &EnumPrototype,
+        variant: &EnumVariant,
+        arguments: Option<&[UnvalidatedExpression]>,
+        expected: Option<&DataType>,
+        trace: Trace,
+    ) -> Typed<Expression> {
+        self.check_visible(&enumeration.name, &enumeration.visibility, trace)?;
+
+        let ty = enumeration.data_type();
+        let name = GlobalIdentifier(
+            ty.namespace().expect("Enums have a namespace"),
+            variant.name.clone(),
+        );
+
+        let mut inference = Inference::new(&enumeration.generics);
+        inference.expect(&ty, expected);
+
+        let fields = match (&variant.data, arguments) {
+            (EnumVariantData::Unit, None) => vec![],
+            (EnumVariantData::Positional(types), Some(arguments)) => {
+                if types.len() != arguments.len() {
+                    return Err(CompilerError::ArgumentCount {
+                        function: name,
+                        expected: types.len(),
+                        found: arguments.len(),
+                        trace,
+                    });
+                }
+
+                let mut fields = vec![];
+                for (argument, declared) in arguments.iter().zip(types) {
+                    let (value, ty) = self.check_expression(argument, inference.hint(declared).as_ref())?;
+                    inference.check(declared, &ty, argument.trace())?;
+                    fields.push(value);
+                }
+                fields
+            }
+            _ => return Err(CompilerError::InvalidVariantConstruction {
+                variant: name,
+                expected: variant.syntax(&enumeration.name),
+                trace,
+            }),
+        };
+
+        let type_arguments = inference.finish(&name, trace)?;
+        let construction = Expression::Variant {
+            enumeration: enumeration.name.clone(),
+            type_arguments: type_arguments.clone(),
+            variant: variant.name.clone(),
+            fields,
+        };
+        Ok((construction, DataType::Enumeration(enumeration.name.clone(), type_arguments)))
+    }
+
+    fn check_call(
+        &mut self,
+        symbol: &UnvalidatedSymbol,
+        arguments: &[UnvalidatedExpression],
+        expected: Option<&DataType>,
+        trace: Trace,
+    ) -> Typed<Expression> {
+        let (module, name) = self.get_item_module(symbol, ModuleRegistry::has_function, trace)?;
+        let candidates = module.get_functions_named(&name);
+        let function_name = || module.create_identifier(name.clone());
+
+        let same_arity: Vec<&FunctionPrototype> = candidates
+            .iter()
+            .copied()
+            .filter(|function| function.arguments.len() == arguments.len())
+            .collect();
+
+        match (candidates.as_slice(), same_arity.as_slice()) {
+            ([], _) => return Err(CompilerError::UnknownFunction(function_name(), trace)),
+
+            // without overloading the argument types are known upfront, so literals can adapt
+            (_, [function]) => {
+                let mut inference = Inference::new(&function.generics);
+                inference.expect(&function.return_type(), expected);
+
+                let mut validated = vec![];
+                for (argument, parameter) in arguments.iter().zip(&function.arguments) {
+                    let (expr, ty) = self.check_expression(argument, inference.hint(&parameter.data_type).as_ref())?;
+                    inference.check(&parameter.data_type, &ty, argument.trace())?;
+                    validated.push(expr);
+                }
+
+                return self.finish_call(function, inference, validated, trace);
+            }
+            ([function], []) => {
+                return Err(CompilerError::ArgumentCount {
+                    function: function.name.clone(),
+                    expected: function.arguments.len(),
+                    found: arguments.len(),
+                    trace,
+                });
+            }
+            _ => (),
+        }
+
+        let mut validated = vec![];
+        let mut types = vec![];
+        for argument in arguments {
+            let (expr, ty) = self.check_expression(argument, None)?;
+            validated.push(expr);
+            types.push(ty);
+        }
+
+        let function = module
+            .get_function_by_identifier(&name, &types)
+            .ok_or_else(|| CompilerError::NoMatchingOverload {
+                function: function_name(),
+                arguments: types.clone(),
+                trace,
+            })?;
+
+        let mut inference = Inference::new(&function.generics);
+        for (parameter, ty) in function.arguments.iter().zip(&types) {
+            inference.check(&parameter.data_type, ty, trace)?;
+        }
+        inference.expect(&function.return_type(), expected);
+
+        self.finish_call(function, inference, validated, trace)
+    }
+
+
+    /// Call of a method on a value, methods are looked up in the impl blocks of the type behind
+    /// the references of the object and in the traits it implements
+    fn check_method_call(
+        &mut self,
+        object: &UnvalidatedExpression,
+        method: &Identifier,
+        arguments: &[UnvalidatedExpression],
+        expected: Option<&DataType>,
+        trace: Trace,
+    ) -> Typed<Expression> {
+        let object_trace = object.trace();
+        let (object, object_ty) = self.check_expression(object, None)?;
+        self.call_method(object, object_ty, object_trace, method, arguments, expected, trace)
+    }
+
+    /// Call of a method on an object that was checked already
+    #[allow(clippy::too_many_arguments)]
+    fn call_method(
+        &mut self,
+        object: Expression,
+        object_ty: DataType,
+        object_trace: Trace,
+        method: &Identifier,
+        arguments: &[UnvalidatedExpression],
+        expected: Option<&DataType>,
+        trace: Trace,
+    ) -> Typed<Expression> {
+        // references are followed until a type with a method of that name is found, so methods
+        // of references are found before the ones of the value behind them
+        let mut ty = &object_ty;
+        let mut depth = 0;
+        let candidates = loop {
+            if ty == &DataType::Never {
+                for argument in arguments {
+                    self.check_expression(argument, None)?;
+                }
+                return Ok((object, DataType::Never));
+            }
+
+            let candidates = self.methods(ty, method);
+            match ty {
+                DataType::Pointer(inner) if candidates.is_empty() => {
+                    ty = inner;
+                    depth += 1;
+                }
+                _ => break candidates,
+            }
+        };
+
+        let same_arity: Vec<&FunctionPrototype> = candidates
+            .iter()
+            .copied()
+            .filter(|function| function.arguments.len() == arguments.len() + 1)
+            .collect();
+
+        match (candidates.as_slice(), same_arity.as_slice()) {
+            ([], _) => return Err(CompilerError::UnknownMethod { ty: ty.clone(), method: method.clone(), trace }),
+
+            (_, [function]) => {
+                let receiver = &function.arguments[0].data_type;
+                let (object, object_ty) = adapt_receiver(object, object_ty, depth, function);
+
+                let mut inference = Inference::new(&function.generics);
+                inference.check(receiver, &object_ty, object_trace)?;
+                inference.expect(&function.return_type(), expected);
+
+                let mut validated = vec![object];
+                for (argument, parameter) in arguments.iter().zip(&function.arguments[1..]) {
+                    let (expr, ty) = self.check_expression(argument, inference.hint(&parameter.data_type).as_ref())?;
+                    inference.check(&parameter.data_type, &ty, argument.trace())?;
+                    validated.push(expr);
+                }
+
+                return self.finish_call(function, inference, validated, trace);
+            }
+            ([function], []) => {
+                return Err(CompilerError::ArgumentCount {
+                    function: function.name.clone(),
+                    expected: function.arguments.len() - 1,
+                    found: arguments.len(),
+                    trace,
+                });
+            }
+            _ => (),
+        }
+
+        let mut validated = vec![];
+        let mut types = vec![];
+        for argument in arguments {
+            let (expr, ty) = self.check_expression(argument, None)?;
+            validated.push(expr);
+            types.push(ty);
+        }
+
+        // overloads are told apart by their arguments, the receiver is the same for all of them
+        let function = same_arity
+            .iter()
+            .copied()
+            .find(|function| {
+                let mut bindings = HashMap::new();
+                let (_, object_ty) = adapt_receiver(object.clone(), object_ty.clone(), depth, function);
+
+                std::iter::once(&object_ty)
+                    .chain(&types)
+                    .zip(&function.arguments)
+                    .all(|(ty, parameter)| parameter.data_type.bind(ty, &function.generics, &mut bindings))
+            })
+            .ok_or_else(|| CompilerError::NoMatchingOverload {
+                function: candidates[0].name.clone(),
+                arguments: types.clone(),
+                trace,
+            })?;
+
+        let (object, object_ty) = adapt_receiver(object, object_ty, depth, function);
+        let mut inference = Inference::new(&function.generics);
+        inference.check(&function.arguments[0].data_type, &object_ty, object_trace)?;
+        for (parameter, ty) in function.arguments[1..].iter().zip(&types) {
+            inference.check(&parameter.data_type, ty, trace)?;
+        }
+        inference.expect(&function.return_type(), expected);
+
+        validated.insert(0, object);
+        self.finish_call(function, inference, validated, trace)
+    }
+
+    /// Functions with a receiver of the given name that can be called as methods of the type,
+    /// without following its references
+    fn methods(&self, ty: &DataType, method: &Identifier) -> Vec<&'a FunctionPrototype> {
+        // inside a trait `Self` only has the methods of the trait itself
+        let namespace = match ty {
+            DataType::Parameter(name) if name.as_str() == SELF_TYPE => self.scope.namespace.clone(),
+            ty => ty.namespace(),
+        };
+
+        let registry = self.context.global_registry;
+        let mut candidates = namespace
+            .and_then(|namespace| registry.get_module(&namespace))
+            .map(|module| module.get_functions_named(method))
+            .unwrap_or_default();
+
+        // methods an implemented trait provides, the implemented ones are found in the impl blocks
+        for implementation in registry.impls().iter().filter(|implementation| implementation.is_for(ty)) {
+            let provided = registry
+                .get_trait(&implementation.trait_name)
+                .and_then(|declaration| declaration.get_method(method))
+                .filter(|declared| declared.provided && implementation.get_implementation(&declared.prototype).is_none());
+
+            if let Some(declared) = provided {
+                candidates.push(&declared.prototype);
+            }
+        }
+        candidates.retain(|function| function.arguments.first().is_some_and(|argument| argument.name.as_str() == RECEIVER));
+        candidates
+    }
+
+    /// Call of a function whose arguments were checked, the type parameters have to be inferred.
+    /// Methods of traits are only called on types implementing the trait, which implementation
+    /// runs is decided once the function is instantiated
+    fn finish_call(
+        &mut self,
+        function: &FunctionPrototype,
+        mut inference: Inference,
+        arguments: Vec<Expression>,
+        trace: Trace,
+    ) -> Typed<Expression> {
+        self.check_visible(&function.name, &function.visibility, trace)?;
+
+        let registry = self.context.global_registry;
+        let declaration = registry.trait_of(&function.name);
+
+        // type arguments of the trait follow from its implementation once `Self` is known
+        if let Some(declaration) = declaration {
+            let implemented = inference.bindings
+                .get(&Identifier::from(SELF_TYPE))
+                .and_then(|ty| registry.implemented_arguments(&declaration.name, ty));
+
+            for (parameter, argument) in declaration.generics.iter().zip(implemented.into_iter().flatten()) {
+                inference.bindings.entry(parameter.clone()).or_insert(argument);
+            }
+        }
+
+        let type_arguments = inference.finish(&function.name, trace)?;
+        let returns = inference.substitute(&self.return_type(function));
+
+        if let Some(declaration) = declaration {
+            let self_type = &type_arguments[0];
+            let trait_arguments = &type_arguments[1..=declaration.generics.len()];
+
+            // provided methods can call the other methods of their trait on `Self`
+            let trait_namespace = declaration.name.0.module(declaration.name.1.clone());
+            let inside_trait = self_type == &DataType::Parameter(SELF_TYPE.into())
+                && self.scope.namespace.as_ref() == Some(&trait_namespace);
+            let implemented = inside_trait
+                || self_type == &DataType::Never
+                || registry.find_impl(&declaration.name, self_type, trait_arguments).is_some();
+
+            if !implemented {
+                return Err(CompilerError::TraitNotImplemented {
+                    trait_name: declaration.name.clone(),
+                    ty: self_type.clone(),
+                    trace,
+                });
+            }
+        }
+
+        let call = Expression::FunctionCall {
+            function: function.name.clone(),
+            signature: function.arguments.iter().map(|argument| argument.data_type.clone()).collect(),
+            type_arguments,
+            arguments,
+        };
+        Ok((call, returns))
+    }
+}
+
+/// Type parameters of a generic item that are inferred from the values given to it
+struct Inference<'p> {
+    parameters: &'p [Identifier],
+    bindings: HashMap<Identifier, DataType>,
+}
+
+impl<'p> Inference<'p> {
+    fn new(parameters: &'p [Identifier]) -> Self {
+        Self { parameters, bindings: HashMap::new() }
+    }
+
+    /// Starts out with type arguments that were written out, eg. `make Pair<i32, bool> { .. }`
+    fn given(parameters: &'p [Identifier], arguments: &[DataType]) -> Self {
+        Self {
+            parameters,
+            bindings: parameters.iter().cloned().zip(arguments.iter().cloned()).collect(),
+        }
+    }
+
+    /// Infers parameters from the type the item is expected to produce. An expectation that
+    /// conflicts binds nothing, the mismatch is reported where the result is used instead
+    fn expect(&mut self, result: &DataType, expected: Option<&DataType>) {
+        let Some(expected) = expected else { return };
+
+        let mut bindings = self.bindings.clone();
+        if result.bind(expected, self.parameters, &mut bindings) {
+            self.bindings = bindings;
+        }
+    }
+
+    /// Type a value given where `declared` is expected must have, [None] while it depends on
+    /// parameters that are not inferred yet
+    fn hint(&self, declared: &DataType) -> Option<DataType> {
+        let unknown = self.parameters
+            .iter()
+            .any(|parameter| !self.bindings.contains_key(parameter) && declared.mentions(parameter));
+
+        (!unknown).then(|| declared.substitute(&self.bindings))
+    }
+
+    /// Infers parameters from a value of type `found` given where `declared` is expected
+    fn check(&mut self, declared: &DataType, found: &DataType, trace: Trace) -> CompilerResult<()> {
+        if declared.bind(found, self.parameters, &mut self.bindings) {
+            Ok(())
+        } else {
+            Err(CompilerError::TypeMismatch {
+                expected: self.substitute(declared),
+                found: found.clone(),
+                trace,
+            })
+        }
+    }
+
+    fn substitute(&self, ty: &DataType) -> DataType {
+        ty.substitute(&self.bindings)
+    }
+
+    /// Type arguments in the order of the parameters, every parameter has to be inferred by now
+    fn finish(&self, item: &GlobalIdentifier, trace: Trace) -> CompilerResult<Vec<DataType>> {
+        self.parameters
+            .iter()
+            .map(|parameter| {
+                self.bindings.get(parameter).cloned().ok_or_else(|| CompilerError::UninferredTypeParameter {
+                    item: item.clone(),
+                    parameter: parameter.clone(),
+                    trace,
+                })
+            })
+            .collect()
+    }
+}
+
+/// Variable bound by a pattern
+#[derive(Clone)]
+struct PatternBinding {
+    name: Identifier,
+    variable: Uuid,
+    data_type: DataType,
+}
+
+/// Literal `1` of an integer type
+fn one(primitive: PrimitiveType) -> Expression {
+    match primitive {
+        PrimitiveType::U32 => Expression::U32Literal(1),
+        PrimitiveType::U64 => Expression::U64Literal(1),
+        PrimitiveType::Usize => Expression::UsizeLiteral(1),
+        PrimitiveType::I64 => Expression::I64Literal(1),
+        _ => Expression::I32Literal(1),
+    }
+}
+
+/// Strips every reference off a value, the value behind them is accessed
+fn dereference(mut expr: Expression, mut ty: DataType) -> (Expression, DataType) {
+    while let DataType::Pointer(inner) = ty {
+        expr = Expression::Unary { op: UnaryOperator::Deref, operand: Box::new(expr) };
+        ty = *inner;
+    }
+    (expr, ty)
+}
+
+/// Adds or strips references so that the object of a method call fits the receiver of the
+/// method, which allows `ref[self]` methods to be called on values and `self` methods on
+/// references. The method was found after following `depth` references of the object
+fn adapt_receiver(
+    mut object: Expression,
+    mut ty: DataType,
+    depth: usize,
+    method: &FunctionPrototype,
+) -> (Expression, DataType) {
+    let receiver = &method.arguments[0].data_type;
+    let mut found = &ty;
+    for _ in 0..depth {
+        let DataType::Pointer(inner) = found else { unreachable!("Followed references of the object") };
+        found = inner;
+    }
+
+    let binds = |expected: &DataType| expected.bind(found, &method.generics, &mut HashMap::new());
+    let (dereferences, reference) = match receiver {
+        receiver if binds(receiver) => (depth, false),
+        DataType::Pointer(receiver) if binds(receiver) => (depth.saturating_sub(1), depth == 0),
+        receiver => {
+            // the receiver does not fit, the object gets as many references as it has
+            let references = |mut ty: &DataType| {
+                let mut count: usize = 0;
+                while let DataType::Pointer(inner) = ty {
+                    ty = inner;
+                    count += 1;
+                }
+                count
+            };
+            let (found, expected) = (references(&ty), references(receiver));
+            (found.saturating_sub(expected), found + 1 == expected)
+        }
+    };
+
+    for _ in 0..dereferences {
+        let DataType::Pointer(inner) = ty else { unreachable!() };
+        object = Expression::Unary { op: UnaryOperator::Deref, operand: Box::new(object) };
+        ty = *inner;
+    }
+
+    if reference {
+        object = Expression::Unary { op: UnaryOperator::Ref, operand: Box::new(object) };
+        ty = DataType::Pointer(Box::new(ty));
+    }
+    (object, ty)
+}
+
+fn invalid_variant_pattern(enumeration: &EnumPrototype, variant: &EnumVariant, trace: Trace) -> CompilerError {
+    CompilerError::InvalidVariantPattern {
+        variant: GlobalIdentifier(
+            enumeration.data_type().namespace().expect("Enums have a namespace"),
+            variant.name.clone(),
+        ),
+        expected: variant.syntax(&enumeration.name),
+        trace,
+    }
+}
+
+/// Trace of the expression a block evaluates to, the block itself if it has none
+fn tail_trace(expr: &UnvalidatedExpression) -> Trace {
+    match expr {
+        UnvalidatedExpression::Scope(statements, trace) => match statements.last() {
+            Some(UnvalidatedFunctionExpression::Expression(tail)) => tail_trace(tail),
+            _ => *trace,
+        },
+        _ => expr.trace(),
+    }
+}
+
+/// Trace of the value a branch of an `if`-`else` evaluates to
+fn branch_trace(branch: &UnvalidatedFunctionExpression) -> Trace {
+    match branch {
+        UnvalidatedFunctionExpression::Expression(expr) => tail_trace(expr),
+        UnvalidatedFunctionExpression::If { trace, .. } => *trace,
+        _ => unreachable!("Branches are blocks or if statements"),
+    }
+}
+
+/// Numeric type an unsuffixed integer literal takes, the expected one or `i32`
+fn literal_type(expected: Option<&DataType>) -> PrimitiveType {
+    expected.and_then(DataType::primitive).filter(|p| p.is_numeric()).unwrap_or(PrimitiveType::I32)
+}
+
+/// Unsuffixed integer literals take the numeric type that is expected of them, so `0` can be
+/// given where a float is expected. Integer types are range checked
+fn coerce_integer(value: i128, expected: Option<&DataType>, trace: Trace) -> Typed<Expression> {
+    let primitive = literal_type(expected);
+    let out_of_range = |_| CompilerError::LiteralOutOfRange { value, ty: primitive, trace };
+
+    let expr = match primitive {
+        PrimitiveType::I64 => Expression::I64Literal(value.try_into().map_err(out_of_range)?),
+        PrimitiveType::U32 => Expression::U32Literal(value.try_into().map_err(out_of_range)?),
+        PrimitiveType::U64 => Expression::U64Literal(value.try_into().map_err(out_of_range)?),
+        PrimitiveType::Usize => Expression::UsizeLiteral(value.try_into().map_err(out_of_range)?),
+        PrimitiveType::F32 => Expression::F32Literal(value as f32),
+        PrimitiveType::F64 => Expression::F64Literal(value as f64),
+        _ => Expression::I32Literal(value.try_into().map_err(out_of_range)?),
+    };
+
+    Ok((expr, DataType::Primitive(primitive)))
+}
+
+/// Unsuffixed float literals become doubles when a double is expected
+fn coerce_float(value: f32, expected: Option<&DataType>) -> (Expression, DataType) {
+    if expected == Some(&DataType::Primitive(PrimitiveType::F64)) {
+        // going through the shortest representation keeps `0.1` from becoming `0.10000000149`
+        let value = value.to_string().parse().expect("Formatted float can be parsed");
+        (Expression::F64Literal(value), DataType::Primitive(PrimitiveType::F64))
+    } else {
+        (Expression::F32Literal(value), DataType::Primitive(PrimitiveType::F32))
+    }
+}
+
+fn is_literal(expr: &UnvalidatedExpression) -> bool {
+    match expr {
+        UnvalidatedExpression::IntegerLiteral(..) | UnvalidatedExpression::F32Literal(..) => true,
+        UnvalidatedExpression::Unary { expr, op: UnaryOperator::Negate, .. } => is_literal(expr),
+        UnvalidatedExpression::Parenthetical(expr, _) => is_literal(expr),
+        _ => false,
+    }
+}
+
+fn is_comparison(op: BinaryOperation) -> bool {
+    use BinaryOperation as B;
+    matches!(op, B::Less | B::Greater | B::LesserOrEquals | B::GreaterOrEquals | B::Equals | B::NotEqual)
+}
+
+/// Type produced by a binary operation, [None] if the operation is invalid for the operands
+fn binary_result(op: BinaryOperation, lhs: &DataType, rhs: &DataType) -> Option<DataType> {
+    use BinaryOperation as B;
+
+    if lhs == &DataType::Never || rhs == &DataType::Never {
+        return Some(if is_comparison(op) { DataType::Primitive(PrimitiveType::Bool) } else { DataType::Never });
+    }
+
+    if lhs != rhs {
+        return None;
+    }
+
+    let primitive = lhs.primitive();
+    let valid = match op {
+        B::Plus | B::Minus | B::Multiply | B::Divide | B::Mod => primitive.is_some_and(PrimitiveType::is_numeric),
+        B::BitAnd | B::BitOr | B::Xor => primitive.is_some_and(|p| p.is_integer() || p == PrimitiveType::Bool),
+        B::BitShiftLeft | B::BitShiftRight => primitive.is_some_and(PrimitiveType::is_integer),
+        B::Less | B::Greater | B::LesserOrEquals | B::GreaterOrEquals => {
+            primitive.is_some_and(|p| p.is_numeric() || p == PrimitiveType::Char)
+        }
+        B::Equals | B::NotEqual => primitive.is_some() || matches!(lhs, DataType::Pointer(_)),
+        B::And | B::Or => primitive == Some(PrimitiveType::Bool),
+        _ => false,
+    };
+
+    if !valid {
+        return None;
+    }
+
+    Some(if is_comparison(op) { DataType::Primitive(PrimitiveType::Bool) } else { lhs.clone() })
+}
+
+fn is_valid_cast(from: &DataType, to: &DataType) -> bool {
+    match (from, to) {
+        (DataType::Never, _) => true,
+        _ if from == to => true,
+        (DataType::Primitive(from), DataType::Primitive(to)) => match (from, to) {
+            (from, to) if from.is_numeric() && to.is_numeric() => true,
+            (PrimitiveType::Char | PrimitiveType::Bool, to) => to.is_integer(),
+            (PrimitiveType::U32, PrimitiveType::Char) => true,
+            _ => false,
+        },
+        _ => false,
+    }
+}
+
+/// Values that can be embedded into a format string
+fn is_formattable(ty: &DataType) -> bool {
+    matches!(ty, DataType::Primitive(_) | DataType::Never) || ty == &DataType::string()
+}
@@ -1,7 +1,13 @@
+// errors carry the types they report on, they are only built on the failure path so their size
+// does not matter
+#![allow(clippy::result_large_err)]
+
 pub mod module;
 pub mod data_type;
 pub mod error;
 pub mod registry;
 pub mod function_context;
+pub mod function_validator;
+pub mod monomorphize;
 #[cfg(test)]
 pub mod tests;
@@ -1,26 +1,1157 @@
-use std::rc::Rc;
+use std::collections::{HashMap, HashSet};
+use serde::Serialize;
+use crate::file::identifier::{GlobalIdentifier, Identifier, Namespace};
 use crate::file::source_file::SourceFile;
-use crate::parser::ast::top_level::UnvalidatedTopLevel;
-use crate::validation::error::CompilerResult;
-use crate::validation::registry::ModuleRegistry;
+use crate::file::source_map::{FileId, SourceMap};
+use crate::file::trace::Trace;
+use crate::ir::visibility::Visibility;
+use crate::parser::ast::data::{UnvalidatedType, UnvalidatedVariableInfo};
+use crate::parser::ast::expression::UnvalidatedExpression;
+use crate::parser::expansion::{ARRAY, BUILTIN_MODULE, CRASH, PRINT};
+use crate::parser::ast::function::{UnvalidatedFunction, UnvalidatedFunctionExpression, UnvalidatedFunctionPrototype, SELF_TYPE};
+use crate::parser::ast::r#struct::{UnvalidatedEnumData, UnvalidatedEnumPrototype, UnvalidatedStructProperty, UnvalidatedStructPrototype};
+use crate::parser::ast::r#trait::UnvalidatedTraitPrototype;
+use crate::parser::ast::top_level::{UnvalidatedImport, UnvalidatedTopLevel};
+use crate::parser::ast::UnvalidatedSymbol;
+use crate::validation::data_type::{DataType, PrimitiveType};
+use crate::validation::error::{CompilerError, CompilerResult};
+use crate::validation::function_validator::{FunctionValidator, References};
+use crate::validation::registry::functions::{FunctionPrototype, ValidatedFunction};
+use crate::validation::registry::globals::{GlobalVariable, ValidatedGlobal};
+use crate::validation::registry::variable::VariableInfo;
+use crate::validation::registry::enums::{EnumPrototype, EnumVariant, EnumVariantData};
+use crate::validation::registry::structs::{StructField, StructPrototype};
+use crate::validation::registry::traits::{ImplementedMethod, TraitImpl, TraitMethod, TraitPrototype};
+use crate::validation::registry::{ModuleRegistry, TypeScope};
 
-#[derive(Debug)]
+#[derive(Debug, Serialize)]
 pub struct Module {
     global_registry: ModuleRegistry,
+    functions: Vec<ValidatedFunction>,
+
+    /// Globals in the order their initializers have to run in
+    globals: Vec<ValidatedGlobal>,
+}
+
+/// Function that was declared but whose body still has to be validated
+struct PendingFunction<'a> {
+    /// Module the function is written in, for functions of impl blocks this is not the
+    /// namespace they are registered in
+    namespace: Namespace,
+
+    /// `Self` and the type parameters the body can refer to
+    scope: TypeScope,
+    prototype: FunctionPrototype,
+    body: &'a UnvalidatedFunctionExpression,
+}
+
+/// Global that was declared but whose initializer still has to be validated
+struct PendingGlobal<'a> {
+    /// Module the global is declared in
+    namespace: Namespace,
+    name: GlobalIdentifier,
+    initial: &'a UnvalidatedExpression,
 }
 
 impl Module {
     pub fn new() -> Self {
+        let mut global_registry = ModuleRegistry::create_global();
+        register_builtins(&mut global_registry);
+
         Self {
-            global_registry: ModuleRegistry::create_global()
+            global_registry,
+            functions: vec![],
+            globals: vec![],
         }
     }
 
-    pub fn process_modules(&mut self, modules: &[(Rc<SourceFile>, Vec<UnvalidatedTopLevel>)]) -> CompilerResult<()> {
+    pub fn functions(&self) -> &[ValidatedFunction] {
+        &self.functions
+    }
+
+    pub fn globals(&self) -> &[ValidatedGlobal] {
+        &self.globals
+    }
+
+    pub fn registry(&self) -> &ModuleRegistry {
+        &self.global_registry
+    }
+
+    /// Namespace of the module a source file declares
+    fn file_namespace(&self, file: &SourceFile) -> Namespace {
+        file.module().prefix(self.global_registry.path().clone())
+    }
+
+    /// Validates the given modules, first every declaration is registered so that items can be
+    /// used before they are declared, then every function body is type checked
+    pub fn process_modules(
+        &mut self,
+        sources: &SourceMap,
+        modules: &[(FileId, Vec<UnvalidatedTopLevel>)],
+    ) -> Result<(), Vec<CompilerError>> {
+        let mut errors = vec![];
+
+        // a directory can have a file of the same name declaring its module, which has to be
+        // registered before the files inside of the directory reuse it
+        let mut namespaces: Vec<Namespace> = modules
+            .iter()
+            .map(|(file, _)| self.file_namespace(&sources[*file]))
+            .collect();
+        namespaces.sort_by_key(|namespace| namespace.chain.len());
+
+        for namespace in &namespaces {
+            if let Err(err) = self.global_registry.register_file_module(namespace) {
+                errors.push(err);
+            }
+        }
+
+        // declarations of duplicate modules would conflict with each other
+        if !errors.is_empty() {
+            return Err(errors);
+        }
+
+        // types are declared first so that every signature can use them
+        let mut structs = vec![];
+        let mut enums = vec![];
+        let mut traits = vec![];
         for (file, statements) in modules {
-            self.global_registry.register_sub_module(file.module_name.clone())?;
+            let namespace = self.file_namespace(&sources[*file]);
+
+            for statement in statements {
+                match statement {
+                    UnvalidatedTopLevel::StructDefinition { proto, trace } => {
+                        match self.declare_struct(&namespace, proto, *trace) {
+                            Ok(()) => structs.push((namespace.clone(), proto, *trace)),
+                            Err(err) => errors.push(err),
+                        }
+                    }
+                    UnvalidatedTopLevel::EnumDefinition { proto, trace } => {
+                        match self.declare_enum(&namespace, proto, *trace) {
+                            Ok(()) => enums.push((namespace.clone(), proto, *trace)),
+                            Err(err) => errors.push(err),
+                        }
+                    }
+                    UnvalidatedTopLevel::TraitDefinition { proto, trace } => {
+                        match self.declare_trait(&namespace, proto, *trace) {
+                            Ok(()) => traits.push((namespace.clone(), proto)),
+                            Err(err) => errors.push(err),
+                        }
+                    }
+                    _ => {}
+                }
+            }
+        }
+
+        // imports refer to the declared items, they are usable by every signature. Glob imports
+        // are expanded after every explicit import is known as those shadow them
+        let mut globs = vec![];
+        let mut value_imports = vec![];
+        for (file, statements) in modules {
+            let namespace = self.file_namespace(&sources[*file]);
+
+            for statement in statements {
+                let UnvalidatedTopLevel::Import { imports, .. } = statement else {
+                    continue;
+                };
+
+                for import in imports {
+                    let declared = if import.star {
+                        self.resolve_import(&namespace, &import.path, import.trace)
+                            .map(|target| globs.push((namespace.clone(), target)))
+                    } else {
+                        self.declare_import(&namespace, import)
+                            .map(|value| value_imports.extend(value))
+                    };
+
+                    if let Err(err) = declared {
+                        errors.push(err);
+                    }
+                }
+            }
+        }
+        self.expand_glob_imports(&globs);
+
+        // fields can only be resolved once every type is known
+        for (namespace, proto, trace) in structs {
+            if let Err(err) = self.define_struct(&namespace, proto, trace) {
+                errors.push(err);
+            }
+        }
+        for (namespace, proto, trace) in enums {
+            if let Err(err) = self.define_enum(&namespace, proto, trace) {
+                errors.push(err);
+            }
+        }
+
+        // methods of traits are declared before any impl block is checked against them
+        let mut pending = vec![];
+        for (namespace, proto) in traits {
+            match self.define_trait(&namespace, proto) {
+                Ok(provided) => pending.extend(provided),
+                Err(err) => errors.push(err),
+            }
+        }
+
+        // globals can be referred to by every function, their types are known once every type is
+        let mut globals = vec![];
+        for (file, statements) in modules {
+            let namespace = self.file_namespace(&sources[*file]);
+
+            for statement in statements {
+                if let UnvalidatedTopLevel::GlobalVariable { variable, initial, constant, visibility, trace } = statement {
+                    match self.declare_global(&namespace, variable, *constant, visibility, *trace) {
+                        Ok(name) => globals.push(PendingGlobal { namespace: namespace.clone(), name, initial }),
+                        Err(err) => errors.push(err),
+                    }
+                }
+            }
+        }
+
+        for (file, statements) in modules {
+            let namespace = self.file_namespace(&sources[*file]);
+
+            for statement in statements {
+                match statement {
+                    UnvalidatedTopLevel::FunctionDefinition { proto, body, trace } => {
+                        match self.declare_function(&namespace, &TypeScope::default(), proto, proto.visibility.clone(), *trace) {
+                            Ok((prototype, scope)) => pending.push(PendingFunction {
+                                namespace: namespace.clone(),
+                                scope,
+                                prototype,
+                                body,
+                            }),
+                            Err(err) => errors.push(err),
+                        }
+                    }
+                    UnvalidatedTopLevel::ImplBlock { generics, target, implements, functions, trace } => {
+                        let impl_scope = match self.declare_impl(&namespace, generics, target, implements.as_ref(), *trace) {
+                            Ok(scope) => scope,
+                            Err(err) => {
+                                errors.push(err);
+                                continue;
+                            }
+                        };
+
+                        // an impl block that may not implement the trait does not declare its functions
+                        let implemented = match implements {
+                            Some(implements) => match self.resolve_implemented_trait(&namespace, &impl_scope, implements, *trace) {
+                                Ok(implemented) => Some(implemented),
+                                Err(err) => {
+                                    errors.push(err);
+                                    continue;
+                                }
+                            },
+                            None => None,
+                        };
+
+                        // implemented methods can be used wherever the trait can
+                        let visibility = |function: &UnvalidatedFunction| match implements {
+                            Some(_) => Visibility::Public,
+                            None => function.proto.visibility.clone(),
+                        };
+
+                        let mut declared = vec![];
+                        for function in functions {
+                            if implements.is_some() {
+                                let target = impl_scope.namespace.as_ref().unwrap_or(&namespace);
+                                if let Err(err) = check_written_return(target, &function.proto, function.trace) {
+                                    errors.push(err);
+                                    continue;
+                                }
+                            }
+
+                            match self.declare_function(&namespace, &impl_scope, &function.proto, visibility(function), function.trace) {
+                                Ok((prototype, scope)) => {
+                                    declared.push(prototype.clone());
+                                    pending.push(PendingFunction {
+                                        namespace: namespace.clone(),
+                                        scope,
+                                        prototype,
+                                        body: &function.body,
+                                    });
+                                }
+                                Err(err) => errors.push(err),
+                            }
+                        }
+
+                        // methods that failed to declare would be reported as missing
+                        if let (Some(implementation), true) = (implemented, declared.len() == functions.len()) {
+                            if let Err(err) = self.implement_trait(implementation, declared) {
+                                errors.push(err);
+                            }
+                        }
+                    }
+                    _ => {}
+                }
+            }
+        }
+
+        // functions and globals are declared now, glob imports bring them into scope as well
+        self.expand_glob_imports(&globs);
+        for (namespace, target, trace) in value_imports {
+            if let Err(err) = self.check_value_import(&namespace, target, trace) {
+                errors.push(err);
+            }
+        }
+
+        // function bodies need the types of globals and functions that are inferred from their
+        // initializers and bodies
+        let (inferred, pending): (Vec<_>, Vec<_>) = pending
+            .into_iter()
+            .partition(|function| function.prototype.returns.is_none());
+        errors.extend(self.validate_inferred(globals, inferred));
+
+        for function in &pending {
+            let module = self.global_registry
+                .get_module(&function.namespace)
+                .expect("Module was registered");
+
+            let (validated, _) = FunctionValidator::validate(
+                &self.global_registry,
+                module,
+                &function.scope,
+                &function.prototype,
+                function.body,
+            );
+
+            match validated {
+                Ok(validated) => self.functions.push(validated),
+                Err(err) => errors.extend(err),
+            }
+        }
+
+        if errors.is_empty() { Ok(()) } else { Err(errors) }
+    }
+
+    fn declare_struct(
+        &mut self,
+        namespace: &Namespace,
+        proto: &UnvalidatedStructPrototype,
+        trace: Trace,
+    ) -> CompilerResult<()> {
+        check_generics(&proto.generics, trace)?;
+
+        let module = self.global_registry
+            .get_module_mut(namespace)
+            .expect("Module was registered");
+
+        let name = module.create_identifier(proto.identifier.clone());
+        let ty = DataType::Structure(name.clone(), parameters(&proto.generics));
+        module.register_type(proto.identifier.clone(), ty, trace)?;
+
+        module.register_struct(StructPrototype {
+            name,
+            generics: proto.generics.clone(),
+            fields: vec![],
+            visibility: proto.visibility.clone(),
+            trace,
+        });
+        Ok(())
+    }
+
+    /// Resolves the fields of a declared structure
+    fn define_struct(
+        &mut self,
+        namespace: &Namespace,
+        proto: &UnvalidatedStructPrototype,
+        trace: Trace,
+    ) -> CompilerResult<()> {
+        let module = self.global_registry
+            .get_module_mut(namespace)
+            .expect("Module was registered");
+
+        let scope = TypeScope { parameters: proto.generics.clone(), ..Default::default() };
+        let mut fields: Vec<StructField> = vec![];
+
+        for UnvalidatedStructProperty { property, visibility } in &proto.properties {
+            if fields.iter().any(|field| field.name == property.name) {
+                return Err(CompilerError::DuplicateField(property.name.clone(), trace));
+            }
+
+            fields.push(StructField {
+                name: property.name.clone(),
+                data_type: module.resolve_type(&property.ty, &scope)?,
+                visibility: visibility.clone(),
+            });
+        }
+
+        let name = module.create_identifier(proto.identifier.clone());
+        self.global_registry
+            .get_struct_mut(&name)
+            .expect("Structure was declared")
+            .fields = fields;
+        Ok(())
+    }
+
+    fn declare_enum(
+        &mut self,
+        namespace: &Namespace,
+        proto: &UnvalidatedEnumPrototype,
+        trace: Trace,
+    ) -> CompilerResult<()> {
+        check_generics(&proto.generics, trace)?;
+
+        let module = self.global_registry
+            .get_module_mut(namespace)
+            .expect("Module was registered");
+
+        let name = module.create_identifier(proto.identifier.clone());
+        let ty = DataType::Enumeration(name.clone(), parameters(&proto.generics));
+        module.register_type(proto.identifier.clone(), ty, trace)?;
+
+        module.register_enum(EnumPrototype {
+            name,
+            generics: proto.generics.clone(),
+            variants: vec![],
+            visibility: proto.visibility.clone(),
+            trace,
+        });
+        Ok(())
+    }
+
+    /// Resolves the data of every variant of a declared enum
+    fn define_enum(
+        &mut self,
+        namespace: &Namespace,
+        proto: &UnvalidatedEnumPrototype,
+        trace: Trace,
+    ) -> CompilerResult<()> {
+        let module = self.global_registry
+            .get_module_mut(namespace)
+            .expect("Module was registered");
+
+        let scope = TypeScope { parameters: proto.generics.clone(), ..Default::default() };
+        let resolve = |ty| module.resolve_type(ty, &scope);
+        let mut variants: Vec<EnumVariant> = vec![];
+
+        for variant in &proto.variants {
+            if variants.iter().any(|declared| declared.name == variant.name) {
+                return Err(CompilerError::DuplicateVariant(variant.name.clone(), trace));
+            }
+
+            let data = match &variant.data {
+                None => EnumVariantData::Unit,
+                Some(UnvalidatedEnumData::Positional(fields)) => EnumVariantData::Positional(
+                    fields.iter().map(resolve).collect::<CompilerResult<_>>()?,
+                ),
+                Some(UnvalidatedEnumData::StructLike(properties)) => {
+                    let mut fields: Vec<(Identifier, DataType)> = vec![];
+
+                    for property in properties {
+                        if fields.iter().any(|(name, _)| name == &property.name) {
+                            return Err(CompilerError::DuplicateField(property.name.clone(), trace));
+                        }
+                        fields.push((property.name.clone(), resolve(&property.ty)?));
+                    }
+                    EnumVariantData::StructLike(fields)
+                }
+            };
+
+            variants.push(EnumVariant { name: variant.name.clone(), data });
+        }
+
+        let name = module.create_identifier(proto.identifier.clone());
+        self.global_registry
+            .get_enum_mut(&name)
+            .expect("Enum was declared")
+            .variants = variants;
+        Ok(())
+    }
+
+    fn declare_trait(
+        &mut self,
+        namespace: &Namespace,
+        proto: &UnvalidatedTraitPrototype,
+        trace: Trace,
+    ) -> CompilerResult<()> {
+        check_generics(&proto.generics, trace)?;
+
+        let module = self.global_registry
+            .get_module_mut(namespace)
+            .expect("Module was registered");
+
+        module.register_trait(TraitPrototype {
+            name: module.create_identifier(proto.identifier.clone()),
+            generics: proto.generics.clone(),
+            methods: vec![],
+            visibility: proto.visibility.clone(),
+            trace,
+        })
+    }
+
+    /// Registers the methods of a declared trait in its namespace, `Self` is a type parameter of
+    /// every method. Returns the provided methods whose bodies have to be validated
+    fn define_trait<'a>(
+        &mut self,
+        namespace: &Namespace,
+        proto: &'a UnvalidatedTraitPrototype,
+    ) -> CompilerResult<Vec<PendingFunction<'a>>> {
+        let name = GlobalIdentifier(namespace.clone(), proto.identifier.clone());
+        let trait_namespace = namespace.module(proto.identifier.clone());
+        let scope = TypeScope {
+            self_type: None,
+            parameters: std::iter::once(SELF_TYPE.into()).chain(proto.generics.iter().cloned()).collect(),
+            namespace: Some(trait_namespace.clone()),
+        };
+
+        let mut methods: Vec<TraitMethod> = vec![];
+        let mut provided = vec![];
+
+        for method in &proto.methods {
+            // implementations are matched to the methods by name, so they can not be overloaded
+            if methods.iter().any(|declared| declared.prototype.name.1 == method.proto.name) {
+                let method_name = GlobalIdentifier(trait_namespace.clone(), method.proto.name.clone());
+                return Err(CompilerError::DuplicateFunction(method_name, method.trace));
+            }
+            check_written_return(&trait_namespace, &method.proto, method.trace)?;
+
+            let (prototype, scope) = self.declare_function(namespace, &scope, &method.proto, Visibility::Public, method.trace)?;
+
+            if let Some(body) = &method.body {
+                provided.push(PendingFunction {
+                    namespace: namespace.clone(),
+                    scope,
+                    prototype: prototype.clone(),
+                    body,
+                });
+            }
+            methods.push(TraitMethod { prototype, provided: method.body.is_some() });
+        }
+
+        self.global_registry
+            .get_trait_mut(&name)
+            .expect("Trait was declared")
+            .methods = methods;
+        Ok(provided)
+    }
+
+    /// Brings the item an import names into scope, paths are relative to the importing module
+    /// first. Functions and globals are declared after imports, imports of them are returned to
+    /// be checked with [Self::check_value_import] once they are
+    fn declare_import(&mut self, namespace: &Namespace, import: &UnvalidatedImport) -> CompilerResult<Option<(Namespace, Namespace, Trace)>> {
+        let path = import.path.explicit_namespace.module(import.path.identifier.clone());
+        let absolute = path.prefix(self.global_registry.path().clone());
+        let candidates = [path.prefix(namespace.clone()), absolute.clone()];
+
+        // items that are not modules are looked up in their parent module
+        let parent = |target: &Namespace| Namespace { chain: target.chain[..target.chain.len() - 1].to_vec() };
+        let target = candidates
+            .iter()
+            .find(|target| self.global_registry.get_module(target).is_some())
+            .or_else(|| candidates.iter().find(|target| self.global_registry.get_module(&parent(target)).is_some()))
+            .cloned()
+            .ok_or(CompilerError::UnknownNamespace(absolute, import.trace))?;
+
+        let name = target.chain.last().expect("Imports name an item").clone();
+        if !self.global_registry.is_importable(&target, namespace) {
+            return Err(private_item(target, import.trace));
+        }
+
+        let ty = self.global_registry
+            .get_module(&parent(&target))
+            .and_then(|module| module.get_type(&name))
+            .cloned();
+        let is_module = self.global_registry.get_module(&target).is_some();
+
+        self.global_registry
+            .get_module_mut(namespace)
+            .expect("Module was registered")
+            .register_import(import.alias.clone().unwrap_or(name), target.clone(), ty, import.trace)?;
+
+        Ok((!is_module).then_some((namespace.clone(), target, import.trace)))
+    }
+
+    /// Checks that an import of an item that is not a module names a function or a global the
+    /// importing module `namespace` may use
+    fn check_value_import(&self, namespace: &Namespace, target: Namespace, trace: Trace) -> CompilerResult<()> {
+        let (name, parent) = target.chain.split_last().expect("Imports name an item");
+        let declared = self.global_registry
+            .get_module(&Namespace { chain: parent.to_vec() })
+            .is_some_and(|module| module.has_function(name) || module.has_global(name));
+
+        if !declared {
+            return Err(CompilerError::UnknownNamespace(target, trace));
+        }
+        if !self.global_registry.is_importable(&target, namespace) {
+            return Err(private_item(target, trace));
+        }
+        Ok(())
+    }
+
+    /// Module a glob import brings the items of into scope
+    fn resolve_import(&self, namespace: &Namespace, path: &UnvalidatedSymbol, trace: Trace) -> CompilerResult<Namespace> {
+        let path = path.explicit_namespace.module(path.identifier.clone());
+        let absolute = path.prefix(self.global_registry.path().clone());
+
+        [path.prefix(namespace.clone()), absolute.clone()]
+            .into_iter()
+            .find(|target| self.global_registry.get_module(target).is_some())
+            .ok_or(CompilerError::UnknownNamespace(absolute, trace))
+    }
+
+    /// Brings every item declared so far in the modules of glob imports into scope of the
+    /// importing modules, `globs` holds the importing and the imported module. Private items
+    /// are left out
+    fn expand_glob_imports(&mut self, globs: &[(Namespace, Namespace)]) {
+        for (namespace, target) in globs {
+            let module = self.global_registry.get_module(target).expect("Glob imports name a module");
+            let items: Vec<(Namespace, Option<DataType>)> = module
+                .declared_names()
+                .into_iter()
+                .map(|name| (target.module(name.clone()), module.get_type(&name).cloned()))
+                .filter(|(item, _)| self.global_registry.is_importable(item, namespace))
+                .collect();
+
+            let module = self.global_registry.get_module_mut(namespace).expect("Module was registered");
+            for (item, ty) in items {
+                module.register_glob_import(item, ty);
+            }
+        }
+    }
+
+    /// Registers a global, its type is resolved right away if it is written out
+    fn declare_global(
+        &mut self,
+        namespace: &Namespace,
+        variable: &UnvalidatedVariableInfo,
+        constant: bool,
+        visibility: &Visibility,
+        trace: Trace,
+    ) -> CompilerResult<GlobalIdentifier> {
+        let module = self.global_registry
+            .get_module_mut(namespace)
+            .expect("Module was registered");
+
+        let data_type = match &variable.ty {
+            UnvalidatedType::Implicit => None,
+            ty => Some(module.resolve_type(ty, &TypeScope::default())?),
+        };
+
+        let name = module.create_identifier(variable.ident.clone());
+        module.register_global(GlobalVariable {
+            name: name.clone(),
+            data_type,
+            mutable: variable.mutable,
+            constant,
+            visibility: visibility.clone(),
+            trace,
+        })?;
+        Ok(name)
+    }
+
+    /// Validates the initializers of the globals and the bodies of the functions without a written
+    /// return type. Globals and functions without a written type get the type of their
+    /// initializer or body, those referring to them are checked again once it is known. The
+    /// validated globals are ordered so that every global is initialized after the globals its
+    /// initializer refers to
+    fn validate_inferred(&mut self, mut globals: Vec<PendingGlobal>, mut functions: Vec<PendingFunction>) -> Vec<CompilerError> {
+        let names: Vec<GlobalIdentifier> = globals.iter().map(|global| global.name.clone()).collect();
+        let scope = TypeScope::default();
+
+        let mut errors = vec![];
+        let mut validated = HashMap::new();
+        let mut references = HashMap::new();
+
+        loop {
+            let mut waiting = vec![];
+            let mut waiting_functions = vec![];
+            let mut progress = false;
+
+            for global in globals {
+                let module = self.global_registry
+                    .get_module(&global.namespace)
+                    .expect("Module was registered");
+                let variable = self.global_registry
+                    .get_global(&global.name)
+                    .expect("Global was registered");
+
+                let (result, referenced) = FunctionValidator::validate_global(
+                    &self.global_registry,
+                    module,
+                    &scope,
+                    variable,
+                    global.initial,
+                );
+
+                // the initializer was checked with placeholder types, it is only final once
+                // every global and function it refers to has a type
+                let complete = self.is_known(&referenced);
+                references.insert(global.name.clone(), referenced.globals);
+
+                if !complete {
+                    waiting.push(global);
+                    continue;
+                }
+                progress = true;
+
+                let data_type = match result {
+                    Ok(initialized) => {
+                        let data_type = initialized.data_type().clone();
+                        validated.insert(global.name.clone(), initialized);
+                        data_type
+                    }
+                    Err(err) => {
+                        errors.extend(err);
+                        DataType::Never
+                    }
+                };
+
+                self.global_registry
+                    .get_global_mut(&global.name)
+                    .expect("Global was registered")
+                    .data_type
+                    .get_or_insert(data_type);
+            }
+
+            for function in functions {
+                let module = self.global_registry
+                    .get_module(&function.namespace)
+                    .expect("Module was registered");
+
+                let (result, referenced) = FunctionValidator::validate(
+                    &self.global_registry,
+                    module,
+                    &function.scope,
+                    &function.prototype,
+                    function.body,
+                );
+
+                if !self.is_known(&referenced) {
+                    waiting_functions.push(function);
+                    continue;
+                }
+                progress = true;
+
+                let returns = match result {
+                    Ok(validated) => {
+                        let returns = validated.prototype.return_type();
+                        self.functions.push(validated);
+                        returns
+                    }
+                    Err(err) => {
+                        errors.extend(err);
+                        DataType::Never
+                    }
+                };
+                self.global_registry.infer_return_type(&function.prototype, returns);
+            }
+
+            if waiting.is_empty() && waiting_functions.is_empty() {
+                break;
+            }
+
+            // functions that are still waiting depend on what they return themselves, the globals
+            // and functions calling them go on without it. Globals that are still waiting refer
+            // to each other, which is reported below
+            if !progress {
+                if waiting_functions.is_empty() {
+                    break;
+                }
+
+                for function in waiting_functions.drain(..) {
+                    let trace = function.prototype.trace.expect("Declared function without a trace");
+                    errors.push(CompilerError::UninferredReturnType(function.prototype.name.clone(), trace));
+                    self.global_registry.infer_return_type(&function.prototype, DataType::Never);
+                }
+            }
+
+            globals = waiting;
+            functions = waiting_functions;
+        }
+
+        let (order, cycles) = order_globals(&names, &references);
+
+        for cycle in cycles {
+            let trace = self.global_registry
+                .get_global(&cycle[0])
+                .expect("Global was registered")
+                .trace;
+            errors.push(CompilerError::CyclicGlobals { cycle, trace });
+        }
+
+        self.globals.extend(order.iter().filter_map(|name| validated.remove(name)));
+        errors
+    }
+
+    /// Whether every global and function that was referred to has a known type by now
+    fn is_known(&self, references: &References) -> bool {
+        let global_known = |name: &GlobalIdentifier| {
+            self.global_registry.get_global(name).is_some_and(|global| global.data_type.is_some())
+        };
+        let function_known = |function: &FunctionPrototype| {
+            self.global_registry.get_function(function).is_some_and(|function| function.returns.is_some())
+        };
+
+        references.globals.iter().all(global_known) && references.functions.iter().all(function_known)
+    }
+
+    /// Resolves the type an impl block is for, the returned scope is shared by its functions
+    fn declare_impl(
+        &mut self,
+        namespace: &Namespace,
+        generics: &[Identifier],
+        target: &UnvalidatedType,
+        implements: Option<&UnvalidatedType>,
+        trace: Trace,
+    ) -> CompilerResult<TypeScope> {
+        check_generics(generics, trace)?;
+
+        let module = self.global_registry
+            .get_module(namespace)
+            .expect("Module was registered");
+
+        let mut scope = TypeScope { parameters: generics.to_vec(), ..Default::default() };
+        let is_template_argument = |name: &Identifier| [Some(target), implements].into_iter().flatten().any(|ty| {
+            matches!(ty, UnvalidatedType::Template { template_arguments, .. } if template_arguments
+                .iter()
+                .any(|argument| matches!(argument, UnvalidatedType::Type(argument, _) if argument == name)))
+        });
+
+        let self_type = module.resolve_type(target, &scope).map_err(|err| match err {
+            // `impl Vec<T>` without declaring `T`
+            CompilerError::UnknownType(name, trace) if is_template_argument(&name) => {
+                CompilerError::UnknownTypeParameter(name, trace)
+            }
+            err => err,
+        })?;
+
+        match self_type {
+            DataType::Structure(..) | DataType::Enumeration(..) => (),
+            // primitives and references get their namespace with their first impl block
+            DataType::Primitive(_) | DataType::Pointer(_) => {
+                let namespace = self_type.namespace().expect("Primitives and references have a namespace");
+                if self.global_registry.get_module(&namespace).is_none() {
+                    let (name, _) = namespace.chain.split_last().expect("Namespaces are named");
+                    self.global_registry.register_sub_module(name.clone())?;
+                }
+            }
+            ty => return Err(CompilerError::InvalidImplTarget(ty, target.trace().unwrap_or(trace))),
+        }
+
+        scope.namespace = self_type.namespace();
+        scope.self_type = Some(self_type);
+        Ok(scope)
+    }
+
+    /// Resolves the trait of an `impl Trait for Type` block and checks that the module may
+    /// implement it for the type, the methods are added by [Self::implement_trait]. `scope` is the
+    /// scope of the impl block
+    fn resolve_implemented_trait(
+        &self,
+        namespace: &Namespace,
+        scope: &TypeScope,
+        implements: &UnvalidatedType,
+        trace: Trace,
+    ) -> CompilerResult<TraitImpl> {
+        let module = self.global_registry
+            .get_module(namespace)
+            .expect("Module was registered");
+
+        let (name, arguments, trait_trace) = match implements {
+            UnvalidatedType::Type(name, trace) => (name, vec![], *trace),
+            UnvalidatedType::Template { base, template_arguments, trace } => (base, template_arguments
+                .iter()
+                .map(|argument| module.resolve_type(argument, scope))
+                .collect::<CompilerResult<Vec<_>>>()?, *trace),
+            _ => unreachable!("The parser only accepts named traits"),
+        };
+
+        module.check_ambiguous(name, trait_trace)?;
+        let declaration = module
+            .lookup_trait(name)
+            .and_then(|trait_name| self.global_registry.get_trait(&trait_name))
+            .ok_or_else(|| CompilerError::UnknownTrait(name.clone(), trait_trace))?;
+        let trait_name = declaration.name.clone();
+
+        if arguments.len() != declaration.generics.len() {
+            return Err(CompilerError::TypeArgumentCount {
+                ty: name.clone(),
+                expected: declaration.generics.len(),
+                found: arguments.len(),
+                trace: trait_trace,
+            });
+        }
+
+        let target = scope.self_type.clone().expect("Impl blocks have a target");
+
+        // calls of the trait's methods find the impl block by binding its type parameters
+        if let Some(parameter) = scope.parameters
+            .iter()
+            .find(|parameter| !target.mentions(parameter) && !arguments.iter().any(|ty| ty.mentions(parameter))) {
+            return Err(CompilerError::UninferredTypeParameter { item: trait_name, parameter: parameter.clone(), trace });
         }
 
+        // either the trait or the type has to be declared next to the impl block, so that two
+        // modules can not implement the same trait for the same type
+        let target_module = target.namespace().and_then(|namespace| {
+            let (_, parent) = namespace.chain.split_last()?;
+            Some(Namespace { chain: parent.to_vec() })
+        });
+        if namespace != &trait_name.0 && Some(namespace) != target_module.as_ref() {
+            return Err(CompilerError::OrphanImpl { trait_name, ty: target, trace });
+        }
+
+        let implementation = TraitImpl {
+            trait_name,
+            generics: scope.parameters.clone(),
+            arguments,
+            target,
+            module: namespace.clone(),
+            methods: vec![],
+            trace,
+        };
+
+        if let Some(previous) = self.global_registry.impls().iter().find(|previous| previous.overlaps(&implementation)) {
+            return Err(CompilerError::OverlappingImpls {
+                trait_name: implementation.trait_name,
+                ty: implementation.target,
+                trace,
+                previous: previous.trace,
+            });
+        }
+        Ok(implementation)
+    }
+
+    /// Checks the functions of an impl block against the methods of the trait it implements and
+    /// registers the implementation
+    fn implement_trait(
+        &mut self,
+        mut implementation: TraitImpl,
+        functions: Vec<FunctionPrototype>,
+    ) -> CompilerResult<()> {
+        let declaration = self.global_registry.get_trait(&implementation.trait_name).expect("Trait was resolved");
+        let trait_name = &implementation.trait_name;
+        let trace = implementation.trace;
+
+        let mut bindings: HashMap<Identifier, DataType> = declaration.generics
+            .iter()
+            .cloned()
+            .zip(implementation.arguments.iter().cloned())
+            .collect();
+        bindings.insert(SELF_TYPE.into(), implementation.target.clone());
+
+        let mut methods = vec![];
+        for function in functions {
+            let function_trace = function.trace.expect("Declared function without a trace");
+            let method = declaration
+                .get_method(&function.name.1)
+                .ok_or_else(|| CompilerError::MethodNotInTrait {
+                    method: function.name.1.clone(),
+                    trait_name: trait_name.clone(),
+                    trace: function_trace,
+                })?;
+
+            // the method's own type parameters are matched by position
+            let own = &method.prototype.generics[declaration.generics.len() + 1..];
+            let implemented = &function.generics[implementation.generics.len()..];
+            let mut bindings = bindings.clone();
+            bindings.extend(own.iter().cloned().zip(implemented.iter().cloned().map(DataType::Parameter)));
+
+            let mut expected = method.prototype.clone();
+            expected.name = function.name.clone();
+            for argument in &mut expected.arguments {
+                argument.data_type = argument.data_type.substitute(&bindings);
+            }
+            expected.returns = Some(expected.return_type().substitute(&bindings));
+
+            let matches = own.len() == implemented.len()
+                && expected.return_type() == function.return_type()
+                && expected.arguments.len() == function.arguments.len()
+                && expected.arguments
+                    .iter()
+                    .zip(&function.arguments)
+                    .all(|(expected, found)| expected.data_type == found.data_type);
+
+            if !matches {
+                return Err(CompilerError::TraitSignatureMismatch {
+                    method: function.name.1.clone(),
+                    expected: expected.signature(),
+                    found: function.signature(),
+                    trace: function_trace,
+                });
+            }
+
+            methods.push(ImplementedMethod { declaration: method.prototype.clone(), implementation: function });
+        }
+
+        let missing: Vec<Identifier> = declaration.methods
+            .iter()
+            .filter(|method| !method.provided)
+            .filter(|method| !methods.iter().any(|implemented| implemented.declaration == method.prototype))
+            .map(|method| method.prototype.name.1.clone())
+            .collect();
+        if !missing.is_empty() {
+            return Err(CompilerError::MissingTraitMethods { trait_name: trait_name.clone(), methods: missing, trace });
+        }
+
+        implementation.methods = methods;
+        self.global_registry.register_impl(implementation);
         Ok(())
     }
+
+    /// Registers the signature of a function, functions of impl blocks are registered in the
+    /// namespace of the impl target and methods of traits in the namespace of the trait. `scope`
+    /// is the scope of the impl block, the returned one also contains the type parameters of the
+    /// function. Functions that leave out their return type have it inferred from their body later
+    fn declare_function(
+        &mut self,
+        namespace: &Namespace,
+        scope: &TypeScope,
+        proto: &UnvalidatedFunctionPrototype,
+        visibility: Visibility,
+        trace: Trace,
+    ) -> CompilerResult<(FunctionPrototype, TypeScope)> {
+        let mut scope = scope.clone();
+        scope.parameters.extend(proto.generics.iter().cloned());
+        check_generics(&scope.parameters, trace)?;
+
+        let module = self.global_registry
+            .get_module(namespace)
+            .expect("Module was registered");
+
+        let arguments = proto.arguments
+            .iter()
+            .map(|argument| Ok(VariableInfo {
+                name: argument.ident.clone(),
+                data_type: module.resolve_type(&argument.ty, &scope)?,
+                mutable: argument.mutable,
+            }))
+            .collect::<CompilerResult<_>>()?;
+        let returns = match &proto.returns {
+            UnvalidatedType::Implicit => None,
+            ty => Some(module.resolve_type(ty, &scope)?),
+        };
+
+        let namespace = scope.namespace.clone().unwrap_or_else(|| namespace.clone());
+        let registry = self.global_registry
+            .get_module_mut(&namespace)
+            .expect("Type namespaces are registered with their type");
+
+        let prototype = FunctionPrototype {
+            name: registry.create_identifier(proto.name.clone()),
+            generics: scope.parameters.clone(),
+            arguments,
+            returns,
+            visibility,
+            trace: Some(trace),
+        };
+
+        registry.register_function(prototype.clone())?;
+        Ok((prototype, scope))
+    }
+}
+
+/// Declares the functions the compiler provides itself, they have neither a body nor a trace
+fn register_builtins(registry: &mut ModuleRegistry) {
+    let namespace = registry.path().module(BUILTIN_MODULE.into());
+    registry
+        .register_sub_module(BUILTIN_MODULE.into())
+        .expect("The built-in module is registered first");
+    let module = registry.get_module_mut(&namespace).expect("Registered the built-in module");
+
+    let argument = |name: &str, data_type| VariableInfo { name: name.into(), data_type, mutable: false };
+    let element = DataType::Parameter("T".into());
+
+    let builtins = [
+        (PRINT, vec![], vec![argument("text", DataType::string())], DataType::Unit),
+        (CRASH, vec![], vec![argument("message", DataType::string())], DataType::Never),
+        (ARRAY, vec!["T".into()], vec![
+            argument("start", DataType::Pointer(Box::new(element.clone()))),
+            argument("length", DataType::Primitive(PrimitiveType::Usize)),
+        ], DataType::Array(Box::new(element))),
+    ];
+
+    for (name, generics, arguments, returns) in builtins {
+        module
+            .register_function(FunctionPrototype {
+                name: GlobalIdentifier(namespace.clone(), name.into()),
+                generics,
+                arguments,
+                returns: Some(returns),
+                visibility: Visibility::Public,
+                trace: None,
+            })
+            .expect("Built-in functions have distinct names");
+    }
+}
+
+/// Error for an import of the item at the absolute path that is private to its module
+fn private_item(mut path: Namespace, trace: Trace) -> CompilerError {
+    let name = path.chain.pop().expect("Imports name an item");
+    CompilerError::PrivateItem(GlobalIdentifier(path, name), trace)
+}
+
+/// Whether the function is provided by the compiler instead of being declared in the program
+pub fn is_builtin(function: &GlobalIdentifier) -> bool {
+    function.0 == Namespace::global().module(BUILTIN_MODULE.into())
+}
+
+/// Orders globals after the globals their initializers refer to. Also returns every cycle of
+/// initializers referring to each other, starting and ending with the same global
+fn order_globals(
+    names: &[GlobalIdentifier],
+    references: &HashMap<GlobalIdentifier, Vec<GlobalIdentifier>>,
+) -> (Vec<GlobalIdentifier>, Vec<Vec<GlobalIdentifier>>) {
+    let mut order = vec![];
+    let mut cycles = vec![];
+    let mut visited = HashSet::new();
+
+    for name in names {
+        visit_global(name, references, &mut visited, &mut vec![], &mut order, &mut cycles);
+    }
+    (order, cycles)
+}
+
+/// Depth first search through the references of an initializer, `path` holds the globals whose
+/// references are being visited
+fn visit_global<'a>(
+    name: &'a GlobalIdentifier,
+    references: &'a HashMap<GlobalIdentifier, Vec<GlobalIdentifier>>,
+    visited: &mut HashSet<&'a GlobalIdentifier>,
+    path: &mut Vec<&'a GlobalIdentifier>,
+    order: &mut Vec<GlobalIdentifier>,
+    cycles: &mut Vec<Vec<GlobalIdentifier>>,
+) {
+    if let Some(start) = path.iter().position(|global| *global == name) {
+        cycles.push(path[start..].iter().copied().chain([name]).cloned().collect());
+        return;
+    }
+    if !visited.insert(name) {
+        return;
+    }
+
+    path.push(name);
+    for referenced in references.get(name).into_iter().flatten() {
+        visit_global(referenced, references, visited, path, order, cycles);
+    }
+    path.pop();
+
+    order.push(name.clone());
+}
+
+/// Methods of traits and their implementations are compared with each other before any body is
+/// checked, so they have to write out their return type
+fn check_written_return(namespace: &Namespace, proto: &UnvalidatedFunctionPrototype, trace: Trace) -> CompilerResult<()> {
+    match proto.returns {
+        UnvalidatedType::Implicit => Err(CompilerError::UninferredReturnType(
+            GlobalIdentifier(namespace.clone(), proto.name.clone()),
+            trace,
+        )),
+        _ => Ok(()),
+    }
+}
+
+/// Checks that no type parameter is declared twice
+fn check_generics(generics: &[Identifier], trace: Trace) -> CompilerResult<()> {
+    for (i, name) in generics.iter().enumerate() {
+        if generics[..i].contains(name) {
+            return Err(CompilerError::DuplicateTypeParameter(name.clone(), trace));
+        }
+    }
+    Ok(())
+}
+
+/// Type arguments of a generic type referring to its own parameters, the form it is registered in
+fn parameters(generics: &[Identifier]) -> Vec<DataType> {
+    generics.iter().cloned().map(DataType::Parameter).collect()
 }
@@ -0,0 +1,407 @@
+use std::collections::{HashMap, HashSet};
+use serde::Serialize;
+use crate::file::identifier::{GlobalIdentifier, Identifier};
+use crate::file::trace::Trace;
+use crate::validation::data_type::DataType;
+use crate::validation::error::{CompilerError, CompilerResult};
+use crate::validation::module::{is_builtin, Module};
+use crate::validation::registry::enums::EnumVariant;
+use crate::validation::registry::expression::{Expression, FormatArgument, FunctionStatement};
+use crate::validation::registry::functions::ValidatedFunction;
+use crate::validation::registry::globals::ValidatedGlobal;
+use crate::validation::registry::structs::StructField;
+
+/// Name of the function every program starts at
+pub const ENTRY_POINT: &str = "main";
+
+/// Nesting depth of type arguments after which instantiation is assumed to never end, like for a
+/// generic function calling itself with `[T]` or a structure containing `ref[Node<[T]>]`
+pub const MAX_TYPE_DEPTH: usize = 32;
+
+/// Concrete functions and types of a program, every type parameter is replaced by a type argument
+#[derive(Debug, Default, Serialize)]
+pub struct Instances {
+    /// Functions reachable from the entry point, starting with the entry point itself
+    pub functions: Vec<FunctionInstance>,
+
+    /// Structures and enums the functions use
+    pub types: Vec<TypeInstance>,
+
+    /// Globals the functions use, in the order their initializers have to run in
+    pub globals: Vec<ValidatedGlobal>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct FunctionInstance {
+    pub type_arguments: Vec<DataType>,
+
+    /// Function with the type arguments substituted into its signature, locals and body
+    pub function: ValidatedFunction,
+}
+
+#[derive(Debug, Serialize)]
+pub struct TypeInstance {
+    /// Concrete type, eg. `Option<ref[char]>`
+    pub ty: DataType,
+    pub layout: Layout,
+}
+
+/// Fields of a type instance with the type arguments substituted
+#[derive(Debug, Serialize)]
+pub enum Layout {
+    Structure(Vec<StructField>),
+    Enumeration(Vec<EnumVariant>),
+}
+
+/// Item together with the type arguments it is instantiated with, instances are cached by it
+type InstanceKey = (GlobalIdentifier, Vec<DataType>);
+
+/// Collects the instances of every generic function and type reachable from the entry point.
+///
+/// Function instances are processed from a work list, instantiating a body only queues the
+/// functions it calls so long call chains do not recurse.
+pub struct Monomorphizer<'a> {
+    /// Validated functions by their name and declared parameter types
+    declarations: HashMap<(&'a GlobalIdentifier, Vec<&'a DataType>), &'a ValidatedFunction>,
+    module: &'a Module,
+    instances: Instances,
+
+    /// Function instances that were reached, the declared parameter types tell overloads apart
+    functions: HashSet<(InstanceKey, Vec<DataType>)>,
+    types: HashSet<InstanceKey>,
+    globals: HashSet<GlobalIdentifier>,
+    pending: Vec<(&'a ValidatedFunction, Vec<DataType>)>,
+}
+
+impl<'a> Monomorphizer<'a> {
+    /// Instantiates every function reachable from the entry point and every type they use
+    pub fn run(module: &'a Module) -> CompilerResult<Instances> {
+        let main = Self::entry_point(module)?;
+
+        if !main.prototype.generics.is_empty() {
+            return Err(CompilerError::GenericMain(main.prototype.trace.expect("Validated function without a trace")));
+        }
+
+        let declarations = module.functions()
+            .iter()
+            .map(|function| {
+                let signature = function.prototype.arguments.iter().map(|argument| &argument.data_type).collect();
+                ((&function.prototype.name, signature), function)
+            })
+            .collect();
+
+        let mut monomorphizer = Self {
+            declarations,
+            module,
+            instances: Instances::default(),
+            functions: HashSet::new(),
+            types: HashSet::new(),
+            globals: HashSet::new(),
+            pending: vec![(main, vec![])],
+        };
+
+        let signature = main.prototype.arguments.iter().map(|argument| argument.data_type.clone()).collect();
+        monomorphizer.functions.insert(((main.prototype.name.clone(), vec![]), signature));
+
+        while let Some((function, type_arguments)) = monomorphizer.pending.pop() {
+            monomorphizer.instantiate_function(function, type_arguments)?;
+        }
+
+        Ok(monomorphizer.instances)
+    }
+
+    /// The one function named `main` declared at the top level of a module, functions of impl
+    /// blocks and traits can have the name as well
+    fn entry_point(module: &Module) -> CompilerResult<&ValidatedFunction> {
+        let mut candidates = module.functions().iter().filter(|function| {
+            function.prototype.name.1.as_str() == ENTRY_POINT
+                && !module.registry().is_associated(&function.prototype.name)
+        });
+
+        let main = candidates.next().ok_or(CompilerError::MissingMain)?;
+
+        if let Some(duplicate) = candidates.next() {
+            return Err(CompilerError::DuplicateMain {
+                trace: duplicate.prototype.trace.expect("Validated function without a trace"),
+                previous: main.prototype.trace.expect("Validated function without a trace"),
+            });
+        }
+        Ok(main)
+    }
+
+    fn instantiate_function(&mut self, function: &ValidatedFunction, type_arguments: Vec<DataType>) -> CompilerResult<()> {
+        let trace = function.prototype.trace.expect("Validated function without a trace");
+        let bindings: HashMap<Identifier, DataType> = function.prototype.generics
+            .iter()
+            .cloned()
+            .zip(type_arguments.iter().cloned())
+            .collect();
+
+        let mut instance = function.clone();
+
+        for argument in &mut instance.prototype.arguments {
+            argument.data_type = self.instantiate_type(&argument.data_type, &bindings, trace)?;
+        }
+        if let Some(returns) = &mut instance.prototype.returns {
+            *returns = self.instantiate_type(returns, &bindings, trace)?;
+        }
+        for local in instance.locals.values_mut() {
+            local.data_type = self.instantiate_type(&local.data_type, &bindings, trace)?;
+        }
+        self.instantiate_expression(&mut instance.body, &bindings, trace)?;
+
+        self.instances.functions.push(FunctionInstance { type_arguments, function: instance });
+        Ok(())
+    }
+
+    fn instantiate_statement(
+        &mut self,
+        statement: &mut FunctionStatement,
+        bindings: &HashMap<Identifier, DataType>,
+        trace: Trace,
+    ) -> CompilerResult<()> {
+        match statement {
+            FunctionStatement::Scope(statements) => {
+                for statement in statements {
+                    self.instantiate_statement(statement, bindings, trace)?;
+                }
+            }
+            FunctionStatement::If { condition, then, otherwise } => {
+                self.instantiate_expression(condition, bindings, trace)?;
+                self.instantiate_statement(then, bindings, trace)?;
+
+                if let Some(otherwise) = otherwise {
+                    self.instantiate_statement(otherwise, bindings, trace)?;
+                }
+            }
+            FunctionStatement::While { condition, then } => {
+                self.instantiate_expression(condition, bindings, trace)?;
+                self.instantiate_statement(then, bindings, trace)?;
+            }
+            FunctionStatement::Return(Some(expr))
+            | FunctionStatement::Let { initial: Some(expr), .. }
+            | FunctionStatement::Expression(expr) => self.instantiate_expression(expr, bindings, trace)?,
+            FunctionStatement::Return(None) | FunctionStatement::Let { initial: None, .. } => {}
+        }
+        Ok(())
+    }
+
+    /// Substitutes the type arguments into every type of the expression, the functions and types
+    /// it uses are instantiated as well
+    fn instantiate_expression(
+        &mut self,
+        expr: &mut Expression,
+        bindings: &HashMap<Identifier, DataType>,
+        trace: Trace,
+    ) -> CompilerResult<()> {
+        match expr {
+            Expression::FunctionCall { function, signature, type_arguments, arguments } => {
+                for argument in type_arguments.iter_mut() {
+                    *argument = self.instantiate_type(argument, bindings, trace)?;
+                }
+                for argument in arguments {
+                    self.instantiate_expression(argument, bindings, trace)?;
+                }
+
+                // `Self` is known now, so methods of traits call the implementation for it
+                if let Some(dispatched) = self.module.registry().dispatch(function, signature, type_arguments) {
+                    (*function, *signature, *type_arguments) = dispatched;
+                }
+                // built-in functions have no body to instantiate
+                if !is_builtin(function) {
+                    self.request_function(function, signature, type_arguments)?;
+                }
+            }
+            Expression::Construct { structure: name, type_arguments, fields }
+            | Expression::Variant { enumeration: name, type_arguments, fields, .. } => {
+                for argument in type_arguments.iter_mut() {
+                    *argument = self.instantiate_type(argument, bindings, trace)?;
+                }
+                for field in fields {
+                    self.instantiate_expression(field, bindings, trace)?;
+                }
+                self.request_type(name, type_arguments, trace)?;
+            }
+            Expression::Cast { expr, ty } => {
+                *ty = self.instantiate_type(ty, bindings, trace)?;
+                self.instantiate_expression(expr, bindings, trace)?;
+            }
+            Expression::Format(arguments) => {
+                for argument in arguments {
+                    if let FormatArgument::Value { value, ty } = argument {
+                        *ty = self.instantiate_type(ty, bindings, trace)?;
+                        self.instantiate_expression(value, bindings, trace)?;
+                    }
+                }
+            }
+            Expression::Binary { lhs, rhs, .. } | Expression::Index { array: lhs, index: rhs } => {
+                self.instantiate_expression(lhs, bindings, trace)?;
+                self.instantiate_expression(rhs, bindings, trace)?;
+            }
+            Expression::Unary { operand: expr, .. }
+            | Expression::Parenthetical(expr)
+            | Expression::FieldAccess { object: expr, .. }
+            | Expression::Alloc(expr) => self.instantiate_expression(expr, bindings, trace)?,
+            Expression::IfElse { condition, then, otherwise } => {
+                self.instantiate_expression(condition, bindings, trace)?;
+                self.instantiate_statement(then, bindings, trace)?;
+                self.instantiate_statement(otherwise, bindings, trace)?;
+            }
+            Expression::Match { scrutinee, arms } => {
+                self.instantiate_expression(scrutinee, bindings, trace)?;
+
+                // patterns hold literals only, which have no types to substitute
+                for arm in arms {
+                    if let Some(guard) = &mut arm.guard {
+                        self.instantiate_expression(guard, bindings, trace)?;
+                    }
+                    self.instantiate_expression(&mut arm.body, bindings, trace)?;
+                }
+            }
+            Expression::Scope(statements) => {
+                for statement in statements {
+                    self.instantiate_statement(statement, bindings, trace)?;
+                }
+            }
+            Expression::BoolLiteral(_)
+            | Expression::F32Literal(_)
+            | Expression::F64Literal(_)
+            | Expression::I32Literal(_)
+            | Expression::I64Literal(_)
+            | Expression::U32Literal(_)
+            | Expression::U64Literal(_)
+            | Expression::UsizeLiteral(_)
+            | Expression::CharLiteral(_)
+            | Expression::StringLiteral(_)
+            | Expression::LocalVariableReference(_) => {}
+            Expression::GlobalVariableReference(name) => self.request_global(name)?,
+        }
+        Ok(())
+    }
+
+    /// Instantiates the initializer of a global if it was not reached before. Globals are not
+    /// generic, but their initializers can call generic functions. The globals an initializer
+    /// refers to are instantiated before it, which keeps them in initialization order
+    fn request_global(&mut self, name: &GlobalIdentifier) -> CompilerResult<()> {
+        if !self.globals.insert(name.clone()) {
+            return Ok(());
+        }
+
+        let mut global = self.module.globals()
+            .iter()
+            .find(|global| &global.variable.name == name)
+            .expect("Referenced globals passed validation")
+            .clone();
+        let trace = global.variable.trace;
+        let bindings = HashMap::new();
+
+        self.use_type(global.data_type(), trace)?;
+        for local in global.locals.values() {
+            self.use_type(&local.data_type, trace)?;
+        }
+        self.instantiate_expression(&mut global.initial, &bindings, trace)?;
+
+        self.instances.globals.push(global);
+        Ok(())
+    }
+
+    /// Queues the instance of a function if it was not reached before
+    fn request_function(
+        &mut self,
+        function: &GlobalIdentifier,
+        signature: &[DataType],
+        type_arguments: &[DataType],
+    ) -> CompilerResult<()> {
+        let key = ((function.clone(), type_arguments.to_vec()), signature.to_vec());
+        if self.functions.contains(&key) {
+            return Ok(());
+        }
+
+        let declaration = self.declarations
+            .get(&(function, signature.iter().collect()))
+            .copied()
+            .expect("Called functions passed validation");
+
+        let trace = declaration.prototype.trace.expect("Validated function without a trace");
+        check_depth(function, type_arguments, trace)?;
+
+        self.functions.insert(key);
+        self.pending.push((declaration, type_arguments.to_vec()));
+        Ok(())
+    }
+
+    /// Substitutes the type arguments into a type, the structures and enums it mentions are
+    /// instantiated
+    fn instantiate_type(
+        &mut self,
+        ty: &DataType,
+        bindings: &HashMap<Identifier, DataType>,
+        trace: Trace,
+    ) -> CompilerResult<DataType> {
+        let ty = ty.substitute(bindings);
+        self.use_type(&ty, trace)?;
+        Ok(ty)
+    }
+
+    fn use_type(&mut self, ty: &DataType, trace: Trace) -> CompilerResult<()> {
+        match ty {
+            DataType::Pointer(ty) | DataType::Array(ty) => self.use_type(ty, trace),
+            DataType::Structure(name, arguments) | DataType::Enumeration(name, arguments) => {
+                self.request_type(name, arguments, trace)
+            }
+            _ => Ok(()),
+        }
+    }
+
+    /// Instantiates the layout of a structure or enum if it was not used before. `trace` is
+    /// where the type is used, it is reported if the declaration of the type is not known
+    fn request_type(&mut self, name: &GlobalIdentifier, arguments: &[DataType], trace: Trace) -> CompilerResult<()> {
+        for argument in arguments {
+            self.use_type(argument, trace)?;
+        }
+
+        let key = (name.clone(), arguments.to_vec());
+        if self.types.contains(&key) {
+            return Ok(());
+        }
+        // registered before the fields are instantiated so that types can refer to themselves
+        self.types.insert(key);
+
+        let registry = self.module.registry();
+
+        let (ty, layout) = if let Some(structure) = registry.get_struct(name) {
+            check_depth(name, arguments, structure.trace)?;
+            let bindings = structure.generics.iter().cloned().zip(arguments.iter().cloned()).collect();
+
+            let mut fields = structure.fields.clone();
+            for field in &mut fields {
+                field.data_type = self.instantiate_type(&field.data_type, &bindings, structure.trace)?;
+            }
+            (DataType::Structure(name.clone(), arguments.to_vec()), Layout::Structure(fields))
+        } else if let Some(enumeration) = registry.get_enum(name) {
+            check_depth(name, arguments, enumeration.trace)?;
+            let bindings: HashMap<_, _> = enumeration.generics.iter().cloned().zip(arguments.iter().cloned()).collect();
+
+            let mut variants = enumeration.variants.clone();
+            for variant in &mut variants {
+                for ty in variant.data.types_mut() {
+                    *ty = self.instantiate_type(ty, &bindings, enumeration.trace)?;
+                }
+            }
+            (DataType::Enumeration(name.clone(), arguments.to_vec()), Layout::Enumeration(variants))
+        } else {
+            return Err(CompilerError::UnknownType(name.1.clone(), trace));
+        };
+
+        self.instances.types.push(TypeInstance { ty, layout });
+        Ok(())
+    }
+}
+
+/// Reports instantiations whose type arguments keep growing
+fn check_depth(item: &GlobalIdentifier, arguments: &[DataType], trace: Trace) -> CompilerResult<()> {
+    if arguments.iter().any(|argument| argument.depth() > MAX_TYPE_DEPTH) {
+        return Err(CompilerError::RecursiveInstantiation { item: item.clone(), trace });
+    }
+    Ok(())
+}
@@ -0,0 +1,76 @@
+use serde::Serialize;
+use crate::file::identifier::{GlobalIdentifier, Identifier};
+use crate::file::trace::Trace;
+use crate::ir::visibility::Visibility;
+use crate::validation::data_type::DataType;
+
+#[derive(Debug, Serialize)]
+pub struct EnumPrototype {
+    pub name: GlobalIdentifier,
+
+    /// Type parameters the declared types can refer to
+    pub generics: Vec<Identifier>,
+
+    /// Variants in the order they were declared in, the index of a variant is its discriminant
+    pub variants: Vec<EnumVariant>,
+    pub visibility: Visibility,
+
+    #[serde(skip)]
+    pub trace: Trace,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct EnumVariant {
+    pub name: Identifier,
+    pub data: EnumVariantData,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub enum EnumVariantData {
+    /// Variant without any data, eg. `None`
+    Unit,
+
+    /// Variant with unnamed fields, eg. `Some(T)`
+    Positional(Vec<DataType>),
+
+    /// Variant with named fields, eg. `Rect { width: f32, height: f32 }`
+    StructLike(Vec<(Identifier, DataType)>),
+}
+
+impl EnumPrototype {
+    /// Type of the enum with its own type parameters as arguments
+    pub fn data_type(&self) -> DataType {
+        DataType::Enumeration(self.name.clone(), self.generics.iter().cloned().map(DataType::Parameter).collect())
+    }
+
+    pub fn get_variant(&self, name: &Identifier) -> Option<&EnumVariant> {
+        self.variants.iter().find(|variant| &variant.name == name)
+    }
+}
+
+impl EnumVariantData {
+    /// Types of every field of the variant
+    pub fn types_mut(&mut self) -> Vec<&mut DataType> {
+        match self {
+            EnumVariantData::Unit => vec![],
+            EnumVariantData::Positional(fields) => fields.iter_mut().collect(),
+            EnumVariantData::StructLike(fields) => fields.iter_mut().map(|(_, ty)| ty).collect(),
+        }
+    }
+}
+
+impl EnumVariant {
+    /// How the variant is written when it is constructed, used by diagnostics
+    pub fn syntax(&self, enumeration: &GlobalIdentifier) -> String {
+        let name = format!("{}::{}", enumeration.1, self.name);
+
+        match &self.data {
+            EnumVariantData::Unit => name,
+            EnumVariantData::Positional(fields) => {
+                let fields: Vec<&str> = fields.iter().map(|_| "_").collect();
+                format!("{name}({})", fields.join(", "))
+            }
+            EnumVariantData::StructLike(_) => format!("{name} {{ .. }}"),
+        }
+    }
+}
@@ -1,9 +1,10 @@
+use serde::Serialize;
 use uuid::Uuid;
-use crate::file::identifier::GlobalIdentifier;
+use crate::file::identifier::{GlobalIdentifier, Identifier};
 use crate::parser::ast::operations::{BinaryOperation, UnaryOperator};
 use crate::validation::data_type::DataType;
 
-#[derive(Debug)]
+#[derive(Debug, Clone, Serialize)]
 pub enum FunctionStatement {
     Scope(Vec<FunctionStatement>),
     Return(Option<Expression>),
@@ -16,16 +17,28 @@ pub enum FunctionStatement {
         condition: Expression,
         then: Box<FunctionStatement>,
     },
+    Let {
+        variable: Uuid,
+        initial: Option<Expression>,
+    },
     Expression(Expression),
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone, Serialize)]
 pub enum Expression {
+    BoolLiteral(bool),
     F32Literal(f32),
     F64Literal(f64),
     I32Literal(i32),
     I64Literal(i64),
+    U32Literal(u32),
+    U64Literal(u64),
+    UsizeLiteral(u64),
+    CharLiteral(char),
     StringLiteral(String),
+
+    /// Formatting call building a string out of literal text and formatted values
+    Format(Vec<FormatArgument>),
     Binary {
         lhs: Box<Expression>,
         op: BinaryOperation,
@@ -41,6 +54,105 @@ pub enum Expression {
     },
     LocalVariableReference(Uuid),
     GlobalVariableReference(GlobalIdentifier),
-    FunctionCall(GlobalIdentifier),
+    FunctionCall {
+        function: GlobalIdentifier,
+
+        /// Declared parameter types of the called function, they tell its overloads apart
+        signature: Vec<DataType>,
+
+        /// Types the generic parameters of the function were inferred as, in declaration order
+        type_arguments: Vec<DataType>,
+        arguments: Vec<Expression>,
+    },
     Parenthetical(Box<Expression>),
-}
\ No newline at end of file
+
+    /// Field of a structure, objects behind references are dereferenced explicitly beforehand
+    FieldAccess {
+        object: Box<Expression>,
+        field: Identifier,
+    },
+
+    /// Element of an array
+    Index {
+        array: Box<Expression>,
+        index: Box<Expression>,
+    },
+
+    /// Evaluates to the value of the branch that is taken
+    IfElse {
+        condition: Box<Expression>,
+        then: Box<FunctionStatement>,
+        otherwise: Box<FunctionStatement>,
+    },
+
+    /// Evaluates to the body of the first arm whose pattern matches and whose guard holds, the
+    /// scrutinee is dereferenced beforehand if it refers to a structure or enum
+    Match {
+        scrutinee: Box<Expression>,
+        arms: Vec<MatchArm>,
+    },
+
+    /// Moves the value to the heap, evaluates to a reference to it
+    Alloc(Box<Expression>),
+
+    /// Structure with its field values in declaration order
+    Construct {
+        structure: GlobalIdentifier,
+        type_arguments: Vec<DataType>,
+        fields: Vec<Expression>,
+    },
+
+    /// Enum variant with its field values in declaration order
+    Variant {
+        enumeration: GlobalIdentifier,
+        type_arguments: Vec<DataType>,
+        variant: Identifier,
+        fields: Vec<Expression>,
+    },
+
+    /// Block evaluating to its trailing expression
+    Scope(Vec<FunctionStatement>),
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub enum FormatArgument {
+    Literal(String),
+    Value {
+        value: Expression,
+        ty: DataType,
+    },
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct MatchArm {
+    pub pattern: Pattern,
+    pub guard: Option<Expression>,
+    pub body: Expression,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub enum Pattern {
+    Wildcard,
+
+    /// Copies the matched value into a local
+    Binding(Uuid),
+
+    /// Matches values equal to the literal
+    Literal(Expression),
+    Range {
+        start: Option<Expression>,
+        end: Option<Expression>,
+        inclusive: bool,
+    },
+
+    /// Variant of the matched enum with patterns for its fields in declaration order
+    Variant {
+        variant: Identifier,
+        fields: Vec<Pattern>,
+    },
+
+    /// Patterns for the fields of the matched structure in declaration order, fields the pattern
+    /// leaves out are wildcards
+    Structure(Vec<Pattern>),
+    Alternatives(Vec<Pattern>),
+}
@@ -1,14 +1,59 @@
+use std::cmp::Ordering;
 use std::hash::{Hash, Hasher};
-use crate::file::identifier::GlobalIdentifier;
+use serde::Serialize;
+use crate::file::identifier::{GlobalIdentifier, Identifier};
+use crate::file::trace::Trace;
+use crate::ir::visibility::Visibility;
 use crate::validation::data_type::DataType;
-use crate::validation::registry::expression::FunctionStatement;
+use crate::validation::function_context::LocalVariableTable;
+use crate::validation::registry::expression::Expression;
 use crate::validation::registry::variable::VariableInfo;
 
-#[derive(Debug, Eq, Clone)]
+#[derive(Debug, Eq, Clone, Serialize)]
 pub struct FunctionPrototype {
     pub name: GlobalIdentifier,
+
+    /// Type parameters of the impl block the function is declared in, followed by its own
+    pub generics: Vec<Identifier>,
     pub arguments: Vec<VariableInfo>,
+
+    /// `None` until it is inferred for functions returning an expression without writing out its type
     pub returns: Option<DataType>,
+
+    /// Methods of traits and their implementations are public, the trait decides who can use them
+    pub visibility: Visibility,
+
+    /// Where the function was declared
+    #[serde(skip)]
+    pub trace: Option<Trace>,
+}
+
+impl FunctionPrototype {
+    /// Type calls of the function evaluate to, [DataType::Never] while it is not inferred yet
+    pub fn return_type(&self) -> DataType {
+        self.returns.clone().unwrap_or(DataType::Never)
+    }
+
+    /// Signature as it would be written in code, eg. `fun get(self: Vec<T>, index: usize): T`
+    pub fn signature(&self) -> String {
+        let arguments: Vec<String> = self.arguments
+            .iter()
+            .map(|argument| format!("{}: {}", argument.name, argument.data_type))
+            .collect();
+
+        match self.return_type() {
+            DataType::Unit => format!("fun {}({})", self.name.1, arguments.join(", ")),
+            returns => format!("fun {}({}): {returns}", self.name.1, arguments.join(", ")),
+        }
+    }
+}
+
+/// Function whose body passed validation
+#[derive(Debug, Clone, Serialize)]
+pub struct ValidatedFunction {
+    pub prototype: FunctionPrototype,
+    pub locals: LocalVariableTable,
+    pub body: Expression,
 }
 
 impl PartialEq for FunctionPrototype {
@@ -36,4 +81,21 @@ impl Hash for FunctionPrototype {
             arg.data_type.hash(state);
         }
     }
-}
\ No newline at end of file
+}
+
+/// Orders by name and then by argument types, consistent with equality
+impl Ord for FunctionPrototype {
+    fn cmp(&self, other: &Self) -> Ordering {
+        let argument_types = |prototype: &FunctionPrototype| -> Vec<DataType> {
+            prototype.arguments.iter().map(|argument| argument.data_type.clone()).collect()
+        };
+
+        self.name.cmp(&other.name).then_with(|| argument_types(self).cmp(&argument_types(other)))
+    }
+}
+
+impl PartialOrd for FunctionPrototype {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
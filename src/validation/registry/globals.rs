@@ -0,0 +1,39 @@
+use serde::Serialize;
+use crate::file::identifier::GlobalIdentifier;
+use crate::file::trace::Trace;
+use crate::ir::visibility::Visibility;
+use crate::validation::data_type::DataType;
+use crate::validation::function_context::LocalVariableTable;
+use crate::validation::registry::expression::Expression;
+
+/// Variable declared at module scope with `let`, or a `const` whose value is known at compile time
+#[derive(Debug, Clone, Serialize)]
+pub struct GlobalVariable {
+    pub name: GlobalIdentifier,
+
+    /// Type of the value, `None` until the initializer of a global without a written type has
+    /// been validated
+    pub data_type: Option<DataType>,
+    pub mutable: bool,
+    pub constant: bool,
+    pub visibility: Visibility,
+
+    #[serde(skip)]
+    pub trace: Trace,
+}
+
+/// Global whose initializer passed validation
+#[derive(Debug, Clone, Serialize)]
+pub struct ValidatedGlobal {
+    pub variable: GlobalVariable,
+
+    /// Variables declared by scopes of the initializer
+    pub locals: LocalVariableTable,
+    pub initial: Expression,
+}
+
+impl ValidatedGlobal {
+    pub fn data_type(&self) -> &DataType {
+        self.variable.data_type.as_ref().expect("Validated globals have a type")
+    }
+}
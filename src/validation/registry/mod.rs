@@ -1,62 +1,621 @@
-use std::any::Any;
-use std::collections::{HashMap, HashSet};
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::collections::btree_map::Entry;
 use std::hash::{Hash, Hasher};
+use serde::Serialize;
 use crate::file::identifier::{GlobalIdentifier, Identifier, Namespace};
-use crate::validation::data_type::DataType;
+use crate::file::trace::Trace;
+use crate::ir::visibility::Visibility;
+use crate::parser::ast::data::UnvalidatedType;
+use crate::parser::ast::function::SELF_TYPE;
+use crate::validation::data_type::{DataType, PrimitiveType};
 use crate::validation::error::{CompilerError, CompilerResult};
+use crate::validation::registry::enums::EnumPrototype;
 use crate::validation::registry::functions::FunctionPrototype;
+use crate::validation::registry::globals::GlobalVariable;
+use crate::validation::registry::structs::StructPrototype;
+use crate::validation::registry::traits::{TraitImpl, TraitPrototype};
 
 pub mod enums;
 pub mod functions;
+pub mod globals;
 pub mod structs;
+pub mod traits;
 pub mod variable;
-mod expression;
+pub mod expression;
 
-#[derive(Debug)]
+/// Names a type can refer to besides the types of its module, they are only valid inside of the
+/// item the type is written in
+#[derive(Debug, Default, Clone)]
+pub struct TypeScope {
+    /// Type of the impl block, named by `Self`
+    pub self_type: Option<DataType>,
+
+    /// Type parameters of the item and of the impl block it is declared in
+    pub parameters: Vec<Identifier>,
+
+    /// Namespace `Self::` paths refer to, the one of the impl target or of the trait whose
+    /// methods are declared
+    pub namespace: Option<Namespace>,
+}
+
+/// Items of a module and its sub modules, ordered maps keep the serialized form deterministic
+#[derive(Debug, Serialize)]
 pub struct ModuleRegistry {
     path: Namespace,
-    sub_modules: HashMap<Identifier, ModuleRegistry>,
-    data_types: HashMap<Identifier, DataType>,
-    functions: HashSet<FunctionPrototype>,
+    sub_modules: BTreeMap<Identifier, ModuleRegistry>,
+    data_types: BTreeMap<Identifier, DataType>,
+    structs: BTreeMap<Identifier, StructPrototype>,
+    enums: BTreeMap<Identifier, EnumPrototype>,
+    traits: BTreeMap<Identifier, TraitPrototype>,
+    /// Overloads of every function, keyed by name so that calls do not search the whole module
+    functions: BTreeMap<Identifier, BTreeSet<FunctionPrototype>>,
+    globals: BTreeMap<Identifier, GlobalVariable>,
+
+    /// Names brought into scope by imports together with the namespace they refer to
+    imports: BTreeMap<Identifier, Namespace>,
+
+    /// Names in `imports` that were brought into scope by a glob import, every other name
+    /// shadows them
+    glob_names: BTreeSet<Identifier>,
+
+    /// Names that glob imports bring into scope from more than one item, together with the items
+    ambiguous: BTreeMap<Identifier, Vec<Namespace>>,
+
+    /// Trait implementations of every module, only the global registry holds them as they
+    /// apply everywhere
+    impls: Vec<TraitImpl>,
 }
 
 impl ModuleRegistry {
     pub fn create_global() -> Self {
+        Self::empty(Namespace::global())
+    }
+
+    fn empty(path: Namespace) -> Self {
         Self {
-            path: Namespace::global(),
+            path,
             sub_modules: Default::default(),
             data_types: Default::default(),
+            structs: Default::default(),
+            enums: Default::default(),
+            traits: Default::default(),
             functions: Default::default(),
+            globals: Default::default(),
+            imports: Default::default(),
+            glob_names: Default::default(),
+            ambiguous: Default::default(),
+            impls: Default::default(),
         }
     }
 
-    pub fn register_sub_module(&mut self, name: Identifier) -> CompilerResult<()> {
-        let registry = Self {
-            path: self.path.module(name.clone()),
-            sub_modules: Default::default(),
-            data_types: Default::default(),
-            functions: Default::default(),
+    /// Registers a type declared in this module together with the namespace holding the
+    /// functions of its impl blocks
+    pub fn register_type(&mut self, name: Identifier, ty: DataType, trace: Trace) -> CompilerResult<()> {
+        if self.data_types.contains_key(&name) || self.sub_modules.contains_key(&name) {
+            return Err(CompilerError::DuplicateType(self.create_identifier(name), trace));
+        }
+
+        self.register_sub_module(name.clone())?;
+        self.data_types.insert(name, ty);
+        Ok(())
+    }
+
+    /// Stores a structure whose type was registered with [Self::register_type], its fields are
+    /// filled in once every type is known
+    pub fn register_struct(&mut self, prototype: StructPrototype) {
+        self.structs.insert(prototype.name.1.clone(), prototype);
+    }
+
+    /// Looks up a structure by its absolute name, the registry has to contain its module
+    pub fn get_struct(&self, name: &GlobalIdentifier) -> Option<&StructPrototype> {
+        self.get_module(&name.0)?.structs.get(&name.1)
+    }
+
+    pub fn get_struct_mut(&mut self, name: &GlobalIdentifier) -> Option<&mut StructPrototype> {
+        self.get_module_mut(&name.0)?.structs.get_mut(&name.1)
+    }
+
+    /// Stores an enum whose type was registered with [Self::register_type], its variants are
+    /// filled in once every type is known
+    pub fn register_enum(&mut self, prototype: EnumPrototype) {
+        self.enums.insert(prototype.name.1.clone(), prototype);
+    }
+
+    /// Looks up an enum by its absolute name, the registry has to contain its module
+    pub fn get_enum(&self, name: &GlobalIdentifier) -> Option<&EnumPrototype> {
+        self.get_module(&name.0)?.enums.get(&name.1)
+    }
+
+    pub fn get_enum_mut(&mut self, name: &GlobalIdentifier) -> Option<&mut EnumPrototype> {
+        self.get_module_mut(&name.0)?.enums.get_mut(&name.1)
+    }
+
+    /// Registers a trait together with the namespace holding its methods, the methods are
+    /// added once their signatures are resolved
+    pub fn register_trait(&mut self, prototype: TraitPrototype) -> CompilerResult<()> {
+        let name = prototype.name.1.clone();
+        if self.data_types.contains_key(&name) || self.sub_modules.contains_key(&name) {
+            return Err(CompilerError::DuplicateType(prototype.name, prototype.trace));
+        }
+
+        self.register_sub_module(name.clone())?;
+        self.traits.insert(name, prototype);
+        Ok(())
+    }
+
+    /// Looks up a trait by its absolute name, the registry has to contain its module
+    pub fn get_trait(&self, name: &GlobalIdentifier) -> Option<&TraitPrototype> {
+        self.get_module(&name.0)?.traits.get(&name.1)
+    }
+
+    pub fn get_trait_mut(&mut self, name: &GlobalIdentifier) -> Option<&mut TraitPrototype> {
+        self.get_module_mut(&name.0)?.traits.get_mut(&name.1)
+    }
+
+    /// Absolute name of a trait declared in or imported into this module, imports of items
+    /// that are not traits are returned as well
+    pub fn lookup_trait(&self, name: &Identifier) -> Option<GlobalIdentifier> {
+        if self.traits.contains_key(name) {
+            return Some(self.create_identifier(name.clone()));
+        }
+
+        let (name, namespace) = self.imports.get(name)?.chain.split_last()?;
+        Some(GlobalIdentifier(Namespace { chain: namespace.to_vec() }, name.clone()))
+    }
+
+    /// Whether a function belongs to a type or trait, declared in an impl block or trait rather
+    /// than at the top level of a module
+    pub fn is_associated(&self, function: &GlobalIdentifier) -> bool {
+        let Some((name, namespace)) = function.0.chain.split_last() else {
+            return false;
+        };
+        let owner = GlobalIdentifier(Namespace { chain: namespace.to_vec() }, name.clone());
+
+        self.get_struct(&owner).is_some() || self.get_enum(&owner).is_some() || self.get_trait(&owner).is_some()
+    }
+
+    /// Trait a function is a method of, if it is registered in the namespace of a trait
+    pub fn trait_of(&self, function: &GlobalIdentifier) -> Option<&TraitPrototype> {
+        let (name, namespace) = function.0.chain.split_last()?;
+        self.get_trait(&GlobalIdentifier(Namespace { chain: namespace.to_vec() }, name.clone()))
+    }
+
+    pub fn register_impl(&mut self, implementation: TraitImpl) {
+        self.impls.push(implementation);
+    }
+
+    pub fn impls(&self) -> &[TraitImpl] {
+        &self.impls
+    }
+
+    /// Impl block implementing a trait with the given type arguments for `ty`, together with the
+    /// bindings of its type parameters
+    pub fn find_impl(
+        &self,
+        trait_name: &GlobalIdentifier,
+        ty: &DataType,
+        arguments: &[DataType],
+    ) -> Option<(&TraitImpl, HashMap<Identifier, DataType>)> {
+        self.impls
+            .iter()
+            .filter(|implementation| &implementation.trait_name == trait_name)
+            .find_map(|implementation| Some((implementation, implementation.matches(ty, arguments)?)))
+    }
+
+    /// Type arguments of the trait for `ty`, if exactly one impl block implements it for the type
+    pub fn implemented_arguments(&self, trait_name: &GlobalIdentifier, ty: &DataType) -> Option<Vec<DataType>> {
+        let mut implemented = self.impls
+            .iter()
+            .filter(|implementation| &implementation.trait_name == trait_name)
+            .filter_map(|implementation| {
+                let mut bindings = HashMap::new();
+                let complete = implementation.target.bind(ty, &implementation.generics, &mut bindings)
+                    && implementation.generics.iter().all(|parameter| bindings.contains_key(parameter));
+
+                complete.then(|| implementation.arguments.iter().map(|argument| argument.substitute(&bindings)).collect())
+            });
+
+        match (implemented.next(), implemented.next()) {
+            (Some(arguments), None) => Some(arguments),
+            _ => None,
+        }
+    }
+
+    /// Function a call of a trait method runs once `Self` is known, `None` if the function is not
+    /// a method of a trait. The call goes to the function of the impl block, or stays with the
+    /// trait if it provides the method. Returns the name, declared parameter types and type
+    /// arguments of the function
+    pub fn dispatch(
+        &self,
+        function: &GlobalIdentifier,
+        signature: &[DataType],
+        type_arguments: &[DataType],
+    ) -> Option<(GlobalIdentifier, Vec<DataType>, Vec<DataType>)> {
+        let declaration = self.trait_of(function)?;
+        let method = declaration.methods
+            .iter()
+            .map(|method| &method.prototype)
+            .find(|method| {
+                &method.name == function
+                    && method.arguments.iter().map(|argument| &argument.data_type).eq(signature)
+            })?;
+
+        let trait_arguments = &type_arguments[1..=declaration.generics.len()];
+        let (implementation, bindings) = self.find_impl(&declaration.name, &type_arguments[0], trait_arguments)?;
+
+        let Some(function) = implementation.get_implementation(method) else {
+            return Some((function.clone(), signature.to_vec(), type_arguments.to_vec()));
         };
-        if self.sub_modules.contains_key(&name) {
-            Err(CompilerError::DuplicateNamespace(self.path.module(name)))
+
+        // the impl block's type arguments are followed by the method's own
+        let type_arguments = implementation.generics
+            .iter()
+            .map(|parameter| bindings.get(parameter).cloned().unwrap_or(DataType::Never))
+            .chain(type_arguments[declaration.generics.len() + 1..].iter().cloned())
+            .collect();
+        let signature = function.arguments.iter().map(|argument| argument.data_type.clone()).collect();
+
+        Some((function.name.clone(), signature, type_arguments))
+    }
+
+    /// Brings an item or module of another namespace into scope under the given name, imported
+    /// types can be used like the types of this module
+    pub fn register_import(&mut self, name: Identifier, namespace: Namespace, ty: Option<DataType>, trace: Trace) -> CompilerResult<()> {
+        let defined = self.data_types.contains_key(&name)
+            || self.traits.contains_key(&name)
+            || self.sub_modules.contains_key(&name)
+            || self.imports.contains_key(&name);
+        if defined {
+            return Err(CompilerError::ImportConflict(name, trace));
+        }
+
+        if let Some(ty) = ty {
+            self.data_types.insert(name.clone(), ty);
+        }
+        self.imports.insert(name, namespace);
+        Ok(())
+    }
+
+    /// Brings an item of another module into scope for an `import module::*`. Items declared in
+    /// this module and explicit imports shadow glob imports. A name that two glob imports bring
+    /// in from different items is ambiguous, which is only an error once the name is used
+    pub fn register_glob_import(&mut self, namespace: Namespace, ty: Option<DataType>) {
+        let name = namespace.chain.last().expect("Imports name an item").clone();
+
+        if let Some(candidates) = self.ambiguous.get_mut(&name) {
+            if !candidates.contains(&namespace) {
+                candidates.push(namespace);
+            }
+            return;
+        }
+
+        if self.glob_names.contains(&name) {
+            if self.imports[&name] != namespace {
+                let previous = self.imports.remove(&name).expect("Glob imported names are imports");
+                self.data_types.remove(&name);
+                self.glob_names.remove(&name);
+                self.ambiguous.insert(name, vec![previous, namespace]);
+            }
+            return;
+        }
+
+        if self.declares(&name) || self.imports.contains_key(&name) {
+            return;
+        }
+
+        if let Some(ty) = ty {
+            self.data_types.insert(name.clone(), ty);
+        }
+        self.imports.insert(name.clone(), namespace);
+        self.glob_names.insert(name);
+    }
+
+    /// Namespace an imported name refers to
+    pub fn get_import(&self, name: &Identifier) -> Option<&Namespace> {
+        self.imports.get(name)
+    }
+
+    /// Fails if glob imports bring the name into scope from more than one item
+    pub fn check_ambiguous(&self, name: &Identifier, trace: Trace) -> CompilerResult<()> {
+        match self.ambiguous.get(name) {
+            Some(candidates) => Err(CompilerError::AmbiguousImport {
+                name: name.clone(),
+                candidates: candidates.clone(),
+                trace,
+            }),
+            None => Ok(()),
+        }
+    }
+
+    /// Whether an item declared in this module has the name, imported items aside
+    pub fn declares(&self, name: &Identifier) -> bool {
+        (self.data_types.contains_key(name) && !self.imports.contains_key(name))
+            || self.traits.contains_key(name)
+            || self.sub_modules.contains_key(name)
+            || self.has_function(name)
+            || self.globals.contains_key(name)
+    }
+
+    /// Visibility of an item declared in this module, functions are public if any of their
+    /// overloads is. [None] for modules and for names the module does not declare
+    pub fn get_visibility(&self, name: &Identifier) -> Option<Visibility> {
+        if let Some(structure) = self.structs.get(name) {
+            return Some(structure.visibility.clone());
+        }
+        if let Some(enumeration) = self.enums.get(name) {
+            return Some(enumeration.visibility.clone());
+        }
+        if let Some(declaration) = self.traits.get(name) {
+            return Some(declaration.visibility.clone());
+        }
+        if let Some(global) = self.globals.get(name) {
+            return Some(global.visibility.clone());
+        }
+
+        let overloads = self.functions.get(name)?;
+        if overloads.iter().any(|function| function.visibility == Visibility::Public) {
+            Some(Visibility::Public)
         } else {
-            self.sub_modules.insert(name, registry);
-            Ok(())
+            Some(Visibility::Private)
+        }
+    }
+
+    /// Whether code of the module `from` may use an item. Items without `pub` are only visible
+    /// in the module declaring them and in the modules nested inside of it
+    pub fn is_visible(&self, item: &GlobalIdentifier, visibility: &Visibility, from: &Namespace) -> bool {
+        let mut module = item.0.chain.as_slice();
+
+        // associated functions are declared in the namespace of their type
+        if self.is_associated(item) {
+            module = &module[..module.len() - 1];
+        }
+
+        *visibility == Visibility::Public || from.chain.starts_with(module)
+    }
+
+    /// Whether the module `from` may import the item at the absolute path. Items that are not
+    /// declared yet are checked once they are
+    pub fn is_importable(&self, path: &Namespace, from: &Namespace) -> bool {
+        let Some((name, parent)) = path.chain.split_last() else {
+            return true;
+        };
+
+        let item = GlobalIdentifier(Namespace { chain: parent.to_vec() }, name.clone());
+        match self.get_module(&item.0).and_then(|module| module.get_visibility(name)) {
+            Some(visibility) => self.is_visible(&item, &visibility, from),
+            None => true,
+        }
+    }
+
+    /// Names of the items declared in this module, they are what a glob import brings into scope
+    pub fn declared_names(&self) -> Vec<Identifier> {
+        let mut names: Vec<Identifier> = self.data_types
+            .keys()
+            .filter(|name| !self.imports.contains_key(name))
+            .chain(self.traits.keys())
+            .chain(self.sub_modules.keys())
+            .chain(self.functions.keys())
+            .chain(self.globals.keys())
+            .cloned()
+            .collect();
+
+        // the iteration order of the maps is random, the order of glob imports should not be
+        names.sort_by(|a, b| a.as_str().cmp(b.as_str()));
+        names.dedup();
+        names
+    }
+
+    /// Registers the module a source file declares, the modules of the directories the file is
+    /// in are shared with the other files in them and created as needed
+    pub fn register_file_module(&mut self, namespace: &Namespace) -> CompilerResult<()> {
+        let relative = namespace.chain
+            .strip_prefix(self.path.chain.as_slice())
+            .expect("File module outside of the registry");
+        let (name, directories) = relative.split_last().expect("File module without a name");
+
+        let mut registry = self;
+        for directory in directories {
+            let path = registry.path.module(directory.clone());
+            registry = registry.sub_modules.entry(directory.clone()).or_insert_with(|| Self::empty(path));
+        }
+        registry.register_sub_module(name.clone())
+    }
+
+    pub fn register_sub_module(&mut self, name: Identifier) -> CompilerResult<()> {
+        let registry = Self::empty(self.path.module(name.clone()));
+        match self.sub_modules.entry(name) {
+            Entry::Occupied(entry) => Err(CompilerError::DuplicateNamespace(self.path.module(entry.key().clone()))),
+            Entry::Vacant(entry) => {
+                entry.insert(registry);
+                Ok(())
+            }
+        }
+    }
+
+
+    pub fn path(&self) -> &Namespace {
+        &self.path
+    }
+
+    /// Registry of the given absolute namespace, if it is this registry or one nested inside it
+    pub fn get_module(&self, namespace: &Namespace) -> Option<&ModuleRegistry> {
+        let relative = namespace.chain.strip_prefix(self.path.chain.as_slice())?;
+        relative.iter().try_fold(self, |registry, name| registry.sub_modules.get(name))
+    }
+
+    pub fn get_module_mut(&mut self, namespace: &Namespace) -> Option<&mut ModuleRegistry> {
+        let relative = namespace.chain.strip_prefix(self.path.chain.as_slice())?;
+        relative.iter().try_fold(self, |registry, name| registry.sub_modules.get_mut(name))
+    }
+
+    /// Registers a function, functions can be overloaded as long as their argument types differ
+    pub fn register_function(&mut self, prototype: FunctionPrototype) -> CompilerResult<()> {
+        let overloads = self.functions.entry(prototype.name.1.clone()).or_default();
+
+        if overloads.contains(&prototype) {
+            return Err(CompilerError::DuplicateFunction(
+                prototype.name.clone(),
+                prototype.trace.expect("Registered function without a trace"),
+            ));
         }
+        overloads.insert(prototype);
+        Ok(())
     }
 
+    /// Registers a variable declared at the top level of this module
+    pub fn register_global(&mut self, global: GlobalVariable) -> CompilerResult<()> {
+        match self.globals.entry(global.name.1.clone()) {
+            Entry::Occupied(_) => Err(CompilerError::DuplicateGlobal(global.name, global.trace)),
+            Entry::Vacant(entry) => {
+                entry.insert(global);
+                Ok(())
+            }
+        }
+    }
 
-    pub fn register_function_type(&mut self, name: GlobalIdentifier) {}
+    /// Looks up a global by its absolute name, the registry has to contain its module
+    pub fn get_global(&self, name: &GlobalIdentifier) -> Option<&GlobalVariable> {
+        self.get_module(&name.0)?.globals.get(&name.1)
+    }
+
+    pub fn get_global_mut(&mut self, name: &GlobalIdentifier) -> Option<&mut GlobalVariable> {
+        self.get_module_mut(&name.0)?.globals.get_mut(&name.1)
+    }
 
+    /// Overload of the function that takes arguments of the given types, the type parameters of
+    /// generic overloads are inferred from them
     pub fn get_function_by_identifier(
         &self,
-        name: Identifier,
-        arguments: Vec<DataType>,
-    ) -> CompilerResult<Option<&FunctionPrototype>> {
-        Ok(None)
+        name: &Identifier,
+        arguments: &[DataType],
+    ) -> Option<&FunctionPrototype> {
+        self.functions.get(name)?.iter().find(|function| {
+            let mut bindings = HashMap::new();
+
+            function.arguments.len() == arguments.len()
+                && function.arguments
+                    .iter()
+                    .zip(arguments)
+                    .all(|(arg, ty)| arg.data_type.bind(ty, &function.generics, &mut bindings))
+        })
+    }
+
+    /// Registered declaration of a function, looked up by its absolute name and argument types
+    pub fn get_function(&self, function: &FunctionPrototype) -> Option<&FunctionPrototype> {
+        self.get_module(&function.name.0)?.functions.get(&function.name.1)?.get(function)
+    }
+
+    /// Sets the return type of a function that does not write it out, once it is inferred
+    pub fn infer_return_type(&mut self, function: &FunctionPrototype, returns: DataType) {
+        let overloads = self
+            .get_module_mut(&function.name.0)
+            .and_then(|module| module.functions.get_mut(&function.name.1))
+            .expect("Function was registered");
+
+        overloads.replace(FunctionPrototype { returns: Some(returns), ..function.clone() });
+    }
+
+    pub fn has_function(&self, name: &Identifier) -> bool {
+        self.functions.contains_key(name)
+    }
+
+    pub fn has_global(&self, name: &Identifier) -> bool {
+        self.globals.contains_key(name)
+    }
+
+    /// Every overload of a function, ordered by their number of arguments
+    pub fn get_functions_named(&self, name: &Identifier) -> Vec<&FunctionPrototype> {
+        let mut functions: Vec<&FunctionPrototype> = self.functions
+            .get(name)
+            .into_iter()
+            .flatten()
+            .collect();
+
+        functions.sort_by_key(|function| function.arguments.len());
+        functions
+    }
+
+    /// Type declared in or imported into this module, generic types have their own parameters
+    /// as arguments
+    pub fn get_type(&self, name: &Identifier) -> Option<&DataType> {
+        self.data_types.get(name)
+    }
+
+    /// Resolves a type written in this module, `scope` holds the names only valid inside of the
+    /// item the type is written in. Errors point at the named type that could not be resolved
+    pub fn resolve_type(&self, ty: &UnvalidatedType, scope: &TypeScope) -> CompilerResult<DataType> {
+        Ok(match ty {
+            UnvalidatedType::Type(name, trace) => self.resolve_named(name, &[], scope, *trace)?,
+            UnvalidatedType::Template { base, template_arguments, trace } => {
+                let arguments = template_arguments
+                    .iter()
+                    .map(|argument| self.resolve_type(argument, scope))
+                    .collect::<CompilerResult<Vec<_>>>()?;
+
+                self.resolve_named(base, &arguments, scope, *trace)?
+            }
+            UnvalidatedType::Reference(ty) => DataType::Pointer(Box::new(self.resolve_type(ty, scope)?)),
+            UnvalidatedType::Array(ty) => DataType::Array(Box::new(self.resolve_type(ty, scope)?)),
+            UnvalidatedType::Unit => DataType::Unit,
+            UnvalidatedType::Implicit => unreachable!("Implicit types have to be inferred"),
+        })
+    }
+
+    /// Resolves a type name given the type arguments written after it
+    fn resolve_named(
+        &self,
+        name: &Identifier,
+        arguments: &[DataType],
+        scope: &TypeScope,
+        trace: Trace,
+    ) -> CompilerResult<DataType> {
+        let expect_arguments = |expected: usize| {
+            if arguments.len() == expected {
+                Ok(())
+            } else {
+                Err(CompilerError::TypeArgumentCount { ty: name.clone(), expected, found: arguments.len(), trace })
+            }
+        };
+
+        if name.as_str() == SELF_TYPE {
+            if let Some(self_type) = &scope.self_type {
+                expect_arguments(0)?;
+                return Ok(self_type.clone());
+            }
+        }
+
+        if scope.parameters.contains(name) {
+            expect_arguments(0)?;
+            return Ok(DataType::Parameter(name.clone()));
+        }
+
+        if let Ok(primitive) = PrimitiveType::try_from(name.as_str()) {
+            expect_arguments(0)?;
+            return Ok(DataType::Primitive(primitive));
+        }
+
+        match self.data_types.get(name) {
+            Some(DataType::Structure(ty, parameters)) => {
+                expect_arguments(parameters.len())?;
+                Ok(DataType::Structure(ty.clone(), arguments.to_vec()))
+            }
+            Some(DataType::Enumeration(ty, parameters)) => {
+                expect_arguments(parameters.len())?;
+                Ok(DataType::Enumeration(ty.clone(), arguments.to_vec()))
+            }
+            Some(ty) => {
+                expect_arguments(0)?;
+                Ok(ty.clone())
+            }
+            None => {
+                self.check_ambiguous(name, trace)?;
+                Err(CompilerError::UnknownType(name.clone(), trace))
+            }
+        }
     }
 
-    pub fn search_for(&self, search: FunctionPrototype) {}
+    #[allow(dead_code)]
+    pub fn search_for(&self, _search: FunctionPrototype) {}
 
     pub fn create_identifier(&self, name: Identifier) -> GlobalIdentifier {
         GlobalIdentifier(self.path.clone(), name)
@@ -1,4 +1,33 @@
+use serde::Serialize;
+use crate::file::identifier::{GlobalIdentifier, Identifier};
+use crate::file::trace::Trace;
+use crate::ir::visibility::Visibility;
+use crate::validation::data_type::DataType;
 
-#[derive(Debug, Hash, PartialEq)]
+#[derive(Debug, Serialize)]
 pub struct StructPrototype {
-}
\ No newline at end of file
+    pub name: GlobalIdentifier,
+
+    /// Type parameters the declared types can refer to
+    pub generics: Vec<Identifier>,
+
+    /// Fields in the order they were declared in
+    pub fields: Vec<StructField>,
+    pub visibility: Visibility,
+
+    #[serde(skip)]
+    pub trace: Trace,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct StructField {
+    pub name: Identifier,
+    pub data_type: DataType,
+    pub visibility: Visibility,
+}
+
+impl StructPrototype {
+    pub fn get_field(&self, name: &Identifier) -> Option<&StructField> {
+        self.fields.iter().find(|field| &field.name == name)
+    }
+}
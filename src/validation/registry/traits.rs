@@ -0,0 +1,128 @@
+use std::collections::HashMap;
+use serde::Serialize;
+use crate::file::identifier::{GlobalIdentifier, Identifier, Namespace};
+use crate::file::trace::Trace;
+use crate::ir::visibility::Visibility;
+use crate::validation::data_type::DataType;
+use crate::validation::registry::functions::FunctionPrototype;
+
+#[derive(Debug, Serialize)]
+pub struct TraitPrototype {
+    pub name: GlobalIdentifier,
+
+    /// Type parameters of the trait, the implementing type `Self` is not one of them
+    pub generics: Vec<Identifier>,
+
+    /// Methods in the order they were declared in
+    pub methods: Vec<TraitMethod>,
+    pub visibility: Visibility,
+
+    #[serde(skip)]
+    pub trace: Trace,
+}
+
+#[derive(Debug, Serialize)]
+pub struct TraitMethod {
+    /// Signature registered in the namespace of the trait, its type parameters are `Self`, the
+    /// ones of the trait and its own in that order
+    pub prototype: FunctionPrototype,
+
+    /// Whether the trait has a body for implementations that leave the method out
+    pub provided: bool,
+}
+
+/// `impl Trait for Type` block
+#[derive(Debug, Serialize)]
+pub struct TraitImpl {
+    pub trait_name: GlobalIdentifier,
+
+    /// Type parameters introduced by `impl<T>`
+    pub generics: Vec<Identifier>,
+
+    /// Type arguments of the trait, eg. `T` of `impl<T> Index<T> for Vec<T>`
+    pub arguments: Vec<DataType>,
+    pub target: DataType,
+
+    /// Module the impl block is written in
+    pub module: Namespace,
+    pub methods: Vec<ImplementedMethod>,
+
+    #[serde(skip)]
+    pub trace: Trace,
+}
+
+/// Method of a trait together with the function of an impl block implementing it
+#[derive(Debug, Serialize)]
+pub struct ImplementedMethod {
+    pub declaration: FunctionPrototype,
+
+    /// Function registered in the namespace of the implementing type
+    pub implementation: FunctionPrototype,
+}
+
+impl TraitPrototype {
+    pub fn get_method(&self, name: &Identifier) -> Option<&TraitMethod> {
+        self.methods.iter().find(|method| &method.prototype.name.1 == name)
+    }
+}
+
+impl TraitImpl {
+    /// Bindings of the type parameters of the impl block that make it implement the trait with
+    /// the given type arguments for `ty`
+    pub fn matches(&self, ty: &DataType, arguments: &[DataType]) -> Option<HashMap<Identifier, DataType>> {
+        let mut bindings = HashMap::new();
+
+        let matches = self.target.bind(ty, &self.generics, &mut bindings)
+            && self.arguments.len() == arguments.len()
+            && self.arguments
+                .iter()
+                .zip(arguments)
+                .all(|(declared, argument)| declared.bind(argument, &self.generics, &mut bindings));
+
+        matches.then_some(bindings)
+    }
+
+    /// Whether the impl block implements the trait for `ty`, with any type arguments
+    pub fn is_for(&self, ty: &DataType) -> bool {
+        self.target.bind(ty, &self.generics, &mut HashMap::new())
+    }
+
+    /// Whether some type with some type arguments is implemented by both impl blocks
+    pub fn overlaps(&self, other: &TraitImpl) -> bool {
+        let unifiable = |a: &DataType, b: &DataType| unifiable(a, &self.generics, b, &other.generics);
+
+        self.trait_name == other.trait_name
+            && unifiable(&self.target, &other.target)
+            && self.arguments.iter().zip(&other.arguments).all(|(a, b)| unifiable(a, b))
+    }
+
+    /// Implementation of a method of the trait, if the impl block does not leave it out
+    pub fn get_implementation(&self, declaration: &FunctionPrototype) -> Option<&FunctionPrototype> {
+        self.methods
+            .iter()
+            .find(|method| &method.declaration == declaration)
+            .map(|method| &method.implementation)
+    }
+}
+
+/// Whether both types can be made equal by replacing their type parameters. A parameter can stand
+/// for different types at different places, so types like `Pair<T, T>` and `Pair<i32, bool>` are
+/// considered unifiable as well
+fn unifiable(a: &DataType, a_parameters: &[Identifier], b: &DataType, b_parameters: &[Identifier]) -> bool {
+    match (a, b) {
+        (DataType::Parameter(name), _) if a_parameters.contains(name) => true,
+        (_, DataType::Parameter(name)) if b_parameters.contains(name) => true,
+        (DataType::Pointer(a), DataType::Pointer(b))
+        | (DataType::Array(a), DataType::Array(b)) => unifiable(a, a_parameters, b, b_parameters),
+        (DataType::Structure(a, a_arguments), DataType::Structure(b, b_arguments))
+        | (DataType::Enumeration(a, a_arguments), DataType::Enumeration(b, b_arguments)) => {
+            a == b
+                && a_arguments.len() == b_arguments.len()
+                && a_arguments
+                    .iter()
+                    .zip(b_arguments)
+                    .all(|(a, b)| unifiable(a, a_parameters, b, b_parameters))
+        }
+        _ => a == b,
+    }
+}
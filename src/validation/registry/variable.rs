@@ -1,3 +1,4 @@
+use serde::Serialize;
 use crate::file::identifier::Identifier;
 use crate::validation::data_type::DataType;
 
@@ -8,7 +9,7 @@ use crate::validation::data_type::DataType;
 //     pub mutable: bool,
 // }
 //
-#[derive(Debug, Hash, Eq, PartialEq, Clone)]
+#[derive(Debug, Hash, Eq, PartialEq, Clone, Serialize)]
 pub struct VariableInfo {
     pub name: Identifier,
     pub data_type: DataType,
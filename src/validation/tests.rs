@@ -1,18 +1,1418 @@
+use std::path::Path;
+use crate::file::identifier::Namespace;
+use crate::file::source_file::SourceFile;
+use crate::file::source_map::{FileId, SourceMap};
+use crate::parser::ast::operations::UnaryOperator;
+use crate::parser::ast::top_level::UnvalidatedTopLevel;
+use crate::parser::expansion::expand_macros;
+use crate::parser::parser::Parser;
+use crate::validation::data_type::{DataType, PrimitiveType};
+use crate::validation::error::CompilerError;
+use crate::validation::registry::expression::{Expression, FormatArgument, FunctionStatement, MatchArm, Pattern};
+use crate::validation::monomorphize::{Instances, Layout, Monomorphizer};
 use super::module::Module;
 
+fn parse_from(sources: &mut SourceMap, name: &str, source: &str) -> (FileId, Vec<UnvalidatedTopLevel>) {
+    parse_file(sources, SourceFile::with_name(name.into(), format!("{name}.gs"), source.into()))
+}
+
+/// Parses a file as if it was read from `path`, the directories it is in become modules
+fn parse_path(sources: &mut SourceMap, path: &str, source: &str) -> (FileId, Vec<UnvalidatedTopLevel>) {
+    parse_file(sources, SourceFile::from_path(Path::new(path), Path::new("."), source.into()))
+}
+
+fn parse_file(sources: &mut SourceMap, file: SourceFile) -> (FileId, Vec<UnvalidatedTopLevel>) {
+    let file = sources.add(file);
+    let (tokens, lex_errors) = crate::lexer::tokenize(&sources[file]);
+    assert_eq!(lex_errors, vec![], "Failed to tokenize");
+
+    let (mut statements, errors) = Parser::new(file, tokens).parse();
+    assert_eq!(errors, vec![], "Failed to parse");
+    assert_eq!(expand_macros(&mut statements), vec![], "Failed to expand macros");
 
+    (file, statements)
+}
+
+#[test]
+fn module() {
+    let mut sources = SourceMap::new();
+    let modules = [
+        parse_from(&mut sources, "main", "fun main() {}"),
+        parse_from(&mut sources, "math", "pub fun sqrt(x: f32): f32 => x"),
+    ];
 
-fn parse_from(source: &str) -> (ParserResult<Vec<UnvalidatedTopLevel>>, Box<dyn Fn() -> Trace>) {
-    let source = SourceFile::new(source).rc();
-    let trace = source.trace(0..0);
-    let tokens = crate::lexer::tokenize(Rc::clone(&source));
+    let result = Module::new().process_modules(&sources, &modules);
 
-    let trace = move || trace.clone();
-    (Parser::new(source, tokens).parse(), Box::new(trace))
+    assert!(result.is_ok());
 }
 
 #[test]
-fn module()  {
-    let module = Module::new();
+fn duplicate_module() {
+    let mut sources = SourceMap::new();
+    let modules = [
+        parse_from(&mut sources, "math", "fun a() {}"),
+        parse_from(&mut sources, "math", "fun b() {}"),
+    ];
+
+    let result = Module::new().process_modules(&sources, &modules);
+
+    match result.as_ref().map_err(Vec::as_slice) {
+        Err([CompilerError::DuplicateNamespace(namespace)]) => {
+            assert_eq!(namespace, &Namespace::global().module("math".into()));
+        }
+        other => panic!("Expected duplicate namespace error, got {other:?}"),
+    }
+}
+
+fn validate(source: &str) -> Result<Module, Vec<CompilerError>> {
+    let mut sources = SourceMap::new();
+    let modules = [parse_from(&mut sources, "main", source)];
+
+    let mut module = Module::new();
+    module.process_modules(&sources, &modules).map(|_| module)
+}
+
+fn error_codes(source: &str) -> Vec<&'static str> {
+    match validate(source) {
+        Ok(_) => vec![],
+        Err(errors) => errors.iter().map(CompilerError::code).collect(),
+    }
+}
+
+#[test]
+fn type_check_function() {
+    let module = validate(r#"
+        fun add(a: i32, b: i32): i32 => a + b
+
+        fun main(argc: usize): i32 {
+            let mut total: u64 = 0
+            total += 4
+            if argc == 0 {
+                return add(1, 2)
+            }
+            return 0
+        }
+    "#).expect("Valid program");
+
+    assert_eq!(module.functions().len(), 2);
+}
+
+#[test]
+fn type_errors() {
+    assert_eq!(error_codes("fun f(): i32 => true"), vec!["E0007"]);
+    assert_eq!(error_codes("fun f() { let x = 1 x = 2 }"), vec!["E0013"]);
+    assert_eq!(error_codes("fun f() { let x = y + 1 let z = x }"), vec!["E0005"]);
+    assert_eq!(error_codes("fun f(a: u32) { let b = -a }"), vec!["E0009"]);
+    assert_eq!(error_codes("fun f() { g(1) }"), vec!["E0006"]);
+    assert_eq!(error_codes("fun f(a: i32) {} fun g() { f(1, 2) }"), vec!["E0011"]);
+    assert_eq!(error_codes("fun f(a: banana) {}"), vec!["E0004"]);
+    assert_eq!(error_codes("fun f() {} fun f() {}"), vec!["E0003"]);
+}
+
+#[test]
+fn inferred_return_types() {
+    let module = validate(r#"
+        let LIMIT = twice(limit())
+
+        fun twice(x: i32) => x * 2
+        fun limit() => 10
+        fun capped(): i32 => LIMIT
+        fun origin() => make Point { x: 0.0 y: 0.0 }
+        fun length(point: Point) => point.x + origin().y
+
+        struct Point { x: f32 y: f32 }
+    "#).expect("Valid program");
+
+    let returns = |name: &str| {
+        let function = module.functions().iter().find(|f| f.prototype.name.1 == name.into()).unwrap();
+        function.prototype.return_type().to_string()
+    };
+    assert_eq!(returns("twice"), "i32");
+    assert_eq!(returns("origin"), "Point");
+    assert_eq!(returns("length"), "f32");
+
+    assert_eq!(error_codes("fun f() => 1 fun g(): bool => f()"), vec!["E0007"]);
+    assert_eq!(error_codes("fun f(n: i32) => f(n - 1)"), vec!["E0062"]);
+    assert_eq!(error_codes("fun f(x: bool) => if x { return 1 } else { 2 }"), vec!["E0062"]);
+    assert_eq!(error_codes("trait T { fun f(self) => 1 }"), vec!["E0062"]);
+    assert_eq!(error_codes("trait T { fun f(self): i32 } struct A {} impl T for A { fun f(self) => 1 }"), vec!["E0062"]);
+}
+
+#[test]
+fn integer_literals() {
+    validate(r#"
+        fun f(x: i32): i32 {
+            let big: u32 = 4000000000
+            let max: u64 = 18446744073709551615
+            let min: i64 = -9223372036854775808
+            let small = -2147483648
+            let scale: f32 = 2
+            let offset: f64 = -1
+            match x {
+                -2147483648 => 1
+                _ => small
+            }
+        }
+    "#).expect("Valid program");
+
+    assert_eq!(error_codes("fun f() { let x = 2147483648 }"), vec!["E0059"]);
+    assert_eq!(error_codes("fun f() { let x: u32 = 4294967296 }"), vec!["E0059"]);
+    assert_eq!(error_codes("fun f() { let x = -2147483649 }"), vec!["E0059"]);
+    assert_eq!(error_codes("fun f() { let x: u32 = -1 }"), vec!["E0009"]);
+
+    let errors = validate("fun f() { let x: i64 = 9223372036854775808 }").unwrap_err();
+    assert_eq!(errors[0].to_string(), "literal `9223372036854775808` does not fit into the type `i64`");
+}
+
+#[test]
+fn format_string() {
+    let module = validate(r#"
+        fun count(): usize => 3
+        fun main() {
+            let name = "goose"
+            let x = 1.5
+            let message = "{name} has {count() * 2} eggs weighing {x} {{kg}}"
+        }
+    "#).expect("Valid format string");
+
+    let main = module.functions().iter().find(|f| f.prototype.name.1 == "main".into()).unwrap();
+    let Expression::Scope(statements) = &main.body else { panic!("Expected a block") };
+
+    let FunctionStatement::Let { initial: Some(Expression::Format(arguments)), .. } = &statements[2] else {
+        panic!("Expected a format string, got {:?}", statements[2]);
+    };
+
+    let literals: Vec<&str> = arguments
+        .iter()
+        .filter_map(|argument| match argument {
+            FormatArgument::Literal(text) => Some(text.as_str()),
+            FormatArgument::Value { .. } => None,
+        })
+        .collect();
+    assert_eq!(literals, vec![" has ", " eggs weighing ", " {kg}"]);
+
+    let types: Vec<String> = arguments
+        .iter()
+        .filter_map(|argument| match argument {
+            FormatArgument::Value { ty, .. } => Some(ty.to_string()),
+            FormatArgument::Literal(_) => None,
+        })
+        .collect();
+    assert_eq!(types, vec!["ref[char]", "usize", "f32"]);
+}
+
+#[test]
+fn format_string_errors() {
+    let source = r#"
+        fun nothing() {}
+        fun main() {
+            let a = "{missing} {nothing()} {}"
+        }
+    "#;
+
+    assert_eq!(error_codes(source), vec!["E0005", "E0016", "E0017"]);
+
+    let errors = validate(source).unwrap_err();
+    let offsets: Vec<_> = errors.iter().map(|err| err.trace().unwrap().range()).collect();
+    let start = source.find("{missing}").unwrap();
+    assert_eq!(offsets, vec![
+        start + 1..start + 8,
+        start + 11..start + 20,
+        start + 22..start + 24,
+    ]);
+}
+
+#[test]
+fn impl_block() {
+    let module = validate(r#"
+        struct Vector2 {
+            x: f32
+            y: f32
+        }
+
+        impl Vector2 {
+            pub fun scale(): f32 => 2.0
+            pub fun scaled(ref[self], by: f32): f32 => Self::scale() * by
+            fun all([self]): [Self] => self
+        }
+
+        fun main(v: ref[Vector2]): f32 => Vector2::scaled(v, 1.5)
+    "#).expect("Valid program");
+
+    let scaled = module.functions().iter().find(|f| f.prototype.name.1 == "scaled".into()).unwrap();
+    assert_eq!(scaled.prototype.name.0, Namespace::global().module("main".into()).module("Vector2".into()));
+    assert_eq!(scaled.prototype.arguments[0].data_type.to_string(), "ref[Vector2]");
+}
+
+#[test]
+fn impl_block_errors() {
+    assert_eq!(error_codes("impl Missing {}"), vec!["E0004"]);
+    assert_eq!(error_codes("impl [i32] { fun f() {} }"), vec!["E0020"]);
+    assert_eq!(error_codes("impl<T> T { fun f() {} }"), vec!["E0020"]);
+    assert_eq!(error_codes("struct A {} struct A {}"), vec!["E0019"]);
+    assert_eq!(error_codes("struct A {} impl A { fun f() {} } impl A { fun f() {} }"), vec!["E0003"]);
+    assert_eq!(error_codes("fun f(self) {}"), vec!["E0004"]);
+    assert_eq!(error_codes("struct A {} fun f() { A::missing() }"), vec!["E0006"]);
+}
+
+#[test]
+fn primitive_impls() {
+    let module = validate(r#"
+        impl i32 {
+            pub fun double(self): i32 => self * 2
+            pub fun zero(): i32 => 0
+        }
+
+        impl<T> ref[T] {
+            pub fun address(self): ref[T] => self
+        }
+
+        fun main(value: ref[i32], values: ref[ref[i32]]): i32 {
+            let outer: ref[ref[i32]] = values.address()
+            value.double() + i32::zero() + 1.double()
+        }
+    "#).expect("Valid program");
+
+    let double = module.functions().iter().find(|f| f.prototype.name.1 == "double".into()).unwrap();
+    assert_eq!(double.prototype.name.0, Namespace::global().module("i32".into()));
+
+    assert_eq!(error_codes("impl i32 { pub fun double(self): i32 => self * 2 } fun f(): i64 => 1.double()"), vec!["E0007"]);
+    assert_eq!(error_codes("impl bool { pub fun not(self): bool => !self } fun f(v: i32) => v.not()"), vec!["E0045"]);
+}
+
+#[test]
+fn construction() {
+    let module = validate(r#"
+        struct Vector2 {
+            x: f64,
+            y: f64,
+        }
+
+        impl Vector2 {
+            fun new(x: f64, y: f64): Self => make Vector2 { x y }
+            fun boxed(): ref[Vector2] => alloc Vector2 { y: 1.0, x: 2.5 }
+        }
+    "#).expect("Valid program");
+
+    let boxed = module.functions().iter().find(|f| f.prototype.name.1 == "boxed".into()).unwrap();
+    let Expression::Alloc(construction) = &boxed.body else {
+        panic!("Expected a heap construction, got {:?}", boxed.body);
+    };
+    let Expression::Construct { fields, .. } = construction.as_ref() else {
+        panic!("Expected a construction, got {construction:?}");
+    };
+
+    // values are reordered to the declaration order of the fields
+    assert!(matches!(fields.as_slice(), [Expression::F64Literal(x), Expression::F64Literal(y)] if *x == 2.5 && *y == 1.0));
+}
+
+#[test]
+fn construction_errors() {
+    let point = "struct Point { x: i32 y: i32 z: i32 }";
+    let codes = |value: &str| error_codes(&format!("{point} fun f() {{ let value = {value} }}"));
+
+    assert_eq!(codes("make Point { x: 1 }"), vec!["E0021"]);
+    assert_eq!(codes("make Point { x: 1 y: 2 z: 3 x: 4 }"), vec!["E0022"]);
+    assert_eq!(codes("make Point { x: 1 y: 2 z: 3 w: 4 }"), vec!["E0023"]);
+    assert_eq!(codes("make Point { x: true y: 2 z: 3 }"), vec!["E0007"]);
+    assert_eq!(codes("make Point { x: 1 y: 1 w: 1 }"), vec!["E0023", "E0021"]);
+    assert_eq!(codes("make i32 {}"), vec!["E0024"]);
+    assert_eq!(codes("make Missing {}"), vec!["E0004"]);
+    assert_eq!(error_codes(&format!("{point} fun f(): Point => alloc Point {{ x: 1 y: 2 z: 3 }}")), vec!["E0007"]);
+
+    assert_eq!(error_codes("struct A { a: i32 a: i32 }"), vec!["E0022"]);
+    assert_eq!(error_codes("struct A { b: B }"), vec!["E0004"]);
+
+    let source = format!("{point} fun f() {{ let value = make Point {{ y: 1 }} }}");
+    let errors = validate(&source).unwrap_err();
+    assert_eq!(errors[0].to_string(), "missing field(s) 'x', 'z' in construction of `Point`");
+}
+
+#[test]
+fn enum_variants() {
+    let module = validate(r#"
+        pub enum Shape {
+            Circle(f64),
+            Rect { width: f64, height: f64 },
+            Empty
+        }
+
+        impl Shape {
+            fun square(): Self => make Shape::Rect { width: 1.0 height: 1.0 }
+        }
+
+        fun circle(r: f64): Shape => Shape::Circle(r)
+        fun empty(): Shape => Shape::Empty
+        fun boxed(): ref[Shape] => alloc Shape::Rect { height: 2.0 width: 1.0 }
+    "#).expect("Valid program");
+
+    let circle = module.functions().iter().find(|f| f.prototype.name.1 == "circle".into()).unwrap();
+    assert!(matches!(&circle.body, Expression::Variant { variant, fields, .. }
+        if variant.as_str() == "Circle" && matches!(fields.as_slice(), [Expression::LocalVariableReference(_)])));
+
+    let empty = module.functions().iter().find(|f| f.prototype.name.1 == "empty".into()).unwrap();
+    assert_eq!(empty.prototype.return_type().to_string(), "Shape");
+    assert!(matches!(&empty.body, Expression::Variant { fields, .. } if fields.is_empty()));
+}
+
+#[test]
+fn enum_errors() {
+    let shape = "enum Shape { Circle(f64) Rect { width: f64 } Empty }";
+    let codes = |value: &str| error_codes(&format!("{shape} fun f() {{ let value = {value} }}"));
+
+    assert_eq!(codes("Shape::Circle(1.0)"), Vec::<&str>::new());
+    assert_eq!(codes("Shape::Circle(1.0, 2.0)"), vec!["E0011"]);
+    assert_eq!(codes("Shape::Circle(true)"), vec!["E0007"]);
+    assert_eq!(codes("Shape::Circle"), vec!["E0026"]);
+    assert_eq!(codes("Shape::Empty(1)"), vec!["E0026"]);
+    assert_eq!(codes("Shape::Rect(1.0)"), vec!["E0026"]);
+    assert_eq!(codes("make Shape::Circle { width: 1.0 }"), vec!["E0026"]);
+    assert_eq!(codes("make Shape::Rect {}"), vec!["E0021"]);
+    assert_eq!(codes("make Shape::Square {}"), vec!["E0027"]);
+    assert_eq!(codes("make Shape {}"), vec!["E0024"]);
+    assert_eq!(codes("Shape::Square(1.0)"), vec!["E0006"]);
+
+    assert_eq!(error_codes("enum A { B B }"), vec!["E0025"]);
+    assert_eq!(error_codes("enum A { B(Missing) }"), vec!["E0004"]);
+    assert_eq!(error_codes("enum A { B } struct A {}"), vec!["E0019"]);
+    assert_eq!(error_codes("enum Option<T, T> { Some(T) None }"), vec!["E0030"]);
+
+    let errors = validate(&format!("{shape} fun f() {{ let value = Shape::Circle }}")).unwrap_err();
+    assert_eq!(errors[0].diagnostic().help, vec!["construct it as `Shape::Circle(_)`"]);
+}
+
+#[test]
+fn generics() {
+    let module = validate(r#"
+        struct Pair<A, B> { first: A second: B }
+        enum Option<T> { Some(T) None }
+
+        impl<T> Option<T> {
+            fun or(self, other: T): T => other
+            fun wrap(value: T): Self => Option::Some(value)
+        }
+
+        fun id<T>(value: T): T => value
+
+        fun main(): i64 {
+            let pair = make Pair { first: 1, second: 'c' }
+            let explicit = make Pair<u32, bool> { first: 1, second: false }
+            let none: Option<u64> = Option::None
+            let some: Option<i64> = Option::wrap(2)
+            Option::or(some, id(3))
+        }
+    "#).expect("Valid program");
+
+    let main = module.functions().iter().find(|f| f.prototype.name.1 == "main".into()).unwrap();
+    let Expression::Scope(statements) = &main.body else {
+        panic!("Expected a block, got {:?}", main.body);
+    };
+
+    let types: Vec<String> = statements
+        .iter()
+        .filter_map(|statement| match statement {
+            FunctionStatement::Let { variable, .. } => Some(main.locals.get(variable).unwrap().data_type.to_string()),
+            _ => None,
+        })
+        .collect();
+    assert_eq!(types, vec!["Pair<i32, char>", "Pair<u32, bool>", "Option<u64>", "Option<i64>"]);
+
+    // the return type is known, so `T` is inferred as `i64` before the arguments are checked
+    assert!(matches!(statements.last(), Some(FunctionStatement::Expression(Expression::FunctionCall { type_arguments, arguments, .. }))
+        if type_arguments == &vec![DataType::Primitive(PrimitiveType::I64)]
+            && matches!(&arguments[1], Expression::FunctionCall { type_arguments, .. }
+                if type_arguments == &vec![DataType::Primitive(PrimitiveType::I64)])));
+}
+
+#[test]
+fn generic_errors() {
+    let types = "struct Pair<A, B> { first: A second: B } enum Option<T> { Some(T) None }";
+    let codes = |source: &str| error_codes(&format!("{types} {source}"));
+
+    assert_eq!(codes("fun f(pair: Pair<i32>) {}"), vec!["E0028"]);
+    assert_eq!(codes("fun f(pair: Pair) {}"), vec!["E0028"]);
+    assert_eq!(codes("fun f(value: i32<bool>) {}"), vec!["E0028"]);
+    assert_eq!(codes("fun f<T>(value: T<bool>) {}"), vec!["E0028"]);
+    assert_eq!(codes("fun f(value: T) {}"), vec!["E0004"]);
+    assert_eq!(codes("impl Option<T> {}"), vec!["E0029"]);
+    assert_eq!(codes("impl<T> Option<T> { fun f<T>() {} }"), vec!["E0030"]);
+    assert_eq!(codes("struct Twice<T, T> {}"), vec!["E0030"]);
+    assert_eq!(codes("fun f() { let none = Option::None }"), vec!["E0031"]);
+    assert_eq!(codes("fun f<T>(): T => f() fun g() { let value = f() }"), vec!["E0031"]);
+    assert_eq!(codes("fun f<T>(a: T, b: T) {} fun g() { f(1, true) }"), vec!["E0007"]);
+    assert_eq!(codes("fun f<T>(value: T): i32 => value"), vec!["E0007"]);
+    assert_eq!(codes("fun f<T>(value: T): T => value + value"), vec!["E0008"]);
+    assert_eq!(codes("fun f() { let pair: Pair<i32, bool> = make Pair { first: true, second: 1 } }"), vec!["E0007", "E0007"]);
+
+    let errors = validate(&format!("{types} impl Option<T> {{}}")).unwrap_err();
+    assert_eq!(errors[0].diagnostic().help, vec!["declare it as a type parameter of the impl block: `impl<T>`"]);
+
+    let offset = |source: &str, ty: &str| {
+        let source = format!("{types} {source}");
+        let errors = validate(&source).unwrap_err();
+        let start = source.find(ty).unwrap();
+        assert_eq!(errors[0].trace().unwrap().range(), start..start + ty.len());
+    };
+    offset("struct W { v: Pair<i32> }", "Pair<i32>");
+    offset("fun f(values: [Missing]) {}", "Missing");
+}
+
+fn instantiate(source: &str) -> Result<Instances, Vec<&'static str>> {
+    let module = validate(source).map_err(|errors| errors.iter().map(CompilerError::code).collect::<Vec<_>>())?;
+    Monomorphizer::run(&module).map_err(|err| vec![err.code()])
+}
+
+#[test]
+fn monomorphization() {
+    let instances = instantiate(r#"
+        struct Pair<A, B> { first: A second: B }
+        enum Option<T> { Some(T) None }
+
+        fun id<T>(value: T): T => value
+        fun wrap<T>(value: T): Option<T> => Option::Some(id(value))
+        fun unused<T>(value: T): T => value
+
+        fun main() {
+            let a = id(1)
+            let b = id(true)
+            let c = id(2)
+            let some = wrap("text")
+            let pair = make Pair { first: 'c', second: some }
+        }
+    "#).expect("Valid program");
+
+    let functions: Vec<String> = instances.functions
+        .iter()
+        .map(|instance| {
+            let arguments: Vec<String> = instance.type_arguments.iter().map(DataType::to_string).collect();
+            format!("{}<{}>", instance.function.prototype.name.1, arguments.join(", "))
+        })
+        .collect();
+
+    // every instance is created once, no matter how often it is used
+    let mut sorted = functions.clone();
+    sorted.sort();
+    assert_eq!(sorted, vec!["id<bool>", "id<i32>", "id<ref[char]>", "main<>", "wrap<ref[char]>"]);
+    assert_eq!(functions[0], "main<>");
+
+    let wrap = instances.functions.iter().find(|instance| instance.function.prototype.name.1 == "wrap".into()).unwrap();
+    assert_eq!(wrap.function.prototype.return_type().to_string(), "Option<ref[char]>");
+    assert!(matches!(&wrap.function.body, Expression::Variant { type_arguments, fields, .. }
+        if type_arguments == &vec![DataType::string()]
+            && matches!(&fields[0], Expression::FunctionCall { type_arguments, .. } if type_arguments == &vec![DataType::string()])));
+
+    let types: Vec<String> = instances.types.iter().map(|instance| instance.ty.to_string()).collect();
+    assert_eq!(types.len(), 2);
+    assert!(types.contains(&"Option<ref[char]>".to_string()));
+    assert!(types.contains(&"Pair<char, Option<ref[char]>>".to_string()));
+
+    let pair = instances.types.iter().find(|instance| instance.ty.to_string().starts_with("Pair")).unwrap();
+    let Layout::Structure(fields) = &pair.layout else {
+        panic!("Expected a structure layout, got {:?}", pair.layout);
+    };
+    let fields: Vec<String> = fields.iter().map(|field| field.data_type.to_string()).collect();
+    assert_eq!(fields, vec!["char", "Option<ref[char]>"]);
+}
+
+#[test]
+fn monomorphization_errors() {
+    assert_eq!(instantiate("fun helper() {}").unwrap_err(), vec!["E0033"]);
+    assert_eq!(instantiate("fun main<T>() {}").unwrap_err(), vec!["E0034"]);
+
+    // functions of impl blocks and traits are not entry points
+    assert_eq!(instantiate("struct S {} impl S { fun main(): i32 => 0 }").unwrap_err(), vec!["E0033"]);
+    assert_eq!(instantiate("trait T { fun main() {} }").unwrap_err(), vec!["E0033"]);
+    assert!(instantiate("struct S {} impl S { fun main(): i32 => 0 } fun main() {}").is_ok());
+
+    // every module can declare a `main`, but only one of them starts the program
+    let mut sources = SourceMap::new();
+    let modules = [
+        parse_from(&mut sources, "first", "fun main() {}"),
+        parse_from(&mut sources, "second", "fun main() {}"),
+    ];
+    let mut module = Module::new();
+    module.process_modules(&sources, &modules).expect("Valid modules");
+    assert!(matches!(Monomorphizer::run(&module), Err(CompilerError::DuplicateMain { .. })));
+
+    // a recursive type is fine as long as its type arguments stay the same
+    assert!(instantiate("struct Node<T> { value: T next: ref[Node<T>] } fun main() { let node: Node<i32> }").is_ok());
+    assert!(instantiate("fun count<T>(value: T): i32 => count(value) fun main() { let value = count(1) }").is_ok());
+
+    let nested = r#"
+        struct Wrap<T> { value: T }
+        fun nest<T>(value: T): i32 => nest(make Wrap { value: value })
+        fun main() { let value = nest(1) }
+    "#;
+    assert_eq!(instantiate(nested).unwrap_err(), vec!["E0032"]);
+    assert_eq!(
+        instantiate("struct Tree<T> { children: ref[Tree<[T]>] } fun main() { let tree: Tree<i32> }").unwrap_err(),
+        vec!["E0032"],
+    );
+}
+
+#[test]
+fn traits() {
+    let instances = instantiate(r#"
+        trait Describe {
+            fun id(self): i32
+            fun twice(self): i32 => Self::id(self) * 2
+        }
+
+        trait Index<T> {
+            fun index(self, at: usize): T
+        }
+
+        struct Counter {}
+        struct Vec<T> { first: T }
+
+        impl Describe for Counter {
+            fun id(self): i32 => 1
+        }
+
+        impl<T> Describe for Vec<T> {
+            fun id(self): i32 => 2
+            fun twice(self): i32 => 4
+        }
+
+        impl<T> Index<T> for Vec<T> {
+            fun index(self, at: usize): T => Index::index(self, at)
+        }
+
+        fun main() {
+            let counter = make Counter {}
+            let numbers = make Vec { first: 1 }
+            let a = Describe::twice(counter)
+            let b = Describe::twice(numbers)
+            let c = Index::index(numbers, 0)
+            let d = Counter::id(counter)
+        }
+    "#).expect("Valid program");
+
+    let mut functions: Vec<String> = instances.functions
+        .iter()
+        .map(|instance| {
+            let arguments: Vec<String> = instance.type_arguments.iter().map(DataType::to_string).collect();
+            format!("{}.{}<{}>", instance.function.prototype.name.0.chain.last().unwrap(), instance.function.prototype.name.1, arguments.join(", "))
+        })
+        .collect();
+    functions.sort();
+
+    // the provided `twice` is only used by `Counter`, `Vec` implements its own
+    assert_eq!(functions, vec![
+        "Counter.id<>",
+        "Describe.twice<Counter>",
+        "Vec.index<i32>",
+        "Vec.twice<i32>",
+        "main.main<>",
+    ]);
+}
+
+#[test]
+fn trait_errors() {
+    assert_eq!(error_codes("struct A {} impl Missing for A {}"), vec!["E0035"]);
+    assert_eq!(error_codes("trait T {} struct A {} impl T for A { fun f() {} }"), vec!["E0036"]);
+    assert_eq!(error_codes("trait T { fun f(self): i32 } struct A {} impl T for A { fun f(self): bool => true }"), vec!["E0037"]);
+    assert_eq!(error_codes("trait T { fun f(self) fun g(self) { Self::f(self) } } struct A {} impl T for A {}"), vec!["E0038"]);
+    assert_eq!(error_codes("trait T<X> {} struct A {} impl T for A {}"), vec!["E0028"]);
+    assert_eq!(error_codes("trait T {} struct A<X> {} impl<X, Y> T for A<X> {}"), vec!["E0031"]);
+    assert_eq!(error_codes("trait T { fun f(self) fun f(self, x: i32) } struct A {}"), vec!["E0003"]);
+    assert_eq!(error_codes("trait T {} struct T {}"), vec!["E0019"]);
+
+    // implementations can not overlap, even when they are generic
+    assert_eq!(error_codes("trait T {} struct A {} impl T for A {} impl T for A {}"), vec!["E0040"]);
+    assert_eq!(error_codes("trait T {} struct A<X> {} impl T for A<i32> {} impl<X> T for A<X> {}"), vec!["E0040"]);
+    assert!(validate("trait T {} struct A<X> {} impl T for A<i32> {} impl T for A<bool> {}").is_ok());
+
+    // trait methods can only be called on types implementing the trait
+    assert_eq!(error_codes("trait T { fun f(self) } struct A {} fun main() { T::f(make A {}) }"), vec!["E0041"]);
+    assert_eq!(error_codes("trait T { fun f(self) } fun g<X>(value: X) { T::f(value) }"), vec!["E0041"]);
+}
+
+#[test]
+fn trait_coherence() {
+    let modules = |sources: &mut SourceMap, uses: &str| [
+        parse_from(sources, "ops", "pub trait Drop { fun drop(self) }"),
+        parse_from(sources, "boxed", r#"
+            import ops::Drop
+            pub struct Box {}
+            impl Drop for Box { fun drop(self) {} }
+        "#),
+        parse_from(sources, "main", uses),
+    ];
+
+    let mut sources = SourceMap::new();
+    let valid = modules(&mut sources, r#"
+        import ops::Drop
+        import boxed::Box
+        fun main(value: Box) { Drop::drop(value) }
+    "#);
+    assert!(Module::new().process_modules(&sources, &valid).is_ok());
+
+    // the impl block of `boxed` already implements the trait, and `main` declares neither
+    let mut sources = SourceMap::new();
+    let orphan = modules(&mut sources, "import ops::Drop import boxed::Box impl Drop for Box { fun drop(self) {} }");
+    let errors = Module::new().process_modules(&sources, &orphan).unwrap_err();
+    assert_eq!(errors.iter().map(CompilerError::code).collect::<Vec<_>>(), vec!["E0039"]);
+
+    // both the module of the trait and the module of the type may implement it, but only once
+    let mut sources = SourceMap::new();
+    let overlapping = [
+        parse_from(&mut sources, "ops", r#"
+            import boxed::Box
+            pub trait Drop { fun drop(self) }
+            impl Drop for Box { fun drop(self) {} }
+        "#),
+        parse_from(&mut sources, "boxed", "import ops::Drop pub struct Box {} impl Drop for Box { fun drop(self) {} }"),
+    ];
+    let errors = Module::new().process_modules(&sources, &overlapping).unwrap_err();
+    assert_eq!(errors.iter().map(CompilerError::code).collect::<Vec<_>>(), vec!["E0040"]);
+
+    let mut sources = SourceMap::new();
+    let conflict = modules(&mut sources, "import ops::Drop import boxed::Box struct Drop {}");
+    let errors = Module::new().process_modules(&sources, &conflict).unwrap_err();
+    assert_eq!(errors.iter().map(CompilerError::code).collect::<Vec<_>>(), vec!["E0042"]);
 }
 
+#[test]
+fn postfix_accesses() {
+    let instances = instantiate(r#"
+        struct Point { x: i32, y: i32 }
+        struct Pair<T> { first: T, second: T }
+        struct Line { start: Point, end: Point }
+
+        trait Length {
+            fun length(self): i32
+            fun doubled(self): i32 => self.length() * 2
+        }
+
+        impl Point {
+            fun sum(self): i32 => self.x + self.y
+            fun scale(ref[self], by: i32) {
+                self.x = self.x * by
+            }
+        }
+
+        impl Length for Line {
+            fun length(self): i32 => self.end.sum() - self.start.sum()
+        }
+
+        fun first(values: [i32], pair: ref[Pair<bool>]): bool {
+            let a: i32 = values[0]
+            pair.second
+        }
+
+        fun main() {
+            let mut point = make Point { x: 1, y: 2 }
+            let pair = make Pair { first: 1, second: 2 }
+            let line = make Line { start: point, end: point }
+
+            point.scale(2)
+            point.y = pair.first + line.start.x
+            let total = line.doubled()
+        }
+    "#).expect("Valid program");
+
+    let mut functions: Vec<String> = instances.functions
+        .iter()
+        .map(|instance| format!("{}.{}", instance.function.prototype.name.0.chain.last().unwrap(), instance.function.prototype.name.1))
+        .collect();
+    functions.sort();
+
+    // `doubled` is provided by the trait and reached through a method call on `Line`
+    assert_eq!(functions, vec![
+        "Length.doubled",
+        "Line.length",
+        "Point.scale",
+        "Point.sum",
+        "main.main",
+    ]);
+}
+
+#[test]
+fn postfix_errors() {
+    assert_eq!(error_codes("struct A { x: i32 } fun f(a: A): i32 => a.y"), vec!["E0023"]);
+    assert_eq!(error_codes("fun f(a: i32): i32 => a.x"), vec!["E0043"]);
+    assert_eq!(error_codes("fun f(a: i32): i32 => a[0]"), vec!["E0044"]);
+    assert_eq!(error_codes("fun f(a: [i32]): i32 => a[true]"), vec!["E0007"]);
+    assert_eq!(error_codes("struct A {} fun f(a: A) => a.missing()"), vec!["E0045"]);
+    assert_eq!(error_codes("struct A {} impl A { fun g(self, x: i32) {} } fun f(a: A) => a.g()"), vec!["E0011"]);
+
+    // static functions are not methods
+    assert_eq!(error_codes("struct A {} impl A { fun g(x: A) {} } fun f(a: A) => a.g()"), vec!["E0045"]);
+
+    // fields of immutable variables can not be changed, unless they are behind a reference
+    assert_eq!(error_codes("struct A { x: i32 } fun f(a: A) { a.x = 1 }"), vec!["E0013"]);
+    assert!(validate("struct A { x: i32 } fun f(a: ref[A], values: [i32]) { a.x = 1 values[0] = 2 }").is_ok());
+}
+
+#[test]
+fn for_loops() {
+    let mut sources = SourceMap::new();
+    let modules = [
+        parse_path(&mut sources, "main.gs", r#"
+            import std::iter::*
+
+            struct Countdown { left: u64 }
+
+            impl Iterator<u64> for Countdown {
+                fun has_next(ref[self]): bool => self.left > 0
+                fun next(ref[self]): u64 {
+                    self.left -= 1
+                    self.left
+                }
+            }
+
+            fun sum(n: usize): usize {
+                let mut total: usize = 0
+                for i in 0..n {
+                    for j in 1..=i {
+                        total += j
+                    }
+                }
+                total
+            }
+
+            fun main() {
+                let mut last: u64 = 0
+                for value in make Countdown { left: 3 } {
+                    last = value
+                }
+                let numbers: Range<u32> = 0..10
+                for value in numbers {
+                    last += value as u64
+                }
+                let total = sum(10)
+            }
+        "#),
+        parse_path(&mut sources, "std/iter.gs", include_str!("../../std/iter.gs")),
+    ];
+
+    let mut module = Module::new();
+    module.process_modules(&sources, &modules).expect("Valid program");
+    let instances = Monomorphizer::run(&module).expect("Instantiable program");
+
+    let mut functions: Vec<String> = instances.functions
+        .iter()
+        .map(|instance| format!("{}.{}", instance.function.prototype.name.0.chain.last().unwrap(), instance.function.prototype.name.1))
+        .collect();
+    functions.sort();
+    assert_eq!(functions, vec![
+        "Countdown.has_next",
+        "Countdown.next",
+        "Range.has_next",
+        "Range.next",
+        "main.main",
+        "main.sum",
+    ]);
+
+    // the bounds of a range agree on their type, the loop variable can not be assigned to
+    assert_eq!(error_codes("fun f(n: usize, m: i64) { for i in n..m {} }"), vec!["E0007"]);
+    assert_eq!(error_codes("fun f(n: usize) { for i in 0..n { i = 2 } }"), vec!["E0013"]);
+    assert_eq!(error_codes("fun f() { for i in 0.0..1.0 {} }"), vec!["E0046"]);
+    assert_eq!(error_codes("struct A {} fun f(a: A) { for i in a {} }"), vec!["E0046"]);
+
+    // having the methods is not enough, the type has to implement the trait
+    let methods = "struct A {} impl A { fun has_next(ref[self]): bool => false fun next(ref[self]): i32 => 0 }";
+    assert_eq!(error_codes(&format!("{methods} fun f(a: A) {{ for i in a {{}} }}")), vec!["E0046"]);
+
+    // ranges are only values when the standard library declares their type
+    assert_eq!(error_codes("fun f(n: i32) { let range = 0..n }"), vec!["E0061"]);
+}
+
+#[test]
+fn index_trait() {
+    let mut sources = SourceMap::new();
+    let modules = [
+        parse_path(&mut sources, "main.gs", r#"
+            import std::ops::Index
+
+            struct Digits {}
+
+            impl Index<i32> for Digits {
+                fun index(ref[self], at: usize): i32 => at as i32
+            }
+
+            fun main(digits: ref[Digits]): i32 {
+                let values: Digits = make Digits {}
+                values[1] + digits[2]
+            }
+        "#),
+        parse_path(&mut sources, "std/ops.gs", include_str!("../../std/ops.gs")),
+    ];
+
+    let mut module = Module::new();
+    module.process_modules(&sources, &modules).expect("Valid program");
+    let instances = Monomorphizer::run(&module).expect("Instantiable program");
+    assert!(instances.functions.iter().any(|instance| instance.function.prototype.name.1 == "index".into()));
+
+    // the trait makes values readable by index, they can not be assigned to
+    let mut sources = SourceMap::new();
+    let modules = [
+        parse_path(&mut sources, "main.gs", r#"
+            import std::ops::Index
+            struct Digits {}
+            impl Index<i32> for Digits { fun index(ref[self], at: usize): i32 => at as i32 }
+            fun assign(mut digits: Digits) { digits[0] = 1 }
+            fun select(digits: Digits): i32 => digits[true]
+        "#),
+        parse_path(&mut sources, "std/ops.gs", include_str!("../../std/ops.gs")),
+    ];
+
+    let errors = Module::new().process_modules(&sources, &modules).expect_err("Invalid indexing");
+    assert_eq!(errors.iter().map(CompilerError::code).collect::<Vec<_>>(), vec!["E0044", "E0007"]);
+}
+
+#[test]
+fn if_else() {
+    assert!(validate(r#"
+        fun sign(n: i32): i32 {
+            if n > 0 { 1 } else if n == 0 { 0 } else { -1 }
+        }
+
+        fun clamp(n: u64, max: u64): u64 {
+            let value = if n > max { max } else { n }
+            value
+        }
+
+        fun checked(n: usize): usize => unless n == 0 { n } else { return 0 }
+
+        fun main() {
+            let mut count = 0
+            if count > 1 { count = 2 } else unless count == 0 { count = 1 }
+        }
+    "#).is_ok());
+
+    assert_eq!(error_codes("fun f(c: bool): i32 => if c { 1 } else { true }"), vec!["E0047"]);
+    assert_eq!(error_codes("fun f(c: bool): i32 => if c { 1 } else if c { 2 }"), vec!["E0047"]);
+    assert_eq!(error_codes("fun f(c: i32): i32 => if c { 1 } else { 2 }"), vec!["E0007"]);
+    assert_eq!(error_codes("fun f(c: bool): bool => if c { 1 } else { 2 }"), vec!["E0007"]);
+}
+
+#[test]
+fn match_expressions() {
+    let module = validate(r#"
+        enum Option<T> { Some(T), None }
+        enum Shape { Rect { width: i32, height: i32 }, Circle(f32), Empty }
+        struct Point { x: i32, y: i32 }
+
+        impl<T> Option<T> {
+            fun is_some(ref[self]): bool => match self {
+                Some(_) => true
+                None => false
+            }
+
+            fun unwrap(self): T => match self {
+                Some(v) => v
+                None => crash!("Unwrapped none option")
+            }
+
+            fun or_default(self, default: T): T => match self {
+                Option::Some(v) => v
+                Option::None => default
+            }
+        }
+
+        fun area(shape: Shape): i32 => match shape {
+            Shape::Rect { width: 0, .. } | Shape::Empty => 0
+            Shape::Rect { width, height } if width > 0 => width * height
+            Shape::Circle(_) => 1
+            _ => -1
+        }
+
+        fun classify(value: i32, letter: char): u64 => match value {
+            ..=-11 | -10..0 => 2
+            0 => 0
+            1 | 2 | 3 => 1
+            n if n > 100 && letter == 'a' => 3
+            _ => match letter { 'a'..='z' => 4 _ => 5 }
+        }
+
+        fun origin(point: Point): bool => match point {
+            Point { x: 0, y: 0 } => true
+            Point { x, .. } | Point { y: x, .. } => x == 0
+        }
+    "#).expect("Valid program");
+
+    let unwrap = module.functions().iter().find(|function| function.prototype.name.1 == "unwrap".into()).unwrap();
+    let Expression::Match { scrutinee, arms } = &unwrap.body else {
+        panic!("Expected a match expression, got {:?}", unwrap.body);
+    };
+    assert!(matches!(**scrutinee, Expression::LocalVariableReference(_)));
+    assert!(matches!(arms.as_slice(), [
+        MatchArm { pattern: Pattern::Variant { fields, .. }, guard: None, .. },
+        MatchArm { pattern: Pattern::Variant { .. }, guard: None, .. },
+    ] if matches!(fields.as_slice(), [Pattern::Binding(_)])));
+
+    // a reference is matched through to the value behind it
+    let is_some = module.functions().iter().find(|function| function.prototype.name.1 == "is_some".into()).unwrap();
+    assert!(matches!(&is_some.body, Expression::Match { scrutinee, .. }
+        if matches!(**scrutinee, Expression::Unary { op: UnaryOperator::Deref, .. })));
+}
+
+#[test]
+fn match_errors() {
+    let option = "enum Option<T> { Some(T), None } enum Shape { Rect { width: i32, height: i32 } }";
+    let codes = |body: &str| error_codes(&format!("{option} {body}"));
+
+    assert_eq!(codes("fun f(o: Option<i32>): i32 => match o { Some(v) => v None => true }"), vec!["E0053"]);
+    assert_eq!(codes("fun f(o: Option<i32>): bool => match o { Some(true) => true _ => false }"), vec!["E0007"]);
+    assert_eq!(codes("fun f(o: Option<i32>): bool => match o { Some(v) if v => true _ => false }"), vec!["E0007"]);
+    assert_eq!(codes("fun f(o: Option<i32>): bool => match o { Other(v) => true _ => false }"), vec!["E0027"]);
+    assert_eq!(codes("fun f(o: Option<i32>): bool => match o { Shape::Rect { .. } => true }"), vec!["E0052"]);
+    assert_eq!(codes("fun f(o: i32): bool => match o { Some(v) => true }"), vec!["E0052"]);
+    assert_eq!(codes("fun f(o: bool): bool => match o { false..true => true }"), vec!["E0052"]);
+    assert_eq!(codes("fun f(o: Option<i32>): bool => match o { Some => true _ => false }"), vec!["E0056"]);
+    assert_eq!(codes("fun f(o: Option<i32>): bool => match o { Some(a, b) => true _ => false }"), vec!["E0056"]);
+    assert_eq!(codes("fun f(s: Shape): i32 => match s { Shape::Rect { width } => width }"), vec!["E0057"]);
+    assert_eq!(codes("fun f(s: Shape): i32 => match s { Shape::Rect { depth, .. } => depth }"), vec!["E0023"]);
+    assert_eq!(codes("fun f(s: Shape): i32 => match s { Shape::Rect { width: a, height: a } => a }"), vec!["E0055"]);
+    assert_eq!(codes("fun f(o: Option<i32>): i32 => match o { Some(v) | None => v }"), vec!["E0054"]);
+    assert_eq!(codes("fun f(o: Option<Option<i32>>): i32 => match o { Some(Some(v)) | Some(v) => 0 _ => 1 }"), vec!["E0007"]);
+    assert_eq!(codes("fun f(o: ref[char]): i32 => match o { \"a\" => 1 _ => 0 }"), vec!["E0018"]);
+
+    // every arm is checked even if an earlier one is invalid
+    assert_eq!(codes("fun f(o: Option<i32>): i32 => match o { Some(v) => w None => x }"), vec!["E0005", "E0005"]);
+    assert_eq!(codes("const A: i32 = match 1 { _ => 1 }"), vec!["E0049"]);
+}
+
+#[test]
+fn builtin_macros() {
+    let instances = instantiate(r#"
+        struct Counter { count: i32 }
+
+        fun checked(value: i32): i32 {
+            assert!(value >= 0, "{} is negative", value)
+            let half = if value % 2 == 0 { value / 2 } else { crash!("{value} is odd") }
+            half
+        }
+
+        fun unreachable(): Counter => crash!()
+
+        fun main() {
+            let name = format!("count {}", checked(4))
+            print!("{}: ", name)
+            println!()
+            println!("{} and {}", 1, true)
+        }
+    "#).expect("Valid program");
+
+    // the built-in functions have no body to instantiate
+    let names: Vec<&str> = instances.functions.iter().map(|instance| instance.function.prototype.name.1.as_str()).collect();
+    assert_eq!(names, vec!["main", "checked"]);
+
+    assert_eq!(error_codes("struct A {} fun f(a: A) => println!(\"{}\", a)"), vec!["E0016"]);
+    assert_eq!(error_codes("fun f(): i32 => print!(\"a\")"), vec!["E0007"]);
+    assert_eq!(error_codes("fun f() => assert!(1)"), vec!["E0009"]);
+}
+
+#[test]
+fn global_variables() {
+    let mut sources = SourceMap::new();
+    let modules = [
+        parse_from(&mut sources, "main", r#"
+            let mut counter = 0
+            let limit = config::LIMIT * 2
+
+            fun main() {
+                counter += 1
+                let total: i32 = counter + limit
+            }
+        "#),
+        parse_from(&mut sources, "config", r#"
+            pub const LIMIT: i32 = BASE + 1
+            const BASE = 4
+        "#),
+    ];
+
+    let mut module = Module::new();
+    module.process_modules(&sources, &modules).expect("Valid program");
+
+    // every global is initialized after the globals it refers to
+    let names: Vec<&str> = module.globals().iter().map(|global| global.variable.name.1.as_str()).collect();
+    assert_eq!(names, vec!["counter", "BASE", "LIMIT", "limit"]);
+
+    let limit = module.globals().iter().find(|global| global.variable.name.1 == "limit".into()).unwrap();
+    assert_eq!(limit.data_type(), &DataType::Primitive(PrimitiveType::I32));
+}
+
+#[test]
+fn global_errors() {
+    assert_eq!(error_codes("let a = b + 1 let b = a"), vec!["E0050"]);
+    assert_eq!(error_codes("let a: i32 = b let b: i32 = a"), vec!["E0050"]);
+    assert_eq!(error_codes("const SELF = SELF"), vec!["E0050"]);
+    assert_eq!(error_codes("let a = 1 let a = 2"), vec!["E0048"]);
+    assert_eq!(error_codes("const FLAG: bool = 1"), vec!["E0007"]);
+    assert_eq!(error_codes("fun f(): i32 => 1 const X = f()"), vec!["E0049"]);
+    assert_eq!(error_codes("let y = 1 const X = y + 1"), vec!["E0049"]);
+    assert_eq!(error_codes("const X = if true { 1 } else { 2 }"), vec!["E0049"]);
+    assert_eq!(error_codes("const X = 1 fun f() { X = 2 }"), vec!["E0013"]);
+    assert_eq!(error_codes("let x = 1 fun f() { x = 2 }"), vec!["E0013"]);
+    assert_eq!(error_codes("fun f(): i32 => missing::X"), vec!["E0005"]);
+
+    // constants can construct values and use other constants
+    assert!(validate(r#"
+        enum Option<T> { Some(T) None }
+        struct Range { start: i32 end: i32 }
+
+        const START = 2
+        const RANGE = make Range { start: START, end: START * 4 }
+        const FIRST: Option<i32> = Option::Some(-START)
+        let mut cursor = RANGE.start
+    "#).is_ok());
+}
+
+#[test]
+fn global_instances() {
+    let instances = instantiate(r#"
+        fun id<T>(value: T): T => value
+
+        let unused = id(true)
+        let start = id(3)
+        let next = start + 1
+
+        fun main() {
+            let value = next
+        }
+    "#).expect("Valid program");
+
+    // only globals the program uses are instantiated, after the globals they refer to
+    let globals: Vec<&str> = instances.globals.iter().map(|global| global.variable.name.1.as_str()).collect();
+    assert_eq!(globals, vec!["start", "next"]);
+
+    let functions: Vec<String> = instances.functions
+        .iter()
+        .map(|instance| format!("{}{:?}", instance.function.prototype.name.1, instance.type_arguments))
+        .collect();
+    assert_eq!(functions, vec!["main[]", "id[Primitive(I32)]"]);
+}
+
+#[test]
+fn import_resolution() {
+    let mut sources = SourceMap::new();
+    let modules = [
+        parse_from(&mut sources, "main", r#"
+            import shapes::*
+            import units::*
+            import math as m
+            import math::{square as sq, ORIGIN}
+            import builtin::*
+
+            // declared items shadow glob imports
+            fun area(): f32 => 0.0
+
+            fun main() {
+                let circle = make Circle { radius: m::square(2.0) }
+                let scaled: f32 = sq(circle.radius) + ORIGIN + area()
+                let length = Unit::Meter
+                print("done")
+            }
+        "#),
+        parse_from(&mut sources, "shapes", r#"
+            pub struct Circle { radius: f32 }
+            pub fun area(circle: Circle): f32 => circle.radius
+        "#),
+        parse_from(&mut sources, "units", "pub enum Unit { Meter Foot }"),
+        parse_from(&mut sources, "math", "pub fun square(x: f32): f32 => x * x pub const ORIGIN: f32 = 0.0"),
+    ];
+
+    let mut module = Module::new();
+    module.process_modules(&sources, &modules).expect("Valid program");
+}
+
+#[test]
+fn directory_modules() {
+    let mut sources = SourceMap::new();
+    let modules = [
+        parse_path(&mut sources, "main.gs", r#"
+            import std::collections::list::List
+            import std::ops::*
+
+            fun main(): i32 {
+                let list = make List { length: 0 }
+                Drop::drop(list)
+                std::version()
+            }
+        "#),
+        parse_path(&mut sources, "std/collections/list.gs", r#"
+            import std::ops::*
+
+            pub struct List { length: i32 }
+            impl Drop for List {
+                fun drop(self) {}
+            }
+        "#),
+        parse_path(&mut sources, "./std/ops.gs", "pub trait Drop { fun drop(self) }"),
+
+        // the module of a directory can have a file of its own
+        parse_path(&mut sources, "std.gs", "pub fun version(): i32 => 1"),
+    ];
+
+    let mut module = Module::new();
+    module.process_modules(&sources, &modules).expect("Valid program");
+
+    let std = Namespace::global().module("std".into());
+    assert!(module.registry().get_module(&std.module("ops".into())).is_some());
+    assert!(module.registry().get_module(&std.module("collections".into()).module("list".into())).is_some());
+
+    // module paths start at the root, files outside of it are top level modules
+    let module = |path: &str, root: &str| SourceFile::from_path(Path::new(path), Path::new(root), String::new()).module();
+    assert_eq!(module("../lib/math.gs", "."), Namespace { chain: vec!["math".into()] });
+    assert_eq!(module("../lib/math.gs", ".."), Namespace { chain: vec!["lib".into(), "math".into()] });
+    assert_eq!(module("../std/ops.gs", "../"), module("std/ops.gs", "."));
+    assert_eq!(module("std/../std/ops.gs", "std/.."), Namespace { chain: vec!["std".into(), "ops".into()] });
+
+    let mut sources = SourceMap::new();
+    let modules = [
+        parse_path(&mut sources, "std/ops.gs", "fun a() {}"),
+        parse_path(&mut sources, "./std/ops.gs", "fun b() {}"),
+    ];
+    match Module::new().process_modules(&sources, &modules).as_ref().map_err(Vec::as_slice) {
+        Err([CompilerError::DuplicateNamespace(namespace)]) => assert_eq!(namespace, &std.module("ops".into())),
+        other => panic!("Expected duplicate namespace error, got {other:?}"),
+    }
+}
+
+#[test]
+fn standard_library() {
+    let mut sources = SourceMap::new();
+    let modules = [
+        parse_path(&mut sources, "std/ops.gs", include_str!("../../std/ops.gs")),
+        parse_path(&mut sources, "std/box.gs", include_str!("../../std/box.gs")),
+        parse_path(&mut sources, "std/iter.gs", include_str!("../../std/iter.gs")),
+        parse_path(&mut sources, "std/num.gs", include_str!("../../std/num.gs")),
+        parse_path(&mut sources, "std/option.gs", include_str!("../../std/option.gs")),
+        parse_path(&mut sources, "std/result.gs", include_str!("../../std/result.gs")),
+    ];
+
+    Module::new().process_modules(&sources, &modules).expect("Valid standard library");
+}
+
+#[test]
+fn gs_library() {
+    let mut sources = SourceMap::new();
+    let modules = [
+        parse_path(&mut sources, "main.gs", r#"
+            import gs::math
+            import gs::mem
+            import std::box::Box
+
+            fun main(argc: usize, argv: ref[ref[char]], squares: ref[[f32]]): f32 {
+                let args = argv.to_array(argc)
+                let count = usize::parse(args[0])
+                let values: Box<[f32]> = Box::of(squares)
+                if count.is_err() {
+                    return math::sqrt(values[1])
+                }
+                count.unwrap() as f32
+            }
+        "#),
+        parse_path(&mut sources, "gs/math.gs", include_str!("../../gs/math.gs")),
+        parse_path(&mut sources, "gs/mem.gs", include_str!("../../gs/mem.gs")),
+        parse_path(&mut sources, "std/box.gs", include_str!("../../std/box.gs")),
+        parse_path(&mut sources, "std/num.gs", include_str!("../../std/num.gs")),
+        parse_path(&mut sources, "std/ops.gs", include_str!("../../std/ops.gs")),
+        parse_path(&mut sources, "std/option.gs", include_str!("../../std/option.gs")),
+        parse_path(&mut sources, "std/result.gs", include_str!("../../std/result.gs")),
+    ];
+
+    let mut module = Module::new();
+    module.process_modules(&sources, &modules).expect("Valid program");
+    let instances = Monomorphizer::run(&module).expect("Instantiable program");
+
+    let mut functions: Vec<String> = instances.functions
+        .iter()
+        .map(|instance| {
+            let arguments: Vec<String> = instance.type_arguments.iter().map(DataType::to_string).collect();
+            format!("{}<{}>", instance.function.prototype.name, arguments.join(", "))
+        })
+        .collect();
+    functions.sort();
+    // `to_array` is called on the reference to the arguments, not on the first argument
+    assert_eq!(functions, vec![
+        "main.gs.math:sqrt<>",
+        "main.main:main<>",
+        "main.ref:to_array<ref[char]>",
+        "main.std.box.Box:index<f32>",
+        "main.std.box.Box:of<[f32]>",
+        "main.std.num:char_at<>",
+        "main.std.result.Result:is_err<usize, ref[char]>",
+        "main.std.result.Result:unwrap<usize, ref[char]>",
+        "main.usize:parse<>",
+    ]);
+}
+
+#[test]
+fn standard_library_instances() {
+    let mut sources = SourceMap::new();
+    let modules = [
+        parse_path(&mut sources, "main.gs", r#"
+            import std::option::*
+            import std::result::*
+
+            fun parse(digit: char): Result<i32, ref[char]> {
+                if digit < '0' || digit > '9' {
+                    return Result::Err("not a digit")
+                }
+                Result::Ok(digit as i32 - '0' as i32)
+            }
+
+            fun main(): i32 {
+                let value: Option<i32> = Option::Some(4)
+                let parsed = parse('2')
+                if parsed.err() || !value.is_some() {
+                    return 1
+                }
+                value.unwrap() + parsed.unwrap()
+            }
+        "#),
+        parse_path(&mut sources, "std/option.gs", include_str!("../../std/option.gs")),
+        parse_path(&mut sources, "std/result.gs", include_str!("../../std/result.gs")),
+    ];
+
+    let mut module = Module::new();
+    module.process_modules(&sources, &modules).expect("Valid program");
+    let instances = Monomorphizer::run(&module).expect("Instantiable program");
+
+    let mut functions: Vec<String> = instances.functions
+        .iter()
+        .map(|instance| {
+            let arguments: Vec<String> = instance.type_arguments.iter().map(DataType::to_string).collect();
+            format!("{}<{}>", instance.function.prototype.name, arguments.join(", "))
+        })
+        .collect();
+    functions.sort();
+    assert_eq!(functions, vec![
+        "main.main:main<>",
+        "main.main:parse<>",
+        "main.std.option.Option:is_some<i32>",
+        "main.std.option.Option:unwrap<i32>",
+        "main.std.result.Result:err<i32, ref[char]>",
+        "main.std.result.Result:unwrap<i32, ref[char]>",
+    ]);
+
+    let mut types: Vec<String> = instances.types.iter().map(|instance| instance.ty.to_string()).collect();
+    types.sort();
+    assert_eq!(types, vec!["Option<i32>", "Result<i32, ref[char]>"]);
+}
+
+#[test]
+fn examples() {
+    let programs = [
+        vec![
+            ("examples/main.gosling", include_str!("../../examples/main.gosling")),
+            ("gs/math.gs", include_str!("../../gs/math.gs")),
+            ("gs/mem.gs", include_str!("../../gs/mem.gs")),
+            ("std/num.gs", include_str!("../../std/num.gs")),
+            ("std/result.gs", include_str!("../../std/result.gs")),
+        ],
+        vec![("examples/functions.gosling", include_str!("../../examples/functions.gosling"))],
+        vec![
+            ("examples/vector.gosling", include_str!("../../examples/vector.gosling")),
+            ("std/box.gs", include_str!("../../std/box.gs")),
+            ("std/ops.gs", include_str!("../../std/ops.gs")),
+            ("std/option.gs", include_str!("../../std/option.gs")),
+        ],
+    ];
+
+    for files in programs {
+        let mut sources = SourceMap::new();
+        let modules: Vec<_> = files.iter().map(|(path, source)| parse_path(&mut sources, path, source)).collect();
+
+        let result = Module::new().process_modules(&sources, &modules);
+        assert!(result.is_ok(), "{}: {result:?}", files[0].0);
+    }
+}
+
+#[test]
+fn import_errors() {
+    let codes = |main: &str| {
+        let mut sources = SourceMap::new();
+        let modules = [
+            parse_from(&mut sources, "main", main),
+            parse_from(&mut sources, "a", "pub struct Shared {} pub fun f() {} pub fun only_a() {}"),
+            parse_from(&mut sources, "b", "pub struct Shared {} pub fun f() {}"),
+        ];
+
+        match Module::new().process_modules(&sources, &modules) {
+            Ok(()) => vec![],
+            Err(errors) => errors.iter().map(CompilerError::code).collect(),
+        }
+    };
+
+    // names two glob imports bring in are only an error once they are used
+    assert_eq!(codes("import a::* import b::* fun g() => only_a()"), Vec::<&str>::new());
+    assert_eq!(codes("import a::* import b::* fun g() => f()"), vec!["E0051"]);
+    assert_eq!(codes("import a::* import b::* fun g(s: Shared) {}"), vec!["E0051"]);
+    assert_eq!(codes("import a::* import b::* fun g() => Shared::new()"), vec!["E0051"]);
+
+    // explicit imports and declarations shadow glob imports
+    assert_eq!(codes("import a::* import b::* import b::f fun g() => f()"), Vec::<&str>::new());
+    assert_eq!(codes("import a::* import b::* struct Shared {} fun g(s: Shared) {}"), Vec::<&str>::new());
+
+    assert_eq!(codes("import a::missing"), vec!["E0001"]);
+    assert_eq!(codes("import missing::*"), vec!["E0001"]);
+    assert_eq!(codes("import a::f import b::f"), vec!["E0042"]);
+    assert_eq!(codes("import a::Shared as S fun g(s: S) {}"), Vec::<&str>::new());
+}
+
+#[test]
+fn private_items() {
+    let codes = |main: &str| {
+        let mut sources = SourceMap::new();
+        let modules = [
+            parse_path(&mut sources, "main.gs", main),
+            parse_path(&mut sources, "math.gs", r#"
+                fun hidden(): i32 => 1
+                pub fun shown(): i32 => hidden()
+                let SECRET: i32 = 2
+                struct Internal {}
+                enum Mode { Fast }
+
+                pub struct Point { x: i32 }
+                impl Point {
+                    fun helper(): i32 => 3
+                    pub fun origin(): Point => make Point { x: Point::helper() }
+                }
+            "#),
+            parse_path(&mut sources, "math/nested.gs", "fun g(): i32 => math::hidden() + math::SECRET"),
+        ];
+
+        match Module::new().process_modules(&sources, &modules) {
+            Ok(()) => vec![],
+            Err(errors) => errors.iter().map(CompilerError::code).collect(),
+        }
+    };
+
+    assert_eq!(codes("import math::shown fun f(): i32 => shown() + math::Point::origin().x"), Vec::<&str>::new());
+
+    assert_eq!(codes("import math::hidden"), vec!["E0060"]);
+    assert_eq!(codes("import math::SECRET"), vec!["E0060"]);
+    assert_eq!(codes("import math::Internal"), vec!["E0060"]);
+    assert_eq!(codes("fun f(): i32 => math::hidden()"), vec!["E0060"]);
+    assert_eq!(codes("fun f(): i32 => math::SECRET"), vec!["E0060"]);
+    assert_eq!(codes("fun f(): i32 => math::Point::helper()"), vec!["E0060"]);
+    assert_eq!(codes("fun f() { let mode = math::Mode::Fast }"), vec!["E0060"]);
+
+    // glob imports only bring the public items into scope
+    assert_eq!(codes("import math::* fun f(): i32 => shown()"), Vec::<&str>::new());
+    assert_eq!(codes("import math::* fun f(): i32 => hidden()"), vec!["E0006"]);
+    assert_eq!(codes("import math::* fun f(s: Internal) {}"), vec!["E0004"]);
+}
+
+#[test]
+fn deterministic_output() {
+    let source = "
+        struct A { x: i32 }
+        struct B { a: A }
+        struct C { b: B, y: i32 }
+        struct D { a: A, c: C }
+        fun f(a: A): i32 { let b = a.x let c = b + 1 c }
+        fun g(): i32 => 1
+    ";
+    let serialize = || serde_json::to_string(&validate(source).expect("Failed to validate")).expect("Failed to serialize");
+
+    let first = serialize();
+    for _ in 0..4 {
+        assert_eq!(serialize(), first);
+    }
+}
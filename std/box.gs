@@ -1,4 +1,6 @@
 import std::ops::Drop
+import std::ops::Index
+import std::option::Option
 
 struct RCInternal<T> {
 	ref_count: usize
@@ -6,10 +8,10 @@ struct RCInternal<T> {
 }
 
 pub struct Rc<T> {
-	ptr: Box<RCInternal<T>>
+	ptr: ref[RCInternal<T>]
 }
 
-impl Rc<T> {
+impl<T> Rc<T> {
 	pub fun of(arg: T): Rc<T> => make Rc<T> {
 		ptr: alloc RCInternal<T> {
 			ref_count: 1 
@@ -18,9 +20,26 @@ impl Rc<T> {
 	}
 }
 
-impl Drop for Rc {
-	fun drop(self) {
-		self.ref_count -= 1;
+impl<T> Drop for Rc<T> {
+	fun drop(ref[self]) {
+		self.ptr.ref_count -= 1
 	}
 }
 
+/// Value on the heap that may not have been given yet
+pub struct Box<T> {
+	value: Option<ref[T]>
+}
+
+impl<T> Box<T> {
+	pub fun new(): Box<T> => make Box<T> { value: Option::None }
+
+	pub fun of(value: ref[T]): Box<T> => make Box<T> { value: Option::Some(value) }
+}
+
+impl<T> Index<T> for Box<[T]> {
+	fun index(ref[self], at: usize): T => match self.value {
+		Option::Some(values) => values[at]
+		Option::None => crash!("Indexed an empty box")
+	}
+}
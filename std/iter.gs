@@ -0,0 +1,83 @@
+/// Values `for` loops can go over, the loop asks for the next value as long as there is one
+pub trait Iterator<T> {
+	fun has_next(ref[self]): bool
+	fun next(ref[self]): T
+}
+
+/// Integers from `start` up to `end`, which a range like `0..n` or `0..=n` is when it is not the
+/// head of a `for` loop. `end` is only part of it for inclusive ranges
+pub struct Range<T> {
+	start: T
+	end: T
+	inclusive: bool
+}
+
+impl Iterator<i32> for Range<i32> {
+	fun has_next(ref[self]): bool => self.start < self.end || self.inclusive && self.start == self.end
+
+	fun next(ref[self]): i32 {
+		let value = self.start
+		if value == self.end {
+			self.inclusive = false
+		} else {
+			self.start += 1
+		}
+		value
+	}
+}
+
+impl Iterator<i64> for Range<i64> {
+	fun has_next(ref[self]): bool => self.start < self.end || self.inclusive && self.start == self.end
+
+	fun next(ref[self]): i64 {
+		let value = self.start
+		if value == self.end {
+			self.inclusive = false
+		} else {
+			self.start += 1
+		}
+		value
+	}
+}
+
+impl Iterator<u32> for Range<u32> {
+	fun has_next(ref[self]): bool => self.start < self.end || self.inclusive && self.start == self.end
+
+	fun next(ref[self]): u32 {
+		let value = self.start
+		if value == self.end {
+			self.inclusive = false
+		} else {
+			self.start += 1
+		}
+		value
+	}
+}
+
+impl Iterator<u64> for Range<u64> {
+	fun has_next(ref[self]): bool => self.start < self.end || self.inclusive && self.start == self.end
+
+	fun next(ref[self]): u64 {
+		let value = self.start
+		if value == self.end {
+			self.inclusive = false
+		} else {
+			self.start += 1
+		}
+		value
+	}
+}
+
+impl Iterator<usize> for Range<usize> {
+	fun has_next(ref[self]): bool => self.start < self.end || self.inclusive && self.start == self.end
+
+	fun next(ref[self]): usize {
+		let value = self.start
+		if value == self.end {
+			self.inclusive = false
+		} else {
+			self.start += 1
+		}
+		value
+	}
+}
@@ -0,0 +1,27 @@
+import std::result::Result
+
+impl usize {
+	/// Reads a text of decimal digits that ends with a `'\0'`
+	pub fun parse(text: ref[char]): Result<usize, ref[char]> {
+		let mut value: usize = 0
+		let mut length: usize = 0
+
+		while char_at(text, length) != '\0' {
+			let digit = char_at(text, length)
+			if digit < '0' || digit > '9' {
+				return Result::Err("not a digit")
+			}
+
+			value = value * 10 + (digit as usize - '0' as usize)
+			length += 1
+		}
+
+		if length == 0 {
+			return Result::Err("no digits")
+		}
+		Result::Ok(value)
+	}
+}
+
+/// Character of a text that has at least `at + 1` of them
+fun char_at(text: ref[char], at: usize): char => builtin::array(text, at + 1)[at]
@@ -0,0 +1,7 @@
+pub trait Drop {
+	fun drop(ref[self])
+}
+
+pub trait Index<T> {
+	fun index(ref[self], at: usize): T
+}
@@ -4,7 +4,7 @@ pub enum Option<T> {
 	None
 }
 
-impl Option<T> {
+impl<T> Option<T> {
 	pub fun is_some(ref[self]): bool =>	match self {
 			Some(_) => true 
 		  None => false
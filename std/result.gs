@@ -1,15 +1,26 @@
- pub enum Result<T, E> {
+pub enum Result<T, E> {
 	Ok(T),
 	Err(E)
 }
 
-impl Result<T, E> {
+impl<T, E> Result<T, E> {
 	pub fun err(ref[self]): bool => match self {
-		 Ok(_) => false
-		 Err(_) => false
+		Ok(_) => false
+		Err(_) => true
 	}
-	
 
-	pub fun unwrap(self) -> T {
+	pub fun is_ok(ref[self]): bool => match self {
+		Ok(_) => true
+		Err(_) => false
+	}
+
+	pub fun is_err(ref[self]): bool => match self {
+		Ok(_) => false
+		Err(_) => true
+	}
+
+	pub fun unwrap(self): T => match self {
+		Ok(v) => v
+		Err(_) => crash!("Unwrapped error result")
 	}
 }